@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use tokio::sync::{OnceCell, watch};
 
 use crate::{
-    runtime::context::Context, services::DownloadService, traits::mod_provider::ModDownloadResult,
+    registry::RegistryError,
+    runtime::context::Context,
+    services::DownloadService,
+    traits::{discovery::UserProfile, mod_provider::ModDownloadResult},
 };
 
 /// API for interacting with Void Mod Manager
@@ -14,6 +17,16 @@ pub trait ProviderApi: Send + Sync {
     fn context(&self) -> Arc<Context>;
     fn set_context(&self, ctx: Arc<Context>);
     async fn queue_download(&self, url: String) -> watch::Receiver<ModDownloadResult>;
+
+    /// Fetches the user profile from the mod provider of the active game
+    async fn get_active_provider_user_profile(&self) -> Result<UserProfile, RegistryError> {
+        let provider = self.context().get_required_provider_for_active_game()?;
+
+        provider
+            .get_user_profile()
+            .await
+            .map_err(|e| RegistryError::ModProviderError(e.to_string()))
+    }
 }
 
 /// The default implementation of ProviderAPI as used in Void Mod Manager
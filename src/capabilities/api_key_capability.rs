@@ -1,18 +1,69 @@
 use std::sync::{Arc, Weak};
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::capabilities::{base::Capability, builder::CapabilityError, form::FormSchema, ids};
+use crate::capabilities::{
+    base::Capability,
+    builder::CapabilityError,
+    form::{FieldError, FormFlow, FormSchema, FormValidationError},
+    ids,
+};
 
 /// What the runtime should do with a successfully provided key.
+///
+/// Variant names are pinned with explicit serde tags, as hosts persist and exchange them.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum KeyAction {
     /// The runtime will store the key for the future.
+    #[serde(rename = "Store")]
     Store,
     /// The runtime will NOT store the key
+    #[serde(rename = "DontStore")]
     DontStore,
+    /// The runtime will store the key until `expires_at` (unix seconds), after which
+    /// `needs_prompt` is asked again with no key.
+    #[serde(rename = "StoreWithExpiry")]
+    StoreWithExpiry { expires_at: i64 },
+    /// The runtime will store the key in the OS keychain (or equivalent) instead of its config.
+    /// Hosts without a secure store should not persist the key at all.
+    #[serde(rename = "StoreSecure")]
+    StoreSecure,
+    /// The runtime will store `value` instead of the submitted key, e.g. a token the provider
+    /// exchanged the key for. The raw input is discarded.
+    #[serde(rename = "StoreTransformed")]
+    StoreTransformed { value: String },
+}
+
+impl KeyAction {
+    /// Whether the runtime should persist anything
+    pub fn should_store(&self) -> bool {
+        !matches!(self, KeyAction::DontStore)
+    }
+
+    /// The value the runtime should persist for the given raw input, if any
+    pub fn stored_value<'a>(&'a self, raw: &'a str) -> Option<&'a str> {
+        match self {
+            KeyAction::DontStore => None,
+            KeyAction::StoreTransformed { value } => Some(value),
+            _ => Some(raw),
+        }
+    }
+}
+
+/// What the runtime should do after the user rejected entering a key.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum RejectAction {
+    /// Don't use the provider until the app restarts.
+    DisableForSession,
+    /// Keep the provider enabled and prompt again on the next launch.
+    #[default]
+    RetryNextLaunch,
+    /// Keep using the provider without a key, e.g. with limited features.
+    ContinueAnonymous,
 }
 
 #[derive(Error, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -26,8 +77,48 @@ pub enum ApiKeyValidationError {
     Invalid,
     #[error("An error occured while working with the provider.")]
     ProviderError,
+    /// The user hasn't finished authorizing yet, see `OAuthPrompt::DeviceCode`
+    #[error("Authorization is still pending")]
+    AuthorizationPending,
     #[error("{0}")]
     Other(String),
+    /// Failures attributed to individual fields of the rendered form
+    #[error("{} field(s) failed validation", .0.len())]
+    FieldErrors(Vec<FieldError>),
+}
+
+impl ApiKeyValidationError {
+    /// Shorthand for a `FieldErrors` with a single field
+    pub fn field(id: impl Into<String>, message: impl Into<String>) -> Self {
+        ApiKeyValidationError::FieldErrors(vec![FieldError {
+            field_id: id.into(),
+            message: message.into(),
+        }])
+    }
+}
+
+impl From<CapabilityError> for ApiKeyValidationError {
+    fn from(value: CapabilityError) -> Self {
+        match value {
+            CapabilityError::ProviderDropped => ApiKeyValidationError::ProviderError,
+            e @ (CapabilityError::Network(_)
+            | CapabilityError::Timeout(_)
+            | CapabilityError::DuplicateCapability(_)) => {
+                ApiKeyValidationError::Other(e.to_string())
+            }
+        }
+    }
+}
+
+/// Lets providers return the output of `FormSchema::validate` unchanged
+impl From<FormValidationError> for ApiKeyValidationError {
+    fn from(value: FormValidationError) -> Self {
+        match value {
+            FormValidationError::Fields(errors) => ApiKeyValidationError::FieldErrors(errors),
+            FormValidationError::SchemaUnavailable(_) => ApiKeyValidationError::ProviderError,
+            FormValidationError::InvalidImport(message) => ApiKeyValidationError::Other(message),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,20 +129,43 @@ pub struct ApiSubmitResponse {
 }
 
 /// Behavior-only trait (no Capability)
+#[async_trait]
 pub trait RequiresApiKey: Send + Sync {
     /// Called when the user submits a key.
     /// Return Err(message) to indicate validation failure.
+    ///
+    /// This is the fallback path for format checks only, the runtime calls `on_provided_async`.
     fn on_provided(&self, values: &[ApiSubmitResponse])
     -> Result<KeyAction, ApiKeyValidationError>;
 
+    /// Called when the user submits a key, override this to check the key against the provider
+    /// (e.g. a `/validate` endpoint) before it gets stored. Network failures should map to
+    /// `ApiKeyValidationError::ProviderError`.
+    ///
+    /// Defaults to the synchronous `on_provided`.
+    async fn on_provided_async(
+        &self,
+        values: &[ApiSubmitResponse],
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        self.on_provided(values)
+    }
+
     /// Called when the user explicitly rejects entering a key (e.g. cancels).
-    fn on_rejected(&self) {}
+    fn on_rejected(&self) -> RejectAction {
+        RejectAction::RetryNextLaunch
+    }
 
     /// Whether the UI should prompt for a key (e.g. missing or invalid).
     fn needs_prompt(&self, existing_key: Option<&str>) -> bool;
 
     /// Returns the form schema used to render the API key collection UI.
     fn render(&self) -> Result<FormSchema, CapabilityError>;
+
+    /// Returns a multi-step flow for the API key collection UI.
+    /// Defaults to a single step wrapping `render()`, override this for onboarding wizards.
+    fn render_flow(&self) -> Result<FormFlow, CapabilityError> {
+        self.render().map(FormFlow::single)
+    }
 }
 
 /// Wrapper giving this behavior a concrete Capability
@@ -94,6 +208,7 @@ impl<T: RequiresApiKey + Send + Sync + 'static> Capability for ApiKeyCapability<
 }
 
 /// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
 impl<T: RequiresApiKey + Send + Sync + 'static> RequiresApiKey for ApiKeyCapability<T> {
     fn on_provided(
         &self,
@@ -104,9 +219,19 @@ impl<T: RequiresApiKey + Send + Sync + 'static> RequiresApiKey for ApiKeyCapabil
             Err(_) => Err(ApiKeyValidationError::ProviderError),
         }
     }
-    fn on_rejected(&self) {
-        if let Ok(p) = self.inner() {
-            p.on_rejected();
+    async fn on_provided_async(
+        &self,
+        values: &[ApiSubmitResponse],
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        match self.inner() {
+            Ok(p) => p.on_provided_async(values).await,
+            Err(_) => Err(ApiKeyValidationError::ProviderError),
+        }
+    }
+    fn on_rejected(&self) -> RejectAction {
+        match self.inner() {
+            Ok(p) => p.on_rejected(),
+            Err(_) => RejectAction::RetryNextLaunch,
         }
     }
     fn needs_prompt(&self, existing_key: Option<&str>) -> bool {
@@ -121,4 +246,10 @@ impl<T: RequiresApiKey + Send + Sync + 'static> RequiresApiKey for ApiKeyCapabil
             Err(e) => Err(e),
         }
     }
+    fn render_flow(&self) -> Result<FormFlow, CapabilityError> {
+        match self.inner() {
+            Ok(p) => p.render_flow(),
+            Err(e) => Err(e),
+        }
+    }
 }
@@ -1,6 +1,116 @@
-use std::{any::Any, sync::Arc};
+use std::{any::Any, collections::HashMap, ops::RangeInclusive, sync::Arc};
 
-use crate::capabilities::api_key_capability::RequiresApiKey;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    capabilities::{
+        api_key_capability::RequiresApiKey, configurable_mods_capability::ConfigurableModsBehavior,
+        dependencies_capability::ResolvesDependencies, game_launcher_capability::LaunchesGame,
+        ids::CapabilityId, multiple_files_capability::HasMultipleFiles,
+        oauth_capability::RequiresOAuth, profiles_capability::SupportsProfiles,
+        update_checker_capability::ChecksForUpdates,
+    },
+    runtime::context::Context,
+};
+
+/// Human readable information about a capability, e.g. for a provider's settings page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CapabilityDescriptor {
+    pub id: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    /// Whether the capability has user facing settings
+    pub configurable: bool,
+}
+
+/// Derives a display name from the last segment of an id, e.g. `vmm.mod.requires_api_key` -> `Requires api key`
+pub fn display_name_from_id(id: &str) -> String {
+    let name = id.rsplit('.').next().unwrap_or(id).replace('_', " ");
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// An inclusive range of capability schema versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct VersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl VersionRange {
+    pub fn contains(&self, version: u32) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+}
+
+impl From<RangeInclusive<u32>> for VersionRange {
+    fn from(value: RangeInclusive<u32>) -> Self {
+        Self {
+            min: *value.start(),
+            max: *value.end(),
+        }
+    }
+}
+
+/// The capability schema versions the host understands, keyed by capability id.
+///
+/// Defaults to the versions in `CapabilityId`, hosts built against an older lib-vmm can
+/// override entries with `with`.
+#[derive(Debug, Clone)]
+pub struct SupportedCapabilities {
+    versions: HashMap<String, VersionRange>,
+}
+
+impl Default for SupportedCapabilities {
+    fn default() -> Self {
+        Self {
+            versions: CapabilityId::ALL
+                .iter()
+                .map(|id| (id.as_str().to_string(), id.supported_versions().into()))
+                .collect(),
+        }
+    }
+}
+
+impl SupportedCapabilities {
+    pub fn with(mut self, id: &str, versions: RangeInclusive<u32>) -> Self {
+        self.versions.insert(id.to_string(), versions.into());
+        self
+    }
+
+    /// Returns the supported versions, `None` for capabilities unknown to the host
+    pub fn get(&self, id: &str) -> Option<VersionRange> {
+        self.versions.get(id).copied()
+    }
+}
+
+/// A capability whose version the host doesn't support
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CapabilityMismatch {
+    pub provider_id: String,
+    pub capability_id: String,
+    pub provided_version: u32,
+    pub supported_range: VersionRange,
+}
+
+/// Result of `Context::check_capability_compatibility`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct CompatibilityReport {
+    pub mismatches: Vec<CapabilityMismatch>,
+}
+
+impl CompatibilityReport {
+    pub fn is_compatible(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
 
 pub trait Capability: Any + Send + Sync {
     /// String discriminator. Prefer lowercase, dot-seperated names
@@ -10,9 +120,67 @@ pub trait Capability: Any + Send + Sync {
     /// Used for typed downcasting helpers.
     fn as_any(&self) -> &dyn Any;
 
+    /// The schema version of the capability, bumped on breaking changes to its behavior trait
+    /// or types. Checked against `SupportedCapabilities` when the `Context` is frozen.
+    fn version(&self) -> u32 {
+        1
+    }
+
+    /// Describes the capability for the frontend.
+    /// Defaults to the entry in `CapabilityId` or a name derived from `id()` for custom capabilities.
+    fn descriptor(&self) -> CapabilityDescriptor {
+        match CapabilityId::from_id(self.id()) {
+            Some(id) => id.descriptor(),
+            None => CapabilityDescriptor {
+                id: self.id().to_string(),
+                display_name: display_name_from_id(self.id()),
+                description: None,
+                configurable: false,
+            },
+        }
+    }
+
+    /// Called once when the owning provider is registered on a `ContextBuilder`,
+    /// with the normalized id it was registered under.
+    #[allow(unused_variables)]
+    fn on_registered(&self, provider_id: &str) {}
+
+    /// Called once by `ContextBuilder::freeze` after the `Context` is built.
+    /// Mod provider capabilities are notified first, then game provider capabilities.
+    #[allow(unused_variables)]
+    fn on_context_ready(&self, ctx: &Context) {}
+
     fn as_requires_api_key(&self) -> Option<&dyn RequiresApiKey> {
         None
     }
+
+    fn as_requires_oauth(&self) -> Option<&dyn RequiresOAuth> {
+        None
+    }
+
+    fn as_has_multiple_files(&self) -> Option<&dyn HasMultipleFiles> {
+        None
+    }
+
+    fn as_resolves_dependencies(&self) -> Option<&dyn ResolvesDependencies> {
+        None
+    }
+
+    fn as_configurable_mods(&self) -> Option<&dyn ConfigurableModsBehavior> {
+        None
+    }
+
+    fn as_supports_profiles(&self) -> Option<&dyn SupportsProfiles> {
+        None
+    }
+
+    fn as_checks_for_updates(&self) -> Option<&dyn ChecksForUpdates> {
+        None
+    }
+
+    fn as_launches_game(&self) -> Option<&dyn LaunchesGame> {
+        None
+    }
 }
 
 /// Helper to avoid manual downcast_ref
@@ -27,16 +195,101 @@ impl CapabilityCastExt for dyn Capability {
 }
 
 /// Macro to reduce boilerplate when declaring a capability with a fixed id.
+///
+/// - `capability!(MyCap, "my.id")` only implements `id()` and `as_any()`.
+/// - Capabilities implementing a behavior can list the accessors to override after the id,
+///   e.g. `capability!(MyCap, "my.id", requires_api_key, configurable_mods)`. Each generates the
+///   `as_*` method returning `Some(self)`, known accessors are `requires_api_key`,
+///   `requires_oauth`, `has_multiple_files`, `resolves_dependencies`, `configurable_mods`,
+///   `supports_profiles`, `checks_for_updates` and `launches_game`.
+/// - A schema version other than 1 goes right after the id, e.g.
+///   `capability!(MyCap, "my.id", version = 2, requires_api_key)`.
 #[macro_export]
 macro_rules! capability {
-    ($ty:ty, $id:expr) => {
-        impl Capability for $ty {
+    ($ty:ty, $id:expr, version = $version:literal $(, $accessor:ident)* $(,)?) => {
+        impl $crate::capabilities::base::Capability for $ty {
             fn id(&self) -> &'static str {
                 $id
             }
             fn as_any(&self) -> &dyn std::any::Any {
                 self
             }
+            fn version(&self) -> u32 {
+                $version
+            }
+            $(
+                $crate::capability!(@accessor $accessor);
+            )*
+        }
+    };
+    ($ty:ty, $id:expr $(, $accessor:ident)* $(,)?) => {
+        impl $crate::capabilities::base::Capability for $ty {
+            fn id(&self) -> &'static str {
+                $id
+            }
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+            $(
+                $crate::capability!(@accessor $accessor);
+            )*
+        }
+    };
+    (@accessor requires_api_key) => {
+        fn as_requires_api_key(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::api_key_capability::RequiresApiKey> {
+            Some(self)
+        }
+    };
+    (@accessor requires_oauth) => {
+        fn as_requires_oauth(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::oauth_capability::RequiresOAuth> {
+            Some(self)
+        }
+    };
+    (@accessor has_multiple_files) => {
+        fn as_has_multiple_files(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::multiple_files_capability::HasMultipleFiles> {
+            Some(self)
+        }
+    };
+    (@accessor resolves_dependencies) => {
+        fn as_resolves_dependencies(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::dependencies_capability::ResolvesDependencies> {
+            Some(self)
+        }
+    };
+    (@accessor configurable_mods) => {
+        fn as_configurable_mods(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::configurable_mods_capability::ConfigurableModsBehavior>
+        {
+            Some(self)
+        }
+    };
+    (@accessor supports_profiles) => {
+        fn as_supports_profiles(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::profiles_capability::SupportsProfiles> {
+            Some(self)
+        }
+    };
+    (@accessor checks_for_updates) => {
+        fn as_checks_for_updates(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::update_checker_capability::ChecksForUpdates> {
+            Some(self)
+        }
+    };
+    (@accessor launches_game) => {
+        fn as_launches_game(
+            &self,
+        ) -> Option<&dyn $crate::capabilities::game_launcher_capability::LaunchesGame> {
+            Some(self)
         }
     };
 }
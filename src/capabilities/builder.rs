@@ -1,17 +1,37 @@
-use std::sync::{Arc, Weak};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Weak},
+};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::capabilities::{
     api_key_capability::{ApiKeyCapability, RequiresApiKey},
-    base::CapabilityRef,
+    base::{Capability, CapabilityRef},
+    configurable_mods_capability::{ConfigurableModsBehavior, ConfigurableModsCapability},
+    dependencies_capability::{DependenciesCapability, ResolvesDependencies},
+    game_launcher_capability::{GameLauncherCapability, LaunchesGame},
+    multiple_files_capability::{HasMultipleFiles, MultipleFilesCapability},
+    oauth_capability::{OAuthCapability, RequiresOAuth},
+    profiles_capability::{ProfilesCapability, SupportsProfiles},
+    search_capability::{FullTextSearchCapability, SupportsFullTextSearch},
+    update_checker_capability::{ChecksForUpdates, UpdateCheckerCapability},
 };
 
 #[derive(Error, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CapabilityError {
     #[error("The provider was dropped before the refrence could be upgraded.")]
     ProviderDropped,
+    /// The provider's backend couldn't be reached
+    #[error("Network error: {0}")]
+    Network(String),
+    /// A request timed out after the given number of milliseconds
+    #[error("Request timed out after {0}ms")]
+    Timeout(u64),
+    /// A capability id was added more than once, see `CapabilityBuilder::finish_checked`
+    #[error("Capability {0} was added more than once")]
+    DuplicateCapability(String),
 }
 
 /// Fluent builder use by providers to handle constructors
@@ -35,8 +55,36 @@ impl<T> CapabilityBuilder<T> {
         }
     }
 
+    /// Adds a capability that doesn't need a reference to the provider,
+    /// e.g. a marker declared with `capability!`
+    pub fn with_cap<C: Capability + 'static>(mut self, cap: C) -> Self {
+        self.caps.push(Arc::new(cap) as CapabilityRef);
+        self
+    }
+
+    /// `with_cap`, only if `condition` holds
+    pub fn with_cap_if<C: Capability + 'static>(self, condition: bool, cap: C) -> Self {
+        if condition { self.with_cap(cap) } else { self }
+    }
+
+    /// Returns the capabilities. Debug builds panic if an id was added twice, see `finish_checked`
     pub fn finish(self) -> Vec<CapabilityRef> {
-        self.caps
+        if cfg!(debug_assertions) {
+            self.finish_checked().unwrap()
+        } else {
+            self.caps
+        }
+    }
+
+    /// `finish`, failing with `DuplicateCapability` for the first id added twice
+    pub fn finish_checked(self) -> Result<Vec<CapabilityRef>, CapabilityError> {
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = self.caps.iter().find(|c| !seen.insert(c.id())) {
+            return Err(CapabilityError::DuplicateCapability(
+                duplicate.id().to_string(),
+            ));
+        }
+        Ok(self.caps)
     }
 }
 
@@ -47,3 +95,67 @@ impl<T: RequiresApiKey + Send + Sync + 'static> CapabilityBuilder<T> {
         self
     }
 }
+
+impl<T: RequiresOAuth + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn oauth(mut self) -> Self {
+        self.caps
+            .push(Arc::new(OAuthCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: HasMultipleFiles + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn multiple_files(mut self) -> Self {
+        self.caps
+            .push(Arc::new(MultipleFilesCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: ResolvesDependencies + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn dependencies(mut self) -> Self {
+        self.caps
+            .push(Arc::new(DependenciesCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: ConfigurableModsBehavior + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn configurable_mods(mut self) -> Self {
+        self.caps
+            .push(Arc::new(ConfigurableModsCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: SupportsProfiles + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn profiles(mut self) -> Self {
+        self.caps
+            .push(Arc::new(ProfilesCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: ChecksForUpdates + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn update_checker(mut self) -> Self {
+        self.caps
+            .push(Arc::new(UpdateCheckerCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: LaunchesGame + Send + Sync + 'static> CapabilityBuilder<T> {
+    pub fn game_launcher(mut self) -> Self {
+        self.caps
+            .push(Arc::new(GameLauncherCapability::new(self.weak.clone())) as CapabilityRef);
+        self
+    }
+}
+
+impl<T: SupportsFullTextSearch + 'static> CapabilityBuilder<T> {
+    pub fn full_text_search(mut self) -> Self {
+        self.caps
+            .push(Arc::new(FullTextSearchCapability) as CapabilityRef);
+        self
+    }
+}
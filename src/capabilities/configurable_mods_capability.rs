@@ -0,0 +1,196 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::capabilities::{
+    base::Capability,
+    builder::CapabilityError,
+    form::{FieldError, FormResponse, FormSchema, FormValidationError, responses_from_json},
+    ids,
+};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ConfigurationError {
+    #[error("Invalid value for {id}: {reason}")]
+    InvalidField { id: String, reason: String },
+    #[error("Mod {0} was not found")]
+    ModNotFound(String),
+    #[error("Failed to save the configuration: {0}")]
+    Io(String),
+    #[error("The provider was dropped before the configuration could be applied.")]
+    ProviderDropped,
+    #[error(transparent)]
+    Invalid(#[from] FormValidationError),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<std::io::Error> for ConfigurationError {
+    fn from(value: std::io::Error) -> Self {
+        ConfigurationError::Io(value.to_string())
+    }
+}
+
+impl From<CapabilityError> for ConfigurationError {
+    fn from(value: CapabilityError) -> Self {
+        match value {
+            CapabilityError::ProviderDropped => ConfigurationError::ProviderDropped,
+            e @ (CapabilityError::Network(_)
+            | CapabilityError::Timeout(_)
+            | CapabilityError::DuplicateCapability(_)) => ConfigurationError::Other(e.to_string()),
+        }
+    }
+}
+
+/// Behavior-only trait (no Capability)
+///
+/// For games whose mods expose settings, rendered with the same form schemas as API keys.
+///
+/// # Migration
+/// Before 0.3.0 `apply_configuration` was synchronous and returned `()`, and `get_configurable`
+/// returned a plain `Option`. Implementations need `#[async_trait]`, should return
+/// `Ok(())` where they used to return nothing and wrap their schema lookup in `Ok(..)`.
+#[async_trait]
+pub trait ConfigurableModsBehavior: Send + Sync {
+    /// Returns the configuration form of a mod, or `None` if it has no settings.
+    fn get_configurable(&self, mod_id: &str) -> Result<Option<FormSchema>, CapabilityError>;
+
+    /// Called when the user submits the configuration form of a mod.
+    /// Implementations typically write the mod's config files or call the provider's API.
+    async fn apply_configuration(
+        &self,
+        mod_id: &str,
+        responses: Vec<FormResponse>,
+    ) -> Result<(), ConfigurationError>;
+
+    /// Checks responses against the mod's schema without applying them.
+    /// Mods without settings accept no responses.
+    fn validate_configuration(
+        &self,
+        mod_id: &str,
+        responses: &[FormResponse],
+    ) -> Result<(), FormValidationError> {
+        let schema = self
+            .get_configurable(mod_id)
+            .map_err(|e| FormValidationError::SchemaUnavailable(e.to_string()))?;
+        match schema {
+            Some(schema) => schema.validate(responses).map(|_| ()),
+            None if responses.is_empty() => Ok(()),
+            None => Err(FormValidationError::Fields(
+                responses
+                    .iter()
+                    .map(|r| FieldError {
+                        field_id: r.id.clone(),
+                        message: "Unknown field".into(),
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Applies the default value of every field of the mod's schema, see `Field::default_value`.
+    /// Does nothing for mods without settings.
+    async fn reset_to_defaults(&self, mod_id: &str) -> Result<(), ConfigurationError> {
+        match self.get_configurable(mod_id)? {
+            Some(schema) => {
+                self.apply_configuration(mod_id, schema.default_responses())
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The current configuration of a mod as a JSON object keyed by field id, e.g. to back it up
+    /// when switching profiles. See `form::responses_to_json`.
+    #[allow(unused_variables)]
+    fn export_configuration(&self, mod_id: &str) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Applies a configuration written by `export_configuration`, after checking it with
+    /// `validate_configuration`. Malformed data fails with `FormValidationError::InvalidImport`.
+    async fn import_configuration(
+        &self,
+        mod_id: &str,
+        data: serde_json::Value,
+    ) -> Result<(), ConfigurationError> {
+        let responses = responses_from_json(data)?;
+        self.validate_configuration(mod_id, &responses)?;
+        self.apply_configuration(mod_id, responses).await
+    }
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct ConfigurableModsCapability<T: ConfigurableModsBehavior + Send + Sync + 'static>(Weak<T>);
+
+impl<T: ConfigurableModsBehavior + Send + Sync + 'static> ConfigurableModsCapability<T> {
+    /// Creates a new `ConfigurableModsCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: ConfigurableModsBehavior + Send + Sync + 'static> Capability
+    for ConfigurableModsCapability<T>
+{
+    fn id(&self) -> &'static str {
+        ids::CONFIGURABLE_MODS
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_configurable_mods(&self) -> Option<&dyn ConfigurableModsBehavior> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
+impl<T: ConfigurableModsBehavior + Send + Sync + 'static> ConfigurableModsBehavior
+    for ConfigurableModsCapability<T>
+{
+    fn get_configurable(&self, mod_id: &str) -> Result<Option<FormSchema>, CapabilityError> {
+        self.inner()?.get_configurable(mod_id)
+    }
+    async fn apply_configuration(
+        &self,
+        mod_id: &str,
+        responses: Vec<FormResponse>,
+    ) -> Result<(), ConfigurationError> {
+        self.inner()?.apply_configuration(mod_id, responses).await
+    }
+    fn validate_configuration(
+        &self,
+        mod_id: &str,
+        responses: &[FormResponse],
+    ) -> Result<(), FormValidationError> {
+        match self.inner() {
+            Ok(p) => p.validate_configuration(mod_id, responses),
+            Err(e) => Err(FormValidationError::SchemaUnavailable(e.to_string())),
+        }
+    }
+    async fn reset_to_defaults(&self, mod_id: &str) -> Result<(), ConfigurationError> {
+        self.inner()?.reset_to_defaults(mod_id).await
+    }
+    fn export_configuration(&self, mod_id: &str) -> Option<serde_json::Value> {
+        match self.inner() {
+            Ok(p) => p.export_configuration(mod_id),
+            Err(_) => None,
+        }
+    }
+    async fn import_configuration(
+        &self,
+        mod_id: &str,
+        data: serde_json::Value,
+    ) -> Result<(), ConfigurationError> {
+        self.inner()?.import_configuration(mod_id, data).await
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Weak},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    capabilities::{base::Capability, builder::CapabilityError, ids},
+    traits::discovery::{DiscoveryError, ModDependency},
+};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum DependencyError {
+    /// The mods forming the cycle, starting and ending with the same id
+    #[error("Dependency cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("Missing required dependency {0}")]
+    Missing(String),
+}
+
+/// Behavior-only trait (no Capability)
+#[async_trait]
+pub trait ResolvesDependencies: Send + Sync {
+    /// Returns the direct dependencies of a mod
+    async fn dependencies_of(&self, mod_id: &str) -> Result<Vec<ModDependency>, DiscoveryError>;
+}
+
+/// Orders `root` and its transitive dependencies so every mod comes after the mods it depends on.
+///
+/// `deps` maps every known mod to its direct dependencies. Required dependencies without an entry
+/// are reported as missing, optional ones are skipped.
+pub fn resolve_install_order(
+    root: &str,
+    deps: &HashMap<String, Vec<ModDependency>>,
+) -> Result<Vec<String>, DependencyError> {
+    fn visit(
+        id: &str,
+        deps: &HashMap<String, Vec<ModDependency>>,
+        path: &mut Vec<String>,
+        done: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), DependencyError> {
+        if done.contains(id) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|p| p == id) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id.to_string());
+            return Err(DependencyError::Cycle(cycle));
+        }
+        let children = deps
+            .get(id)
+            .ok_or_else(|| DependencyError::Missing(id.to_string()))?;
+
+        path.push(id.to_string());
+        for dep in children {
+            if dep.optional && !deps.contains_key(&dep.mod_id) {
+                continue;
+            }
+            visit(&dep.mod_id, deps, path, done, order)?;
+        }
+        path.pop();
+
+        done.insert(id.to_string());
+        order.push(id.to_string());
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    visit(root, deps, &mut Vec::new(), &mut HashSet::new(), &mut order)?;
+    Ok(order)
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct DependenciesCapability<T: ResolvesDependencies + Send + Sync + 'static>(Weak<T>);
+
+impl<T: ResolvesDependencies + Send + Sync + 'static> DependenciesCapability<T> {
+    /// Creates a new `DependenciesCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: ResolvesDependencies + Send + Sync + 'static> Capability for DependenciesCapability<T> {
+    fn id(&self) -> &'static str {
+        ids::RESOLVES_DEPENDENCIES
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_resolves_dependencies(&self) -> Option<&dyn ResolvesDependencies> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
+impl<T: ResolvesDependencies + Send + Sync + 'static> ResolvesDependencies
+    for DependenciesCapability<T>
+{
+    async fn dependencies_of(&self, mod_id: &str) -> Result<Vec<ModDependency>, DiscoveryError> {
+        match self.inner() {
+            Ok(p) => p.dependencies_of(mod_id).await,
+            Err(_) => Err(DiscoveryError::ProviderUnavailable),
+        }
+    }
+}
@@ -1,4 +1,10 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::capabilities::api_key_capability::ApiSubmitResponse;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
@@ -7,6 +13,52 @@ pub enum FieldType {
     Password,
     Select(Vec<String>),
     MarkdownInfo,
+    /// A boolean toggle
+    Checkbox {
+        default: bool,
+    },
+    /// A numeric input, rendered as a slider when both bounds are set
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+        step: Option<f64>,
+    },
+    /// Like `Select`, but allows picking any number of the options
+    MultiSelect(Vec<String>),
+    /// Like `Select`, but with separate values and (localizable) labels
+    SelectV2(Vec<SelectOption>),
+    /// A native file/folder picker
+    FilePicker {
+        directories_only: bool,
+        /// Allowed file extensions without the leading dot, empty allows all
+        extensions: Vec<String>,
+    },
+}
+
+/// An option of a `SelectV2` field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SelectOption {
+    /// The value submitted when the option is picked
+    pub value: String,
+    pub label: String,
+    #[serde(default)]
+    pub label_key: Option<String>,
+}
+
+impl SelectOption {
+    pub fn new(value: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            label: label.into(),
+            label_key: None,
+        }
+    }
+
+    pub fn label_key(mut self, key: impl Into<String>) -> Self {
+        self.label_key = Some(key.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,16 +66,819 @@ pub enum FieldType {
 pub struct Field {
     pub id: String,
     pub label: String,
+    /// Translation key for `label`, see `FormSchema::localize`
+    #[serde(default)]
+    pub label_key: Option<String>,
     pub field_type: FieldType,
     pub placeholder: Option<String>,
     pub regex: Option<String>,
     pub help: Option<String>,
+    /// Translation key for `help`
+    #[serde(default)]
+    pub help_key: Option<String>,
+    /// Whether the field must be given a non-blank value
+    #[serde(default)]
+    pub required: bool,
+    /// The initial value of the field, also used as its default
+    #[serde(default)]
+    pub value: Option<FormValue>,
+    /// Only show (and validate) the field while the condition holds
+    #[serde(default)]
+    pub visible_when: Option<FieldCondition>,
+}
+
+/// A condition on the current value of another field of the same schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum FieldCondition {
+    Equals {
+        field_id: String,
+        value: FormValue,
+    },
+    NotEquals {
+        field_id: String,
+        value: FormValue,
+    },
+    OneOf {
+        field_id: String,
+        values: Vec<FormValue>,
+    },
+}
+
+impl FieldCondition {
+    pub fn equals(field_id: impl Into<String>, value: impl Into<FormValue>) -> Self {
+        FieldCondition::Equals {
+            field_id: field_id.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn not_equals(field_id: impl Into<String>, value: impl Into<FormValue>) -> Self {
+        FieldCondition::NotEquals {
+            field_id: field_id.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn one_of(field_id: impl Into<String>, values: Vec<FormValue>) -> Self {
+        FieldCondition::OneOf {
+            field_id: field_id.into(),
+            values,
+        }
+    }
+
+    /// The id of the field this condition depends on
+    pub fn field_id(&self) -> &str {
+        match self {
+            FieldCondition::Equals { field_id, .. }
+            | FieldCondition::NotEquals { field_id, .. }
+            | FieldCondition::OneOf { field_id, .. } => field_id,
+        }
+    }
+
+    /// Checks the condition against the current value of the referenced field.
+    /// Values are compared by their text encoding, so `"true"` equals `true`
+    pub fn matches(&self, current: Option<&FormValue>) -> bool {
+        let current = current.map(FormValue::to_text);
+        let eq = |v: &FormValue| current.as_deref() == Some(v.to_text().as_str());
+        match self {
+            FieldCondition::Equals { value, .. } => eq(value),
+            FieldCondition::NotEquals { value, .. } => !eq(value),
+            FieldCondition::OneOf { values, .. } => values.iter().any(eq),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct FormSchema {
     pub title: String,
+    /// Translation key for `title`, see `FormSchema::localize`
+    #[serde(default)]
+    pub title_key: Option<String>,
     pub description: Option<String>,
+    /// Translation key for `description`
+    #[serde(default)]
+    pub description_key: Option<String>,
     pub fields: Vec<Field>,
 }
+
+/// Errors for malformed form schemas, returned by `FormSchemaBuilder::build`
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum FormSchemaError {
+    #[error("Field ids cannot be empty")]
+    EmptyFieldId,
+    #[error("Duplicate field id: {0}")]
+    DuplicateFieldId(String),
+    #[error("Field {field_id} depends on unknown field {depends_on}")]
+    UnknownConditionField {
+        field_id: String,
+        depends_on: String,
+    },
+    #[error("Visibility conditions of field {0} form a cycle")]
+    ConditionCycle(String),
+}
+
+/// Fluent builder for `FormSchema`, see `FormSchema::builder`
+pub struct FormSchemaBuilder {
+    schema: FormSchema,
+}
+
+impl FormSchemaBuilder {
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.schema.description = Some(description.into());
+        self
+    }
+
+    pub fn title_key(mut self, key: impl Into<String>) -> Self {
+        self.schema.title_key = Some(key.into());
+        self
+    }
+
+    pub fn description_key(mut self, key: impl Into<String>) -> Self {
+        self.schema.description_key = Some(key.into());
+        self
+    }
+
+    pub fn field(mut self, field: Field) -> Self {
+        self.schema.fields.push(field);
+        self
+    }
+
+    /// Validates the schema, see `FormSchema::check`
+    pub fn build(self) -> Result<FormSchema, FormSchemaError> {
+        self.schema.check()?;
+        Ok(self.schema)
+    }
+}
+
+impl FormSchema {
+    /// Starts building a schema with the given title
+    /// ```
+    /// use lib_vmm::capabilities::form::{Field, FormSchema};
+    ///
+    /// let schema = FormSchema::builder("Enter key")
+    ///     .description("Your key can be found in your account settings")
+    ///     .field(Field::password("api_key").label("API key").placeholder("Paste key here").required())
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(schema.fields.len(), 1);
+    /// ```
+    pub fn builder(title: impl Into<String>) -> FormSchemaBuilder {
+        FormSchemaBuilder {
+            schema: FormSchema {
+                title: title.into(),
+                title_key: None,
+                description: None,
+                description_key: None,
+                fields: Vec::new(),
+            },
+        }
+    }
+
+    /// Checks the schema is well formed: field ids must be unique and non-empty,
+    /// and visibility conditions must reference existing fields without forming cycles
+    pub fn check(&self) -> Result<(), FormSchemaError> {
+        let mut seen = HashSet::new();
+        for field in &self.fields {
+            if field.id.trim().is_empty() {
+                return Err(FormSchemaError::EmptyFieldId);
+            }
+            if !seen.insert(field.id.as_str()) {
+                return Err(FormSchemaError::DuplicateFieldId(field.id.clone()));
+            }
+        }
+
+        for field in &self.fields {
+            let mut chain = HashSet::from([field.id.as_str()]);
+            let mut current = field;
+            while let Some(cond) = &current.visible_when {
+                current = self.field(cond.field_id()).ok_or_else(|| {
+                    FormSchemaError::UnknownConditionField {
+                        field_id: current.id.clone(),
+                        depends_on: cond.field_id().to_string(),
+                    }
+                })?;
+                if !chain.insert(current.id.as_str()) {
+                    return Err(FormSchemaError::ConditionCycle(field.id.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn field(&self, id: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.id == id)
+    }
+
+    /// Returns a copy of the schema with every translation key replaced by the resolved string.
+    /// Keys the resolver doesn't know keep the literal text
+    pub fn localize(&self, resolver: &dyn Fn(&str) -> Option<String>) -> FormSchema {
+        let resolve = |key: &Option<String>| key.as_deref().and_then(resolver);
+
+        let mut schema = self.clone();
+        if let Some(title) = resolve(&schema.title_key) {
+            schema.title = title;
+        }
+        if let Some(description) = resolve(&schema.description_key) {
+            schema.description = Some(description);
+        }
+        for field in &mut schema.fields {
+            if let Some(label) = resolve(&field.label_key) {
+                field.label = label;
+            }
+            if let Some(help) = resolve(&field.help_key) {
+                field.help = Some(help);
+            }
+            if let FieldType::SelectV2(options) = &mut field.field_type {
+                for option in options {
+                    if let Some(label) = resolve(&option.label_key) {
+                        option.label = label;
+                    }
+                }
+            }
+        }
+        schema
+    }
+
+    /// Returns the fields which should be shown given the current responses.
+    ///
+    /// A field is hidden when its condition doesn't hold, or when the field it depends on is
+    /// itself hidden. Fields without a response are compared using their default value.
+    pub fn visible_fields(&self, current: &[FormResponse]) -> Vec<&Field> {
+        self.fields
+            .iter()
+            .filter(|f| self.is_visible(f, current, &mut HashSet::new()))
+            .collect()
+    }
+
+    /// A response for every field with a default value, see `Field::default_value`
+    pub fn default_responses(&self) -> Vec<FormResponse> {
+        self.fields
+            .iter()
+            .filter_map(|f| {
+                f.default_value().map(|value| FormResponse {
+                    id: f.id.clone(),
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    fn is_visible<'a>(
+        &'a self,
+        field: &'a Field,
+        current: &[FormResponse],
+        chain: &mut HashSet<&'a str>,
+    ) -> bool {
+        let Some(cond) = &field.visible_when else {
+            return true;
+        };
+        // Unknown dependencies and cycles are rejected by `check`, hide the field if unchecked
+        let Some(parent) = self.field(cond.field_id()) else {
+            return false;
+        };
+        if !chain.insert(field.id.as_str()) {
+            return false;
+        }
+
+        let value = current.iter().find(|r| r.id == parent.id).map(|r| &r.value);
+        let default = parent.default_value();
+
+        cond.matches(value.or(default.as_ref())) && self.is_visible(parent, current, chain)
+    }
+}
+
+impl Field {
+    /// The field's `value`, falling back to the default of checkboxes
+    pub fn default_value(&self) -> Option<FormValue> {
+        match (&self.value, &self.field_type) {
+            (Some(value), _) => Some(value.clone()),
+            (None, FieldType::Checkbox { default }) => Some(FormValue::Bool(*default)),
+            _ => None,
+        }
+    }
+
+    /// Creates a field of the given type, the label defaults to the id
+    pub fn new(id: impl Into<String>, field_type: FieldType) -> Self {
+        let id = id.into();
+        Self {
+            label: id.clone(),
+            label_key: None,
+            id,
+            field_type,
+            placeholder: None,
+            regex: None,
+            help: None,
+            help_key: None,
+            required: false,
+            value: None,
+            visible_when: None,
+        }
+    }
+
+    pub fn text(id: impl Into<String>) -> Self {
+        Self::new(id, FieldType::Text)
+    }
+
+    pub fn password(id: impl Into<String>) -> Self {
+        Self::new(id, FieldType::Password)
+    }
+
+    pub fn select(id: impl Into<String>, options: Vec<String>) -> Self {
+        Self::new(id, FieldType::Select(options))
+    }
+
+    /// A select with separate values and labels, see `SelectOption`
+    pub fn select_options(id: impl Into<String>, options: Vec<SelectOption>) -> Self {
+        Self::new(id, FieldType::SelectV2(options))
+    }
+
+    pub fn markdown(id: impl Into<String>) -> Self {
+        Self::new(id, FieldType::MarkdownInfo)
+    }
+
+    pub fn checkbox(id: impl Into<String>, default: bool) -> Self {
+        Self::new(id, FieldType::Checkbox { default })
+    }
+
+    pub fn number(id: impl Into<String>, min: Option<f64>, max: Option<f64>) -> Self {
+        Self::new(
+            id,
+            FieldType::Number {
+                min,
+                max,
+                step: None,
+            },
+        )
+    }
+
+    pub fn multi_select(id: impl Into<String>, options: Vec<String>) -> Self {
+        Self::new(id, FieldType::MultiSelect(options))
+    }
+
+    pub fn file_picker(
+        id: impl Into<String>,
+        directories_only: bool,
+        extensions: Vec<String>,
+    ) -> Self {
+        Self::new(
+            id,
+            FieldType::FilePicker {
+                directories_only,
+                extensions,
+            },
+        )
+    }
+
+    /// Sets the step of a `Number` field, ignored for other field types
+    pub fn step(mut self, step: f64) -> Self {
+        if let FieldType::Number { step: s, .. } = &mut self.field_type {
+            *s = Some(step);
+        }
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn label_key(mut self, key: impl Into<String>) -> Self {
+        self.label_key = Some(key.into());
+        self
+    }
+
+    pub fn help_key(mut self, key: impl Into<String>) -> Self {
+        self.help_key = Some(key.into());
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    pub fn regex(mut self, regex: impl Into<String>) -> Self {
+        self.regex = Some(regex.into());
+        self
+    }
+
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Sets the initial (default) value
+    pub fn value(mut self, value: impl Into<FormValue>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn visible_when(mut self, condition: FieldCondition) -> Self {
+        self.visible_when = Some(condition);
+        self
+    }
+}
+
+/// A typed form value.
+///
+/// Serialized untagged, so payloads from before this type existed (plain strings) still
+/// deserialize as `Text`. Code which used to parse strings can use the lossy accessors
+/// (`as_bool`, `as_number`, `as_list`), which also accept the old string encodings
+/// (`"true"`, `"3"`, comma-joined lists).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(untagged)]
+pub enum FormValue {
+    Text(String),
+    Bool(bool),
+    Number(f64),
+    List(Vec<String>),
+}
+
+impl FormValue {
+    /// Returns the text, only for `Text` values
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            FormValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean, parsing `Text` values
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            FormValue::Bool(b) => Some(*b),
+            FormValue::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the number, parsing `Text` values
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            FormValue::Number(n) => Some(*n),
+            FormValue::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the list, splitting comma separated `Text` values
+    pub fn as_list(&self) -> Option<Vec<String>> {
+        match self {
+            FormValue::List(l) => Some(l.clone()),
+            FormValue::Text(s) if s.trim().is_empty() => Some(Vec::new()),
+            FormValue::Text(s) => Some(s.split(',').map(|i| i.trim().to_string()).collect()),
+            _ => None,
+        }
+    }
+
+    /// Lossy conversion into the legacy string encoding
+    pub fn to_text(&self) -> String {
+        match self {
+            FormValue::Text(s) => s.clone(),
+            FormValue::Bool(b) => b.to_string(),
+            FormValue::Number(n) => n.to_string(),
+            FormValue::List(l) => l.join(","),
+        }
+    }
+
+    /// Whether the value counts as "not provided"
+    pub fn is_blank(&self) -> bool {
+        match self {
+            FormValue::Text(s) => s.trim().is_empty(),
+            FormValue::List(l) => l.is_empty(),
+            FormValue::Bool(_) | FormValue::Number(_) => false,
+        }
+    }
+}
+
+impl From<String> for FormValue {
+    fn from(value: String) -> Self {
+        FormValue::Text(value)
+    }
+}
+
+impl From<&str> for FormValue {
+    fn from(value: &str) -> Self {
+        FormValue::Text(value.to_string())
+    }
+}
+
+impl From<bool> for FormValue {
+    fn from(value: bool) -> Self {
+        FormValue::Bool(value)
+    }
+}
+
+impl From<f64> for FormValue {
+    fn from(value: f64) -> Self {
+        FormValue::Number(value)
+    }
+}
+
+impl From<Vec<String>> for FormValue {
+    fn from(value: Vec<String>) -> Self {
+        FormValue::List(value)
+    }
+}
+
+/// A single submitted value for a field of a `FormSchema`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FormResponse {
+    pub id: String,
+    pub value: FormValue,
+}
+
+impl From<ApiSubmitResponse> for FormResponse {
+    fn from(value: ApiSubmitResponse) -> Self {
+        Self {
+            id: value.id,
+            value: value.value.into(),
+        }
+    }
+}
+
+/// Serializes responses as a JSON object keyed by field id, e.g. for configuration backups
+pub fn responses_to_json(responses: &[FormResponse]) -> serde_json::Value {
+    serde_json::Value::Object(
+        responses
+            .iter()
+            .map(|r| (r.id.clone(), serde_json::json!(r.value)))
+            .collect(),
+    )
+}
+
+/// Reads responses written by `responses_to_json`
+pub fn responses_from_json(
+    data: serde_json::Value,
+) -> Result<Vec<FormResponse>, FormValidationError> {
+    let serde_json::Value::Object(fields) = data else {
+        return Err(FormValidationError::InvalidImport(
+            "Expected a JSON object keyed by field id".into(),
+        ));
+    };
+    fields
+        .into_iter()
+        .map(|(id, value)| match serde_json::from_value(value) {
+            Ok(value) => Ok(FormResponse { id, value }),
+            Err(e) => Err(FormValidationError::InvalidImport(format!(
+                "Invalid value for {id}: {e}"
+            ))),
+        })
+        .collect()
+}
+
+/// A validation failure attributed to a single field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FieldError {
+    pub field_id: String,
+    pub message: String,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum FormValidationError {
+    #[error("{} field(s) failed validation", .0.len())]
+    Fields(Vec<FieldError>),
+    /// The schema to validate against couldn't be retrieved, e.g. because the provider is gone
+    #[error("The form schema is unavailable: {0}")]
+    SchemaUnavailable(String),
+    /// Imported data isn't shaped like `responses_to_json` output
+    #[error("Invalid import: {0}")]
+    InvalidImport(String),
+}
+
+impl FormValidationError {
+    /// Returns the error message for the given field, if it failed validation
+    pub fn for_field(&self, id: &str) -> Option<&str> {
+        match self {
+            FormValidationError::Fields(errors) => errors
+                .iter()
+                .find(|e| e.field_id == id)
+                .map(|e| e.message.as_str()),
+            FormValidationError::SchemaUnavailable(_) | FormValidationError::InvalidImport(_) => {
+                None
+            }
+        }
+    }
+}
+
+/// The responses of a form which passed `FormSchema::validate`, keyed by field id
+#[derive(Debug, Clone, Default)]
+pub struct ValidatedForm {
+    values: HashMap<String, FormValue>,
+}
+
+impl ValidatedForm {
+    pub fn get(&self, id: &str) -> Option<&FormValue> {
+        self.values.get(id)
+    }
+
+    pub fn into_values(self) -> HashMap<String, FormValue> {
+        self.values
+    }
+}
+
+impl FormSchema {
+    /// Validates submitted responses against this schema.
+    ///
+    /// Checks that every response belongs to a field, required fields are present and non-blank,
+    /// regexes compile and match, choices are among the declared options and numbers respect their bounds.
+    /// Fields hidden by their `visible_when` condition are skipped and their responses dropped.
+    pub fn validate(
+        &self,
+        responses: &[FormResponse],
+    ) -> Result<ValidatedForm, FormValidationError> {
+        let mut errors = Vec::new();
+        let mut values = HashMap::new();
+
+        for response in responses {
+            if !self.fields.iter().any(|f| f.id == response.id) {
+                errors.push(FieldError {
+                    field_id: response.id.clone(),
+                    message: "Unknown field".into(),
+                });
+            }
+        }
+
+        for field in self.visible_fields(responses) {
+            let value = responses
+                .iter()
+                .find(|r| r.id == field.id)
+                .map(|r| &r.value);
+
+            match value {
+                Some(v) if !v.is_blank() => {
+                    if let Err(message) = field.check_value(v) {
+                        errors.push(FieldError {
+                            field_id: field.id.clone(),
+                            message,
+                        });
+                    } else {
+                        values.insert(field.id.clone(), v.clone());
+                    }
+                }
+                _ if field.required => errors.push(FieldError {
+                    field_id: field.id.clone(),
+                    message: "This field is required".into(),
+                }),
+                _ => {}
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(ValidatedForm { values })
+        } else {
+            Err(FormValidationError::Fields(errors))
+        }
+    }
+}
+
+/// Only show a step when a field of a previous step was answered with the given value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct StepCondition {
+    pub field_id: String,
+    pub equals: FormValue,
+}
+
+/// A single page of a `FormFlow`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FormStep {
+    pub id: String,
+    pub schema: FormSchema,
+    #[serde(default)]
+    pub condition: Option<StepCondition>,
+}
+
+/// An ordered, multi-page form, e.g. pick an auth method -> enter credentials -> confirm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FormFlow {
+    pub steps: Vec<FormStep>,
+}
+
+impl FormFlow {
+    /// Wraps a single schema into a one-step flow
+    pub fn single(schema: FormSchema) -> Self {
+        Self {
+            steps: vec![FormStep {
+                id: "default".into(),
+                schema,
+                condition: None,
+            }],
+        }
+    }
+}
+
+/// What the UI should show next while walking through a `FormFlow`
+#[derive(Debug, Clone, Copy)]
+pub enum FlowStep<'a> {
+    Step(&'a FormStep),
+    Complete,
+}
+
+/// Accumulates the responses of a `FormFlow` across its steps
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FormFlowState {
+    /// Ids of the steps which have been submitted
+    pub completed: Vec<String>,
+    /// Every response submitted so far
+    pub responses: Vec<FormResponse>,
+}
+
+impl FormFlowState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the responses of a step, replacing earlier answers for the same fields
+    pub fn submit(&mut self, step_id: &str, responses: Vec<FormResponse>) {
+        self.responses
+            .retain(|r| !responses.iter().any(|n| n.id == r.id));
+        self.responses.extend(responses);
+        if !self.completed.iter().any(|s| s == step_id) {
+            self.completed.push(step_id.to_string());
+        }
+    }
+
+    /// Returns the first step that hasn't been submitted and whose condition holds, or `Complete`
+    pub fn next<'a>(&self, flow: &'a FormFlow) -> FlowStep<'a> {
+        flow.steps
+            .iter()
+            .filter(|step| !self.completed.contains(&step.id))
+            .find(|step| self.is_visible(step))
+            .map_or(FlowStep::Complete, FlowStep::Step)
+    }
+
+    fn is_visible(&self, step: &FormStep) -> bool {
+        match &step.condition {
+            None => true,
+            Some(cond) => self
+                .responses
+                .iter()
+                .any(|r| r.id == cond.field_id && r.value.to_text() == cond.equals.to_text()),
+        }
+    }
+}
+
+impl Field {
+    /// Checks a non-blank value against the field's pattern and type constraints
+    fn check_value(&self, value: &FormValue) -> Result<(), String> {
+        if let Some(pattern) = &self.regex {
+            let re = Regex::new(pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+            if !re.is_match(&value.to_text()) {
+                return Err("Value does not match the expected format".into());
+            }
+        }
+
+        match &self.field_type {
+            FieldType::Select(options) => {
+                let text = value.to_text();
+                if !options.contains(&text) {
+                    return Err(format!("'{}' is not a valid option", text));
+                }
+            }
+            FieldType::SelectV2(options) => {
+                let text = value.to_text();
+                if !options.iter().any(|o| o.value == text) {
+                    return Err(format!("'{}' is not a valid option", text));
+                }
+            }
+            FieldType::MultiSelect(options) => {
+                let items = value.as_list().ok_or("Expected a list of options")?;
+                if let Some(item) = items.iter().find(|i| !options.contains(i)) {
+                    return Err(format!("'{}' is not a valid option", item));
+                }
+            }
+            FieldType::Number { min, max, .. } => {
+                // NaN would pass any range
+                let n = value
+                    .as_number()
+                    .filter(|n| n.is_finite())
+                    .ok_or_else(|| format!("'{}' is not a number", value.to_text()))?;
+                if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+                    return Err(format!("{} is out of range", n));
+                }
+            }
+            FieldType::Checkbox { .. } if value.as_bool().is_none() => {
+                return Err(format!("'{}' is not a boolean", value.to_text()));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
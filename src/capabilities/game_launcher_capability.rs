@@ -0,0 +1,59 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+
+use crate::capabilities::{base::Capability, builder::CapabilityError, ids};
+pub use crate::traits::game_provider::GameLaunchError;
+
+/// Behavior-only trait (no Capability)
+///
+/// For games VMM can start itself, used by `Context::launch_active_game`.
+#[async_trait]
+pub trait LaunchesGame: Send + Sync {
+    /// Starts the game and returns its process id
+    async fn launch(&self, args: &[String]) -> Result<u32, GameLaunchError>;
+
+    fn is_running(&self) -> bool;
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct GameLauncherCapability<T: LaunchesGame + Send + Sync + 'static>(Weak<T>);
+
+impl<T: LaunchesGame + Send + Sync + 'static> GameLauncherCapability<T> {
+    /// Creates a new `GameLauncherCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: LaunchesGame + Send + Sync + 'static> Capability for GameLauncherCapability<T> {
+    fn id(&self) -> &'static str {
+        ids::GAME_LAUNCHER
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_launches_game(&self) -> Option<&dyn LaunchesGame> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
+impl<T: LaunchesGame + Send + Sync + 'static> LaunchesGame for GameLauncherCapability<T> {
+    async fn launch(&self, args: &[String]) -> Result<u32, GameLaunchError> {
+        self.inner()?.launch(args).await
+    }
+
+    fn is_running(&self) -> bool {
+        match self.inner() {
+            Ok(p) => p.is_running(),
+            Err(_) => false,
+        }
+    }
+}
@@ -1,7 +1,14 @@
 use crate::define_capabilities;
 
 define_capabilities! {
-    REQUIRES_API_KEY = "vmm.mod.requires_api_key";
-    INSTALLS_MOD_LOADER = "vmm.game.installs_mod_loader";
-    CONFIGURABLE_MODS = "vmm.game.configurable_mods";
+    REQUIRES_API_KEY = "vmm.mod.requires_api_key", "API key", configurable;
+    REQUIRES_OAUTH = "vmm.mod.requires_oauth", "OAuth login", configurable;
+    HAS_MULTIPLE_FILES = "vmm.mod.has_multiple_files", "Multiple files per mod";
+    RESOLVES_DEPENDENCIES = "vmm.mod.resolves_dependencies", "Dependency resolution";
+    INSTALLS_MOD_LOADER = "vmm.game.installs_mod_loader", "Mod loader installation";
+    CONFIGURABLE_MODS = "vmm.game.configurable_mods", "Configurable mods", configurable;
+    SUPPORTS_PROFILES = "vmm.game.supports_profiles", "Mod profiles";
+    UPDATE_CHECKER = "vmm.mod.update_checker", "Update checks";
+    FULL_TEXT_SEARCH = "vmm.mod.full_text_search", "Full-text search";
+    GAME_LAUNCHER = "vmm.game.launcher", "Game launching";
 }
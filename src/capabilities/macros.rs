@@ -1,29 +1,88 @@
 /// Helper macro for defining capabilities
+///
+/// Each entry can optionally be followed by a display name and the `configurable` flag,
+/// e.g. `REQUIRES_API_KEY = "vmm.mod.requires_api_key", "API key", configurable;`.
+/// Without a display name one is derived from the id.
+///
+/// The schema versions the host supports can be given after the name, e.g.
+/// `REQUIRES_API_KEY(1..=2) = ...`, entries without one only support version 1.
+///
+/// Attributes before an entry, like `#[deprecated]` for retired ids, apply to both the constant
+/// and the `CapabilityId` variant:
+///
+/// ```compile_fail
+/// #![deny(deprecated)]
+/// lib_vmm::define_capabilities! {
+///     #[deprecated(since = "0.4.0", note = "Use NEW_CAP instead")]
+///     OLD_CAP = "example.old";
+///     NEW_CAP = "example.new";
+/// }
+///
+/// fn main() {
+///     let _ = OLD_CAP;
+/// }
+/// ```
 #[macro_export]
 macro_rules! define_capabilities {
+    (@configurable) => { false };
+    (@configurable configurable) => { true };
+    (@display $value:expr) => { $crate::capabilities::base::display_name_from_id($value) };
+    (@display $value:expr, $display:literal) => { $display.to_string() };
+    (@versions) => { 1..=1 };
+    (@versions $min:literal ..= $max:literal) => { $min..=$max };
+    // Builds `CapabilityId` one variant at a time, see `@variant`
+    (@enum [$($variants:tt)*]) => {
+        /// Type-safe identifier for capabilities
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        #[cfg_attr(feature = "specta", derive(specta::Type))]
+        #[allow(non_camel_case_types, dead_code, deprecated)]
+        pub enum CapabilityId {
+            $($variants)*
+        }
+    };
+    (@enum [$($variants:tt)*] [$($attrs:tt)*] $name:ident $($rest:tt)*) => {
+        $crate::define_capabilities!(@variant [$($variants)*] [$($attrs)*] $name $($rest)*);
+    };
+    // The constant keeps the full attribute, see `__define_capabilities_deprecated` for the variant
+    (@variant [$($variants:tt)*] [[deprecated $($args:tt)*] $($attrs:tt)*] $($rest:tt)*) => {
+        $crate::__define_capabilities_deprecated!([$($variants)*] [$($args)*] [$($attrs)*] $($rest)*);
+    };
+    (@variant [$($variants:tt)*] [[$($attr:tt)*] $($attrs:tt)*] $($rest:tt)*) => {
+        $crate::define_capabilities!(@variant [$($variants)* #[$($attr)*]] [$($attrs)*] $($rest)*);
+    };
+    (@variant [$($variants:tt)*] [] $name:ident $($rest:tt)*) => {
+        $crate::define_capabilities!(@enum [$($variants)* $name,] $($rest)*);
+    };
     (
         $(
-            $(#[$meta:meta])*
-            $name:ident = $value:expr;
+            $(#[$($attr:tt)*])*
+            $name:ident $(($min:literal ..= $max:literal))? = $value:expr $(, $display:literal $(, $flag:ident)?)?;
         )*
     ) => {
         /// String constant for the capability
         $(
-            $(#[$meta])*
+            $(#[$($attr)*])*
             pub const $name: &str = $value;
         )*
 
-        /// Type-safe identifier for capabilities
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-        #[cfg_attr(feature = "specta", derive(specta::Type))]
-        #[allow(non_camel_case_types)]
-        pub enum CapabilityId {
-            $(
-                $name,
-            )*
-        }
+        $crate::define_capabilities!(@enum [] $([$([$($attr)*])*] $name)*);
 
+        #[allow(dead_code, deprecated)]
         impl CapabilityId {
+            /// Every known capability
+            pub const ALL: &[CapabilityId] = &[$(CapabilityId::$name,)*];
+
+            /// Every known capability, in definition order
+            pub fn all() -> &'static [CapabilityId] {
+                static ALL: &[CapabilityId] = CapabilityId::ALL;
+                ALL
+            }
+
+            /// Number of known capabilities
+            pub fn count() -> usize {
+                Self::all().len()
+            }
+
             /// Returns the Capabilities value, e.g. `REQUIRES_API_KEY` -> `vmm.mod.requires_api_key`
             pub fn as_str(&self) -> &'static str {
                 match self {
@@ -32,6 +91,81 @@ macro_rules! define_capabilities {
                     )*
                 }
             }
+
+            /// Looks up the capability with the given id string
+            pub fn from_id(id: &str) -> Option<CapabilityId> {
+                match id {
+                    $(
+                        $value => Some(CapabilityId::$name),
+                    )*
+                    _ => None,
+                }
+            }
+
+            /// Same as `from_id`, e.g. for ids read from plugin manifests
+            #[allow(clippy::should_implement_trait)]
+            pub fn from_str(s: &str) -> Option<CapabilityId> {
+                Self::from_id(s)
+            }
+
+            pub fn display_name(&self) -> String {
+                match self {
+                    $(
+                        CapabilityId::$name => $crate::define_capabilities!(@display $value $(, $display)?),
+                    )*
+                }
+            }
+
+            /// Whether the capability has user facing settings
+            pub fn configurable(&self) -> bool {
+                match self {
+                    $(
+                        CapabilityId::$name => $crate::define_capabilities!(@configurable $($($flag)?)?),
+                    )*
+                }
+            }
+
+            /// The capability schema versions supported by this build of lib-vmm
+            pub fn supported_versions(&self) -> std::ops::RangeInclusive<u32> {
+                match self {
+                    $(
+                        CapabilityId::$name => $crate::define_capabilities!(@versions $($min ..= $max)?),
+                    )*
+                }
+            }
+
+            pub fn descriptor(&self) -> $crate::capabilities::base::CapabilityDescriptor {
+                $crate::capabilities::base::CapabilityDescriptor {
+                    id: self.as_str().to_string(),
+                    display_name: self.display_name(),
+                    description: None,
+                    configurable: self.configurable(),
+                }
+            }
         }
     };
 }
+
+/// Adds a `deprecated` attribute to a `CapabilityId` variant for `define_capabilities!`.
+///
+/// The specta derive can't handle `deprecated(since = ..)`, with lib-vmm's `specta` feature the
+/// variant is only marked deprecated. Picking the definition here keeps the calling crate's
+/// features out of it.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "specta")]
+macro_rules! __define_capabilities_deprecated {
+    ([$($variants:tt)*] [$($args:tt)*] $($rest:tt)*) => {
+        $crate::define_capabilities!(@variant [$($variants)* #[deprecated]] $($rest)*);
+    };
+}
+
+/// Adds a `deprecated` attribute to a `CapabilityId` variant for `define_capabilities!`
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "specta"))]
+macro_rules! __define_capabilities_deprecated {
+    ([$($variants:tt)*] [$($args:tt)*] $($rest:tt)*) => {
+        $crate::define_capabilities!(@variant [$($variants)* #[deprecated $($args)*]] $($rest)*);
+    };
+}
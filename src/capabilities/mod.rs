@@ -1,6 +1,14 @@
 pub mod api_key_capability;
 pub mod base;
 pub mod builder;
+pub mod configurable_mods_capability;
+pub mod dependencies_capability;
 pub mod form;
+pub mod game_launcher_capability;
 pub mod ids;
 pub mod macros;
+pub mod multiple_files_capability;
+pub mod oauth_capability;
+pub mod profiles_capability;
+pub mod search_capability;
+pub mod update_checker_capability;
@@ -0,0 +1,69 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+
+use crate::{
+    capabilities::{base::Capability, builder::CapabilityError, ids},
+    traits::{
+        discovery::{DiscoveryError, ModFileInfo},
+        mod_provider::ModDownloadResult,
+    },
+};
+
+/// Behavior-only trait (no Capability)
+///
+/// For providers hosting several files per mod (main, optional, patches, old versions).
+/// When a provider doesn't expose this capability the runtime should treat every mod as a
+/// single artifact and call `ModProvider::download_mod` instead of listing files.
+#[async_trait]
+pub trait HasMultipleFiles: Send + Sync {
+    /// Lists the downloadable files of a mod
+    async fn list_files(&self, mod_id: &str) -> Result<Vec<ModFileInfo>, DiscoveryError>;
+
+    /// Downloads a single file, `file_id` comes from `ModFileInfo::file_id`
+    async fn download_file(&self, mod_id: &str, file_id: &str) -> ModDownloadResult;
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct MultipleFilesCapability<T: HasMultipleFiles + Send + Sync + 'static>(Weak<T>);
+
+impl<T: HasMultipleFiles + Send + Sync + 'static> MultipleFilesCapability<T> {
+    /// Creates a new `MultipleFilesCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: HasMultipleFiles + Send + Sync + 'static> Capability for MultipleFilesCapability<T> {
+    fn id(&self) -> &'static str {
+        ids::HAS_MULTIPLE_FILES
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_has_multiple_files(&self) -> Option<&dyn HasMultipleFiles> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
+impl<T: HasMultipleFiles + Send + Sync + 'static> HasMultipleFiles for MultipleFilesCapability<T> {
+    async fn list_files(&self, mod_id: &str) -> Result<Vec<ModFileInfo>, DiscoveryError> {
+        match self.inner() {
+            Ok(p) => p.list_files(mod_id).await,
+            Err(_) => Err(DiscoveryError::ProviderUnavailable),
+        }
+    }
+    async fn download_file(&self, mod_id: &str, file_id: &str) -> ModDownloadResult {
+        match self.inner() {
+            Ok(p) => p.download_file(mod_id, file_id).await,
+            Err(e) => ModDownloadResult::Failed(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::capabilities::{
+    api_key_capability::{ApiKeyValidationError, KeyAction},
+    base::Capability,
+    builder::CapabilityError,
+    ids,
+};
+
+/// How the user should be sent through the provider's authorization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum OAuthPrompt {
+    /// Open `authorization_url` in the browser, the provider will redirect to `redirect_uri`
+    /// which the runtime is expected to listen on.
+    Browser {
+        authorization_url: String,
+        redirect_uri: String,
+        /// Opaque value the redirect must echo back
+        state: Option<String>,
+    },
+    /// Show `user_code` and ask the user to enter it at `verification_uri`.
+    /// The runtime polls `complete_auth` every `interval_secs` until it stops returning `AuthorizationPending`.
+    DeviceCode {
+        device_code: String,
+        user_code: String,
+        verification_uri: String,
+        interval_secs: u64,
+        expires_in_secs: Option<u64>,
+    },
+}
+
+/// What the runtime received while waiting on an `OAuthPrompt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum OAuthCallback {
+    /// The browser was redirected back, with the query parameters of the redirect
+    Redirect { code: String, state: Option<String> },
+    /// A poll for the device code flow
+    DevicePoll { device_code: String },
+}
+
+/// Behavior-only trait (no Capability)
+#[async_trait]
+pub trait RequiresOAuth: Send + Sync {
+    /// Starts the authorization, called when `needs_prompt` returns true.
+    fn begin_auth(&self) -> Result<OAuthPrompt, CapabilityError>;
+
+    /// Exchanges the callback for a token.
+    /// Device code flows should return `Err(ApiKeyValidationError::AuthorizationPending)` until the user confirmed.
+    async fn complete_auth(
+        &self,
+        callback: OAuthCallback,
+    ) -> Result<KeyAction, ApiKeyValidationError>;
+
+    /// Refreshes an expired token, providers without refresh tokens can keep the default,
+    /// which makes the runtime prompt again.
+    async fn refresh_auth(&self) -> Result<KeyAction, ApiKeyValidationError> {
+        Err(ApiKeyValidationError::Other(
+            "Token refresh is not supported".into(),
+        ))
+    }
+
+    /// Whether the UI should start the authorization (e.g. missing or expired token).
+    fn needs_prompt(&self, existing_token: Option<&str>) -> bool;
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct OAuthCapability<T: RequiresOAuth + Send + Sync + 'static>(Weak<T>);
+
+impl<T: RequiresOAuth + Send + Sync + 'static> OAuthCapability<T> {
+    /// Creates a new `OAuthCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: RequiresOAuth + Send + Sync + 'static> Capability for OAuthCapability<T> {
+    fn id(&self) -> &'static str {
+        ids::REQUIRES_OAUTH
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_requires_oauth(&self) -> Option<&dyn RequiresOAuth> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
+impl<T: RequiresOAuth + Send + Sync + 'static> RequiresOAuth for OAuthCapability<T> {
+    fn begin_auth(&self) -> Result<OAuthPrompt, CapabilityError> {
+        self.inner()?.begin_auth()
+    }
+    async fn complete_auth(
+        &self,
+        callback: OAuthCallback,
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        self.inner()?.complete_auth(callback).await
+    }
+    async fn refresh_auth(&self) -> Result<KeyAction, ApiKeyValidationError> {
+        self.inner()?.refresh_auth().await
+    }
+    fn needs_prompt(&self, existing_token: Option<&str>) -> bool {
+        match self.inner() {
+            Ok(p) => p.needs_prompt(existing_token),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::{Arc, Weak};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::capabilities::{base::Capability, builder::CapabilityError, ids};
+
+/// A named mod loadout of a game, e.g. "Vanilla + QoL"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProfileInfo {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ProfileError {
+    #[error("Profile {0} was not found")]
+    NotFound(String),
+    #[error("A profile named {0} already exists")]
+    AlreadyExists(String),
+    #[error("Invalid profile name: {0}")]
+    InvalidName(String),
+    #[error("Profile {0} is active and cannot be deleted")]
+    ProfileActive(String),
+    #[error("Failed to switch profiles: {0}")]
+    Io(String),
+    #[error("The provider was dropped before the profile could be changed.")]
+    ProviderDropped,
+}
+
+impl From<std::io::Error> for ProfileError {
+    fn from(value: std::io::Error) -> Self {
+        ProfileError::Io(value.to_string())
+    }
+}
+
+impl From<CapabilityError> for ProfileError {
+    fn from(value: CapabilityError) -> Self {
+        match value {
+            CapabilityError::ProviderDropped => ProfileError::ProviderDropped,
+            e @ (CapabilityError::Network(_)
+            | CapabilityError::Timeout(_)
+            | CapabilityError::DuplicateCapability(_)) => ProfileError::Io(e.to_string()),
+        }
+    }
+}
+
+/// Behavior-only trait (no Capability)
+///
+/// For games supporting multiple mod loadouts, e.g. by swapping the mods folder with
+/// `archive::helpers::replace_symlink_dir`.
+pub trait SupportsProfiles: Send + Sync {
+    fn list_profiles(&self) -> Vec<ProfileInfo>;
+
+    fn create_profile(&self, name: &str) -> Result<ProfileInfo, ProfileError>;
+
+    /// Switches the game to the given profile
+    fn activate_profile(&self, id: &str) -> Result<(), ProfileError>;
+
+    /// Deletes a profile, the active profile cannot be deleted
+    fn delete_profile(&self, id: &str) -> Result<(), ProfileError>;
+
+    fn active_profile(&self) -> Option<ProfileInfo>;
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct ProfilesCapability<T: SupportsProfiles + Send + Sync + 'static>(Weak<T>);
+
+impl<T: SupportsProfiles + Send + Sync + 'static> ProfilesCapability<T> {
+    /// Creates a new `ProfilesCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: SupportsProfiles + Send + Sync + 'static> Capability for ProfilesCapability<T> {
+    fn id(&self) -> &'static str {
+        ids::SUPPORTS_PROFILES
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_supports_profiles(&self) -> Option<&dyn SupportsProfiles> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+impl<T: SupportsProfiles + Send + Sync + 'static> SupportsProfiles for ProfilesCapability<T> {
+    fn list_profiles(&self) -> Vec<ProfileInfo> {
+        self.inner().map(|p| p.list_profiles()).unwrap_or_default()
+    }
+    fn create_profile(&self, name: &str) -> Result<ProfileInfo, ProfileError> {
+        self.inner()?.create_profile(name)
+    }
+    fn activate_profile(&self, id: &str) -> Result<(), ProfileError> {
+        self.inner()?.activate_profile(id)
+    }
+    fn delete_profile(&self, id: &str) -> Result<(), ProfileError> {
+        self.inner()?.delete_profile(id)
+    }
+    fn active_profile(&self) -> Option<ProfileInfo> {
+        self.inner().ok().and_then(|p| p.active_profile())
+    }
+}
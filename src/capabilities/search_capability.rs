@@ -0,0 +1,11 @@
+use crate::{capabilities::ids, capability};
+
+/// Marker trait for providers that apply `DiscoveryQuery::search` server-side.
+///
+/// Without it `Context::discover_mods` filters the results by the search text itself.
+pub trait SupportsFullTextSearch: Send + Sync {}
+
+/// Capability declaring `SupportsFullTextSearch`, it has no behavior to delegate
+pub struct FullTextSearchCapability;
+
+capability!(FullTextSearchCapability, ids::FULL_TEXT_SEARCH);
@@ -0,0 +1,57 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+
+use crate::{
+    capabilities::{base::Capability, builder::CapabilityError, ids},
+    traits::discovery::{DiscoveryError, InstalledMod, UpdateInfo},
+};
+
+/// Behavior-only trait (no Capability)
+#[async_trait]
+pub trait ChecksForUpdates: Send + Sync {
+    /// Returns an entry for every mod in `installed` that has a newer version,
+    /// mods that are up to date or unknown to the provider are left out
+    async fn check_updates(
+        &self,
+        installed: &[InstalledMod],
+    ) -> Result<Vec<UpdateInfo>, DiscoveryError>;
+}
+
+/// Wrapper giving this behavior a concrete Capability
+pub struct UpdateCheckerCapability<T: ChecksForUpdates + Send + Sync + 'static>(Weak<T>);
+
+impl<T: ChecksForUpdates + Send + Sync + 'static> UpdateCheckerCapability<T> {
+    /// Creates a new `UpdateCheckerCapability`, that wraps a given weak refrence
+    pub fn new(inner: Weak<T>) -> Self {
+        Self(inner)
+    }
+
+    /// Obtain a strong `Arc` refrence to the underlying provider if it still exists.
+    pub fn inner(&self) -> Result<Arc<T>, CapabilityError> {
+        self.0.upgrade().ok_or(CapabilityError::ProviderDropped)
+    }
+}
+
+impl<T: ChecksForUpdates + Send + Sync + 'static> Capability for UpdateCheckerCapability<T> {
+    fn id(&self) -> &'static str {
+        ids::UPDATE_CHECKER
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_checks_for_updates(&self) -> Option<&dyn ChecksForUpdates> {
+        Some(self)
+    }
+}
+
+/// Delegate back to underlying behvaior for ergonomics
+#[async_trait]
+impl<T: ChecksForUpdates + Send + Sync + 'static> ChecksForUpdates for UpdateCheckerCapability<T> {
+    async fn check_updates(
+        &self,
+        installed: &[InstalledMod],
+    ) -> Result<Vec<UpdateInfo>, DiscoveryError> {
+        self.inner()?.check_updates(installed).await
+    }
+}
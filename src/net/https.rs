@@ -1,15 +1,31 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use reqwest::header::{CONTENT_TYPE, USER_AGENT};
+use reqwest::{
+    StatusCode,
+    header::{CONTENT_TYPE, RETRY_AFTER, USER_AGENT},
+};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::{capabilities::builder::CapabilityError, traits::discovery::DiscoveryError};
+
+/// Sent with every request of the clients in this crate
+pub const DEFAULT_USER_AGENT: &str =
+    "VoidModManager/0.1.0 (+https://github.com/void-mod-manager/app)";
+
 #[derive(Error, Debug)]
 pub enum HttpError {
     #[error("network: {0}")]
     Network(String),
+    #[error("rate limited")]
+    RateLimit { retry_after_secs: Option<u64> },
+    #[error("timed out after {elapsed_ms}ms")]
+    Timeout { elapsed_ms: u64 },
     #[error("parse json: {0}")]
     Parse(String),
     #[error("schema mismatch: {0}")]
@@ -18,6 +34,30 @@ pub enum HttpError {
     Internal(String),
 }
 
+impl From<HttpError> for DiscoveryError {
+    fn from(value: HttpError) -> Self {
+        match value {
+            HttpError::Network(e) => DiscoveryError::Network(e),
+            HttpError::RateLimit { retry_after_secs } => {
+                DiscoveryError::RateLimit { retry_after_secs }
+            }
+            HttpError::Timeout { elapsed_ms } => DiscoveryError::Timeout { elapsed_ms },
+            e => DiscoveryError::Internal(e.to_string()),
+        }
+    }
+}
+
+/// Everything but timeouts is reported as `Network`, with the `HttpError` as message
+impl From<HttpError> for CapabilityError {
+    fn from(value: HttpError) -> Self {
+        match value {
+            HttpError::Network(e) => CapabilityError::Network(e),
+            HttpError::Timeout { elapsed_ms } => CapabilityError::Timeout(elapsed_ms),
+            e => CapabilityError::Network(e.to_string()),
+        }
+    }
+}
+
 #[async_trait]
 pub trait ProviderHttpClient: Send + Sync {
     async fn get_json(&self, url: &str) -> Result<Value, HttpError>;
@@ -56,23 +96,38 @@ impl ReqwestProviderHttpClient {
 #[async_trait]
 impl ProviderHttpClient for ReqwestProviderHttpClient {
     async fn get_json(&self, url: &str) -> Result<Value, HttpError> {
+        let started = Instant::now();
+        let network_error = |e: reqwest::Error| {
+            if e.is_timeout() {
+                HttpError::Timeout {
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                }
+            } else {
+                HttpError::Network(e.to_string())
+            }
+        };
+
         let resp = self
             .client
             .get(url)
-            .header(
-                USER_AGENT,
-                "VoidModManager/0.1.0 (+https://github.com/void-mod-manager/app)",
-            )
+            .header(USER_AGENT, DEFAULT_USER_AGENT)
             .header(CONTENT_TYPE, "application/json")
             .send()
             .await
-            .map_err(|e| HttpError::Network(e.to_string()))?;
+            .map_err(network_error)?;
 
         let status = resp.status();
-        let text = resp
-            .text()
-            .await
-            .map_err(|e| HttpError::Network(e.to_string()))?;
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            // Only the delay-seconds form of Retry-After is supported, not HTTP dates
+            let retry_after_secs = resp
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok());
+            return Err(HttpError::RateLimit { retry_after_secs });
+        }
+
+        let text = resp.text().await.map_err(network_error)?;
 
         if !status.is_success() {
             return Err(HttpError::Network(format!(
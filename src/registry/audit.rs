@@ -0,0 +1,60 @@
+use std::{fmt::Display, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::model::ProviderSource;
+
+/// Most events a `ContextBuilder` keeps, older ones are dropped first
+pub const MAX_REGISTRY_EVENTS: usize = 1024;
+
+/// A registration call on a `ContextBuilder`, see `ContextBuilder::events`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RegistryEvent {
+    pub timestamp: SystemTime,
+    pub action: RegistryAction,
+    /// The id as passed in, `alias -> target` for aliases and the namespace for reservations
+    pub id: String,
+    /// `None` for aliases and reservations
+    pub source: Option<ProviderSource>,
+    pub outcome: RegistryOutcome,
+}
+
+impl RegistryEvent {
+    pub fn new<T, E: Display>(
+        action: RegistryAction,
+        id: impl Into<String>,
+        source: Option<&ProviderSource>,
+        result: &Result<T, E>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            action,
+            id: id.into(),
+            source: source.cloned(),
+            outcome: match result {
+                Ok(_) => RegistryOutcome::Ok,
+                Err(e) => RegistryOutcome::Failed(e.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum RegistryAction {
+    RegisterModProvider,
+    RegisterLazyModProvider,
+    RegisterGame,
+    AliasProvider,
+    AliasGame,
+    ReserveNamespace,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum RegistryOutcome {
+    Ok,
+    /// The `Display` string of the error
+    Failed(String),
+}
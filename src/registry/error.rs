@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::registry::id::suggest_ids;
+
 /// Error types for the registry
 #[derive(Error, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
@@ -13,6 +15,59 @@ pub enum RegistryError {
     GameAlreadyExists(String),
     #[error("Cannot use reserved identifier 'core' for non-core implementations ({0})")]
     ReservedCoreId(String),
+    #[error("Namespace '{namespace}' is reserved, cannot register {id}")]
+    ReservedNamespace { namespace: String, id: String },
+    #[error("Cannot alias {alias} to {target}, which is an alias itself")]
+    AliasChain { alias: String, target: String },
     #[error("Cannot find id {0}")]
     NotFound(String),
+    /// The provider id followed by the games using it, e.g. `mod:p (used by game-a, game-b)`
+    #[error("Mod provider is still in use: {0}")]
+    ProviderInUse(String),
+    #[error("Cannot find id {id}, did you mean {}?", .suggestions.join(", "))]
+    NotFoundWithSuggestions {
+        id: String,
+        suggestions: Vec<String>,
+    },
+    #[error("Provider {id} is implemented by {expected}, not {found}")]
+    ProviderMismatch {
+        id: String,
+        expected: String,
+        found: String,
+    },
+    /// A mod provider whose `Provider::id()` differs from the id it is registered with
+    #[error("Provider registered as {registered} reports id {provider_id}")]
+    IdMismatch {
+        registered: String,
+        provider_id: String,
+    },
+    #[error("Game {game} requires capability {capability}, which mod provider {provider} lacks")]
+    MissingRequiredCapability {
+        game: String,
+        provider: String,
+        capability: String,
+    },
+    #[error("Failed to initialize provider {id}: {message}")]
+    ProviderInitFailed { id: String, message: String },
+    #[error("Provider validation failed: {0}")]
+    ValidationFailed(String),
+    /// Returned by the active game shortcuts of `Context` while no game is active
+    #[error("No game is active")]
+    NoActiveGame,
+    #[error("Mod provider error: {0}")]
+    ModProviderError(String),
+    #[error("Game provider error: {0}")]
+    GameProviderError(String),
+}
+
+impl RegistryError {
+    /// `NotFound`, or `NotFoundWithSuggestions` if some of the `known` ids are close to `id`
+    pub fn not_found<'a>(id: String, known: impl IntoIterator<Item = &'a str>) -> Self {
+        let suggestions = suggest_ids(&id, known);
+        if suggestions.is_empty() {
+            RegistryError::NotFound(id)
+        } else {
+            RegistryError::NotFoundWithSuggestions { id, suggestions }
+        }
+    }
 }
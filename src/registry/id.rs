@@ -3,37 +3,212 @@ use crate::registry::RegistryError;
 // Normalization rules
 //  - lowercase
 //  - one optional colon seperating namespace and rest
-//  - allowed chars: a-z 0-9 . _ -
-//  - 1..200 length
+//  - allowed chars: a-z 0-9 . _ -, anything else (including non-ASCII letters) is rejected
+//  - 1..=200 chars
+/// Longest allowed id, in chars
+const MAX_ID_LEN: usize = 200;
+
+/// Rules for `normalize_id_with_config`, the default ones are those of `normalize_id`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdConfig {
+    /// Longest allowed id, in chars
+    pub max_len: usize,
+    pub allow_dots: bool,
+    pub allow_hyphens: bool,
+}
+
+impl Default for IdConfig {
+    fn default() -> Self {
+        Self {
+            max_len: MAX_ID_LEN,
+            allow_dots: true,
+            allow_hyphens: true,
+        }
+    }
+}
+
+impl IdConfig {
+    fn allows(&self, ch: char) -> bool {
+        match ch {
+            'a'..='z' | '0'..='9' | '_' => true,
+            '.' => self.allow_dots,
+            '-' => self.allow_hyphens,
+            _ => false,
+        }
+    }
+
+    /// The allowed chars, for error messages
+    fn describe(&self) -> String {
+        let mut allowed = vec!["a-z", "0-9"];
+        if self.allow_dots {
+            allowed.push("'.'");
+        }
+        allowed.push("'_'");
+        if self.allow_hyphens {
+            allowed.push("'-'");
+        }
+        format!("only {} and one ':' are allowed", allowed.join(", "))
+    }
+}
+
 /// Helper function to normalize IDs before usage.
+///
+/// Errors name the offending character and its char position in the trimmed id. For ids with
+/// accented letters, combining marks or whitespace they suggest an ASCII candidate.
 pub fn normalize_id(raw: &str) -> Result<String, RegistryError> {
-    let s = raw.trim().to_lowercase();
-    if s.is_empty() || s.len() > 200 {
+    normalize_id_with_config(raw, &IdConfig::default())
+}
+
+/// `normalize_id` with other rules, e.g. a shorter limit to fit a database column
+pub fn normalize_id_with_config(raw: &str, config: &IdConfig) -> Result<String, RegistryError> {
+    let trimmed = raw.trim();
+    let len = trimmed.chars().count();
+    if len == 0 {
+        return Err(RegistryError::InvalidId("ID is empty.".to_string()));
+    }
+    if len > config.max_len {
+        return Err(RegistryError::InvalidId(format!(
+            "ID '{}' is longer than {} characters.",
+            raw, config.max_len
+        )));
+    }
+
+    if let Some((i, ch)) = find_invalid_char(trimmed, config) {
+        let hint = match ascii_candidate(trimmed, config) {
+            Some(candidate) => format!("try '{}'", candidate),
+            None => config.describe(),
+        };
         return Err(RegistryError::InvalidId(format!(
-            "ID '{}' is invalid.",
-            raw
+            "ID: '{}' contains invalid character '{}' at position {}, {}",
+            raw, ch, i, hint
         )));
     }
+    Ok(trimmed.to_ascii_lowercase())
+}
 
+/// The first char not allowed in an id and its char position
+fn find_invalid_char(id: &str, config: &IdConfig) -> Option<(usize, char)> {
+    let len = id.chars().count();
     let mut seen_colon = false;
-    for (i, ch) in s.chars().enumerate() {
-        match ch {
-            'a'..='z' | '0'..='9' | '.' | '_' | '-' => {}
-            ':' if !seen_colon && i > 0 && i < s.len() - 1 => {
+    for (i, ch) in id.chars().enumerate() {
+        match ch.to_ascii_lowercase() {
+            c if config.allows(c) => {}
+            ':' if !seen_colon && i > 0 && i < len - 1 => {
                 seen_colon = true;
             }
-            _ => {
-                return Err(RegistryError::InvalidId(format!(
-                    "ID: '{}' contains invalid character '{}' at position {}",
-                    raw, ch, i
-                )));
-            }
+            _ => return Some((i, ch)),
+        }
+    }
+    None
+}
+
+/// `id` with accents and combining marks stripped, whitespace replaced by `-` and disallowed
+/// separators by `_`, if that gives a valid id
+fn ascii_candidate(id: &str, config: &IdConfig) -> Option<String> {
+    let mut candidate = String::new();
+    for ch in id.chars().flat_map(char::to_lowercase) {
+        match ch {
+            c if config.allows(c) || c == ':' => candidate.push(c),
+            '.' | '-' => candidate.push('_'),
+            c if c.is_whitespace() => candidate.push(if config.allow_hyphens { '-' } else { '_' }),
+            c => candidate.push_str(fold_diacritic(c).unwrap_or_default()),
         }
     }
-    Ok(s)
+    let valid = !candidate.is_empty()
+        && candidate.len() <= config.max_len
+        && find_invalid_char(&candidate, config).is_none();
+    valid.then_some(candidate)
+}
+
+/// ASCII spelling of a lowercase Latin letter with a diacritic, `None` for combining marks and
+/// anything else
+fn fold_diacritic(ch: char) -> Option<&'static str> {
+    Some(match ch {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ð' | 'ď' | 'đ' => "d",
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
 }
 
 /// Helper function to check if an ID belongs to core or not
 pub fn is_core_id(id: &str) -> bool {
-    id.starts_with("core:")
+    is_in_namespace(id, "core")
+}
+
+/// Whether the part of a (normalized) id before the colon is `ns`
+pub fn is_in_namespace(id: &str, ns: &str) -> bool {
+    get_namespace(id) == Some(ns)
+}
+
+/// Whether an id is namespaced by something other than `core`
+pub fn is_plugin_id(id: &str) -> bool {
+    get_namespace(id).is_some_and(|ns| ns != "core")
+}
+
+/// The part of an id before the colon, `None` for ids without one
+pub fn get_namespace(id: &str) -> Option<&str> {
+    id.split_once(':').map(|(ns, _)| ns)
+}
+
+/// The part of an id after the colon, or the whole id without one
+pub fn get_local_name(id: &str) -> &str {
+    id.split_once(':').map_or(id, |(_, name)| name)
+}
+
+/// Edit distance between two ids, counted in chars
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Largest edit distance, relative to the longer id, for an id to be suggested
+const SUGGESTION_THRESHOLD: f64 = 0.4;
+
+/// Returns up to three of the `known` ids closest to `id`, closest first.
+/// Ids differing in more than 40% of their characters aren't suggested.
+pub fn suggest_ids<'a>(id: &str, known: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut scored: Vec<(f64, &str)> = known
+        .into_iter()
+        .filter_map(|candidate| {
+            let len = id.chars().count().max(candidate.chars().count()).max(1);
+            let score = levenshtein(id, candidate) as f64 / len as f64;
+            (score <= SUGGESTION_THRESHOLD).then_some((score, candidate))
+        })
+        .collect();
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(3)
+        .map(|(_, c)| c.to_string())
+        .collect()
 }
@@ -0,0 +1,121 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    capabilities::base::CapabilityRef,
+    registry::{RegistryError, model::ProviderSource},
+    traits::{
+        game_provider::{GameIcon, GameInstallError, GameMetadata, GameProvider},
+        provider::Provider,
+    },
+};
+
+/// Games declared by a plugin in a data file (JSON, TOML, ...) instead of in Rust,
+/// see `ContextBuilder::register_from_manifest`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProviderManifest {
+    #[serde(default)]
+    pub games: Vec<ManifestGame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ManifestGame {
+    pub id: String,
+    pub display_name: String,
+    pub short_name: String,
+    /// Path of the icon, see `GameIcon::Path`
+    pub icon: String,
+    pub mod_provider_id: String,
+    #[serde(default)]
+    pub external_id: Option<String>,
+}
+
+/// A manifest entry that couldn't be registered
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Manifest game #{index} ({game_id}): {error}")]
+pub struct ManifestError {
+    /// Position of the game in `ProviderManifest::games`
+    pub index: usize,
+    pub game_id: String,
+    #[source]
+    pub error: RegistryError,
+}
+
+/// Installs a mod archive into a manifest game, called with the game id
+pub type ManifestInstallHandler =
+    Arc<dyn Fn(&str, &Path) -> Result<(), GameInstallError> + Send + Sync>;
+
+/// A `GameProvider` backed by a `ManifestGame`.
+///
+/// Without an install handler installs fail with `GameInstallError::Unsupported`.
+pub struct ManifestGameProvider {
+    game: ManifestGame,
+    source: ProviderSource,
+    install_handler: Option<ManifestInstallHandler>,
+    caps: Vec<CapabilityRef>,
+}
+
+impl ManifestGameProvider {
+    pub fn new(game: ManifestGame, source: ProviderSource) -> Self {
+        Self {
+            game,
+            source,
+            install_handler: None,
+            caps: Vec::new(),
+        }
+    }
+
+    pub fn with_install_handler(mut self, handler: ManifestInstallHandler) -> Self {
+        self.install_handler = Some(handler);
+        self
+    }
+}
+
+impl Provider for ManifestGameProvider {
+    fn id(&self) -> &'static str {
+        "manifest.game"
+    }
+    fn display_name(&self) -> Option<&str> {
+        Some(&self.game.display_name)
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &self.caps
+    }
+}
+
+impl GameProvider for ManifestGameProvider {
+    fn game_id(&self) -> &str {
+        &self.game.id
+    }
+    fn mod_provider_id(&self) -> &str {
+        &self.game.mod_provider_id
+    }
+    fn metadata(&self) -> GameMetadata {
+        GameMetadata {
+            id: self.game.id.clone(),
+            display_name: self.game.display_name.clone(),
+            short_name: self.game.short_name.clone(),
+            icon: GameIcon::Path(self.game.icon.clone()),
+            provider_source: self.source.clone(),
+            version: None,
+            executable_path: None,
+            install_path: None,
+        }
+    }
+    fn get_external_id(&self) -> &str {
+        self.game.external_id.as_deref().unwrap_or_default()
+    }
+    fn install_mod(&self, path: &Path) -> Result<(), GameInstallError> {
+        match &self.install_handler {
+            Some(handler) => handler(&self.game.id, path),
+            None => Err(GameInstallError::Unsupported(format!(
+                "{} is declared in a manifest and has no install handler",
+                self.game.id
+            ))),
+        }
+    }
+}
@@ -1,7 +1,12 @@
+pub mod audit;
 pub mod error;
 pub mod id;
+pub mod manifest;
 pub mod model;
+pub mod validation;
 
+pub use audit::*;
 pub use error::*;
 pub use id::*;
 pub use model::*;
+pub use validation::*;
@@ -1,25 +1,353 @@
-use std::sync::Arc;
+use std::{
+    cmp::Ordering,
+    fmt,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::SystemTime,
+};
 
 use serde::{Deserialize, Serialize};
 
-use crate::traits::{game_provider::GameProvider, mod_provider::ModProvider};
+use crate::traits::{
+    game_provider::{GameMetadata, GameProvider},
+    mod_provider::ModProvider,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a plugin was installed from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum PluginOrigin {
+    /// Shipped with the host application
+    Bundled,
+    #[default]
+    UserInstalled,
+    Remote {
+        url: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum ProviderSource {
     Core,
-    Plugin(String), // pluginId/Name
+    Plugin {
+        id: String,
+        version: Option<String>,
+        origin: PluginOrigin,
+    },
+}
+
+impl ProviderSource {
+    /// A user installed plugin without a known version
+    pub fn plugin(id: impl Into<String>) -> Self {
+        ProviderSource::Plugin {
+            id: id.into(),
+            version: None,
+            origin: PluginOrigin::default(),
+        }
+    }
+}
+
+/// Also accepts the bare plugin id of `Plugin` used before 0.3.0
+#[derive(Deserialize)]
+enum ProviderSourceRepr {
+    Core,
+    Plugin(PluginRepr),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PluginRepr {
+    Legacy(String),
+    Full {
+        id: String,
+        #[serde(default)]
+        version: Option<String>,
+        #[serde(default)]
+        origin: PluginOrigin,
+    },
+}
+
+impl<'de> Deserialize<'de> for ProviderSource {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ProviderSourceRepr::deserialize(deserializer)? {
+            ProviderSourceRepr::Core => ProviderSource::Core,
+            ProviderSourceRepr::Plugin(PluginRepr::Legacy(id)) => ProviderSource::plugin(id),
+            ProviderSourceRepr::Plugin(PluginRepr::Full {
+                id,
+                version,
+                origin,
+            }) => ProviderSource::Plugin {
+                id,
+                version,
+                origin,
+            },
+        })
+    }
+}
+
+/// Which sources may register ids in a namespace, see `ContextBuilder::reserve_namespace`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ProviderSourceMatcher {
+    Core,
+    /// The plugin with the given id
+    Plugin(String),
+    /// Any bundled plugin, see `PluginOrigin::Bundled`
+    BundledPlugins,
 }
 
+impl ProviderSourceMatcher {
+    pub fn matches(&self, source: &ProviderSource) -> bool {
+        match (self, source) {
+            (ProviderSourceMatcher::Core, ProviderSource::Core) => true,
+            (ProviderSourceMatcher::Plugin(expected), ProviderSource::Plugin { id, .. }) => {
+                expected == id
+            }
+            (
+                ProviderSourceMatcher::BundledPlugins,
+                ProviderSource::Plugin {
+                    origin: PluginOrigin::Bundled,
+                    ..
+                },
+            ) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Extra settings for `ContextBuilder::register_mod_provider_with`
+#[derive(Debug, Clone, Default)]
+pub struct ProviderOptions {
+    /// Providers with a higher priority are listed and used first, defaults to 0
+    pub priority: i32,
+}
+
+#[derive(Clone)]
 pub struct ProviderEntry {
     pub id: String,
     pub source: ProviderSource,
     pub provider: Arc<dyn ModProvider>,
+    pub priority: i32,
+    /// `Provider::display_name` at registration
+    pub display_name: Option<String>,
+    /// When the provider was registered, for lazy providers when they were registered rather
+    /// than constructed
+    pub registered_at: SystemTime,
+    /// `Provider::version` at registration
+    pub version: Option<String>,
+}
+
+impl ProviderEntry {
+    /// Priority descending, then id ascending
+    pub fn cmp_order(&self, other: &ProviderEntry) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// Builds a lazily registered mod provider, errors are reported as `RegistryError::ProviderInitFailed`
+pub type ProviderFactory =
+    Box<dyn FnOnce() -> Result<Arc<dyn ModProvider + Send + Sync>, String> + Send>;
+
+/// A mod provider constructed on first use, see `ContextBuilder::register_mod_provider_lazy`
+pub struct LazyProviderEntry {
+    pub id: String,
+    pub source: ProviderSource,
+    pub priority: i32,
+    pub registered_at: SystemTime,
+    factory: Mutex<Option<ProviderFactory>>,
+    provider: OnceLock<Result<Arc<dyn ModProvider>, String>>,
 }
 
+impl LazyProviderEntry {
+    pub fn new(
+        id: String,
+        source: ProviderSource,
+        priority: i32,
+        factory: ProviderFactory,
+    ) -> Self {
+        Self {
+            id,
+            source,
+            priority,
+            registered_at: SystemTime::now(),
+            factory: Mutex::new(Some(factory)),
+            provider: OnceLock::new(),
+        }
+    }
+
+    /// Runs the factory on the first call, later and concurrent calls get the same result
+    pub fn get_or_init(&self) -> Result<Arc<dyn ModProvider>, String> {
+        self.provider
+            .get_or_init(|| {
+                let factory = self
+                    .factory
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .take()
+                    .ok_or_else(|| "Provider factory panicked".to_string())?;
+                factory().map(|p| p as Arc<dyn ModProvider>)
+            })
+            .clone()
+    }
+}
+
+#[derive(Clone)]
 pub struct GameEntry {
     pub id: String,
     pub source: ProviderSource,
     pub game: Arc<dyn GameProvider + Send + Sync>,
+    /// The primary provider comes first
+    pub required_provider_ids: Vec<String>,
+}
+
+/// A registered mod provider with its implementation, see `Context::get_provider_entry`.
+/// Serializes without the provider.
+#[derive(Clone, Serialize)]
+pub struct ProviderEntryView {
+    pub id: String,
+    pub source: ProviderSource,
+    pub capability_ids: Vec<String>,
+    pub priority: i32,
+    pub display_name: Option<String>,
+    pub registered_at: SystemTime,
+    pub version: Option<String>,
+    #[serde(skip)]
+    provider: Arc<dyn ModProvider>,
+}
+
+impl ProviderEntryView {
+    pub fn new(entry: &ProviderEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            source: entry.source.clone(),
+            capability_ids: entry
+                .provider
+                .capabilities()
+                .iter()
+                .map(|c| c.id().to_string())
+                .collect(),
+            priority: entry.priority,
+            display_name: entry.display_name.clone(),
+            registered_at: entry.registered_at,
+            version: entry.version.clone(),
+            provider: Arc::clone(&entry.provider),
+        }
+    }
+
+    pub fn provider(&self) -> &Arc<dyn ModProvider> {
+        &self.provider
+    }
+}
+
+impl fmt::Display for ProviderEntryView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.display_name {
+            Some(name) => write!(f, "{} \"{}\" ({:?})", self.id, name, self.source),
+            None => write!(f, "{} ({:?})", self.id, self.source),
+        }
+    }
+}
+
+/// A registered game with its provider, see `Context::get_game_entry`.
+/// Serializes without the provider.
+#[derive(Clone, Serialize)]
+pub struct GameEntryView {
+    pub id: String,
+    pub source: ProviderSource,
+    /// The primary mod provider of the game
+    pub required_provider_id: String,
+    /// The primary provider comes first
+    pub required_provider_ids: Vec<String>,
+    pub metadata: GameMetadata,
+    #[serde(skip)]
+    game: Arc<dyn GameProvider>,
+}
+
+impl GameEntryView {
+    pub fn new(entry: &GameEntry, metadata: GameMetadata) -> Self {
+        Self {
+            id: entry.id.clone(),
+            source: entry.source.clone(),
+            required_provider_id: entry.required_provider_ids[0].clone(),
+            required_provider_ids: entry.required_provider_ids.clone(),
+            metadata,
+            game: Arc::clone(&entry.game) as Arc<dyn GameProvider>,
+        }
+    }
+
+    pub fn game(&self) -> &Arc<dyn GameProvider> {
+        &self.game
+    }
+}
+
+impl fmt::Display for GameEntryView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({:?}) -> Depends on {}",
+            self.id,
+            self.source,
+            self.required_provider_ids.join(", ")
+        )
+    }
+}
+
+/// A registered mod provider, as listed by `Context::list_mod_providers_detailed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProviderDescriptor {
+    pub id: String,
+    pub source: ProviderSource,
+    pub capability_ids: Vec<String>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// A registered game, as listed by `Context::list_games_detailed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct GameDescriptor {
+    pub id: String,
+    pub source: ProviderSource,
+    /// The primary provider comes first
+    pub required_provider_ids: Vec<String>,
+    pub metadata: GameMetadata,
+}
+
+/// Serializable overview of a `Context`, see `Context::snapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ContextSnapshot {
+    pub mod_providers: Vec<ProviderSnapshot>,
+    pub games: Vec<GameSnapshot>,
+    pub active_game: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProviderSnapshot {
+    pub id: String,
+    pub source: ProviderSource,
+    pub capability_ids: Vec<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Seconds since the Unix epoch
+    #[serde(default)]
+    pub registered_at: u64,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct GameSnapshot {
+    pub id: String,
+    pub source: ProviderSource,
+    /// The primary mod provider of the game
     pub required_provider_id: String,
 }
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::registry::model::ProviderSource;
+
+/// A likely mistake in the registered providers, see `ContextBuilder::freeze_validated`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ValidationWarning {
+    /// No game depends on the mod provider
+    OrphanProvider(String),
+    /// The primary provider of a game lacks a capability from
+    /// `GameProvider::required_mod_provider_capabilities`
+    MissingCapability {
+        game: String,
+        provider: String,
+        capability: String,
+    },
+    /// A core provider outside of the `core:` namespace
+    NamespaceMismatch { id: String, source: ProviderSource },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ValidationReport {
+    pub warnings: Vec<ValidationWarning>,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
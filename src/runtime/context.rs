@@ -1,208 +1,2285 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+    any::Any,
+    collections::{HashMap, hash_map::Entry},
+    path::Path,
+    sync::{
+        Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::join_all;
+use tokio::{
+    sync::{broadcast, watch},
+    task::JoinHandle,
 };
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use crate::{
+    capabilities::{
+        base::{
+            Capability, CapabilityMismatch, CapabilityRef, CompatibilityReport,
+            SupportedCapabilities,
+        },
+        ids,
+    },
     registry::{
         RegistryError,
-        id::normalize_id,
-        model::{GameEntry, ProviderEntry, ProviderSource},
+        audit::{MAX_REGISTRY_EVENTS, RegistryAction, RegistryEvent},
+        id::{is_core_id, is_in_namespace, normalize_id},
+        manifest::{
+            ManifestError, ManifestGame, ManifestGameProvider, ManifestInstallHandler,
+            ProviderManifest,
+        },
+        model::{
+            ContextSnapshot, GameDescriptor, GameEntry, GameEntryView, GameSnapshot,
+            LazyProviderEntry, ProviderDescriptor, ProviderEntry, ProviderEntryView,
+            ProviderFactory, ProviderOptions, ProviderSnapshot, ProviderSource,
+            ProviderSourceMatcher,
+        },
+        validation::{ValidationReport, ValidationWarning},
+    },
+    runtime::{
+        events::ContextEvent,
+        settings::{InMemorySettingsStore, SettingsStore},
+        shutdown::{SHUTDOWN_TIMEOUT, ShutdownReport},
+        state::{RestoreReport, RuntimeState},
     },
     traits::{
-        discovery::ModExtendedMetadata,
-        game_provider::{GameMetadata, GameProvider},
+        discovery::{DiscoveryError, DiscoveryQuery, DiscoveryResult, ModExtendedMetadata},
+        game_provider::{
+            GameInstallError, GameLaunchHandle, GameMetadata, GameProvider, GameVerifyReport,
+            InstalledModInfo,
+        },
         mod_provider::ModProvider,
+        provider::ProviderHealthStatus,
     },
 };
 
+/// Capacity of the event channel created by `ContextBuilder::freeze` if none was attached
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
 #[derive(Default)]
 pub struct ContextBuilder {
     mod_providers: HashMap<String, ProviderEntry>,
+    lazy_providers: HashMap<String, Arc<LazyProviderEntry>>,
     games: HashMap<String, GameEntry>,
+    /// Alias -> id of the registered mod provider
+    provider_aliases: HashMap<String, String>,
+    /// Alias -> id of the registered game
+    game_aliases: HashMap<String, String>,
+    /// Namespaces besides `core` only the matching sources may register ids in
+    reserved_namespaces: Vec<(String, ProviderSourceMatcher)>,
+    supported_capabilities: SupportedCapabilities,
+    events: Option<broadcast::Sender<ContextEvent>>,
+    settings: Option<Arc<dyn SettingsStore>>,
+    /// See `events`
+    registry_events: Vec<RegistryEvent>,
 }
 
 impl ContextBuilder {
     pub fn new() -> Self {
         Self {
             mod_providers: HashMap::new(),
+            lazy_providers: HashMap::new(),
             games: HashMap::new(),
+            provider_aliases: HashMap::new(),
+            game_aliases: HashMap::new(),
+            reserved_namespaces: Vec::new(),
+            supported_capabilities: SupportedCapabilities::default(),
+            events: None,
+            settings: None,
+            registry_events: Vec::new(),
+        }
+    }
+
+    /// Every registration, alias and namespace reservation call so far, oldest first,
+    /// including failed ones. Only the latest `MAX_REGISTRY_EVENTS` are kept.
+    pub fn events(&self) -> &[RegistryEvent] {
+        &self.registry_events
+    }
+
+    fn record<T, E: std::fmt::Display>(
+        &mut self,
+        action: RegistryAction,
+        id: &str,
+        source: Option<&ProviderSource>,
+        result: &Result<T, E>,
+    ) {
+        self.registry_events
+            .push(RegistryEvent::new(action, id, source, result));
+        self.truncate_events();
+    }
+
+    /// Drops the oldest events beyond `MAX_REGISTRY_EVENTS`
+    fn truncate_events(&mut self) {
+        let excess = self
+            .registry_events
+            .len()
+            .saturating_sub(MAX_REGISTRY_EVENTS);
+        self.registry_events.drain(..excess);
+    }
+
+    /// Attaches a channel the builder and later the `Context` publish their `ContextEvent`s to.
+    /// Without one, `freeze` creates a channel, see `Context::subscribe_events`.
+    pub fn set_event_sender(&mut self, events: broadcast::Sender<ContextEvent>) {
+        self.events = Some(events);
+    }
+
+    /// Replaces the in-memory settings store, e.g. with one persisting to the host's config
+    /// folder. See `Context::settings`.
+    pub fn with_settings_store(&mut self, store: Arc<dyn SettingsStore>) {
+        self.settings = Some(store);
+    }
+
+    /// Only lets sources matching `allowed_source` register mod providers and games with ids in
+    /// `ns`, e.g. `vmm` for `vmm:thunderstore`. Reserving a namespace several times allows every
+    /// matching source. `core` is always reserved for `ProviderSource::Core` only, reserving it
+    /// has no effect.
+    pub fn reserve_namespace(&mut self, ns: &str, allowed_source: ProviderSourceMatcher) {
+        let ns = ns.trim().trim_end_matches(':').to_lowercase();
+        let result: Result<(), RegistryError> = Ok(());
+        self.record(RegistryAction::ReserveNamespace, &ns, None, &result);
+        self.reserved_namespaces.push((ns, allowed_source));
+    }
+
+    /// Fails if `id` is in a reserved namespace `source` may not use
+    fn check_namespace(&self, id: &str, source: &ProviderSource) -> Result<(), RegistryError> {
+        if is_core_id(id) {
+            return match source {
+                ProviderSource::Core => Ok(()),
+                _ => Err(RegistryError::ReservedCoreId(id.to_string())),
+            };
+        }
+
+        let reservations: Vec<&(String, ProviderSourceMatcher)> = self
+            .reserved_namespaces
+            .iter()
+            .filter(|(ns, _)| is_in_namespace(id, ns))
+            .collect();
+        match reservations.first() {
+            Some((ns, _)) if !reservations.iter().any(|(_, m)| m.matches(source)) => {
+                Err(RegistryError::ReservedNamespace {
+                    namespace: ns.clone(),
+                    id: id.to_string(),
+                })
+            }
+            _ => Ok(()),
         }
     }
 
+    /// Overrides the capability versions checked by `freeze`, defaults to the ones of this lib-vmm build
+    pub fn set_supported_capabilities(&mut self, supported: SupportedCapabilities) {
+        self.supported_capabilities = supported;
+    }
+
     pub fn register_mod_provider(
         &mut self,
         id: &str,
         provider: Arc<dyn ModProvider + Send + Sync>,
         source: ProviderSource,
     ) -> Result<(), RegistryError> {
-        let id = normalize_id(id)?;
-        if id.starts_with("core:") && !matches!(source, ProviderSource::Core) {
-            return Err(RegistryError::ReservedCoreId(id));
+        self.register_mod_provider_with(id, provider, source, ProviderOptions::default())
+    }
+
+    /// `register_mod_provider` with extra options like the provider's priority
+    pub fn register_mod_provider_with(
+        &mut self,
+        id: &str,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+        source: ProviderSource,
+        options: ProviderOptions,
+    ) -> Result<(), RegistryError> {
+        let result = self.check_mod_provider(id, &provider, &source);
+        self.record(
+            RegistryAction::RegisterModProvider,
+            id,
+            Some(&source),
+            &result,
+        );
+        let id = result?;
+
+        for cap in provider.capabilities() {
+            cap.on_registered(&id);
         }
+        if let Some(events) = &self.events {
+            let _ = events.send(ContextEvent::ProviderRegistered(id.clone()));
+        }
+
+        self.insert_mod_provider(id, provider, source, options.priority);
+        Ok(())
+    }
+
+    /// Normalizes the id of a new mod provider and checks it may be registered
+    fn check_mod_provider(
+        &self,
+        id: &str,
+        provider: &Arc<dyn ModProvider + Send + Sync>,
+        source: &ProviderSource,
+    ) -> Result<String, RegistryError> {
+        let id = normalize_id(id)?;
+        self.check_namespace(&id, source)?;
 
-        if self.mod_providers.contains_key(&id) {
+        if self.is_provider_id_taken(&id) {
             return Err(RegistryError::ProviderAlreadyExists(id));
         }
+        validate_mod_provider(&id, provider)?;
+        Ok(id)
+    }
 
+    fn insert_mod_provider(
+        &mut self,
+        id: String,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+        source: ProviderSource,
+        priority: i32,
+    ) {
         self.mod_providers.insert(
             id.clone(),
             ProviderEntry {
                 id,
                 source,
+                display_name: provider.display_name().map(str::to_string),
+                registered_at: SystemTime::now(),
+                version: provider.version().map(str::to_string),
                 provider,
+                priority,
             },
         );
+    }
+
+    /// Unregisters a mod provider, lazy or not, returning its source and priority
+    fn take_mod_provider(&mut self, id: &str) -> Result<(ProviderSource, i32), RegistryError> {
+        let lazy = self.lazy_providers.remove(id);
+        match self.mod_providers.remove(id) {
+            Some(entry) => Ok((entry.source, entry.priority)),
+            None => lazy
+                .map(|entry| (entry.source.clone(), entry.priority))
+                .ok_or_else(|| RegistryError::NotFound(id.to_string())),
+        }
+    }
+
+    /// Registers a mod provider that is only constructed on first access through
+    /// `Context::get_mod_provider` (or a method using it), concurrent accesses share one instance.
+    ///
+    /// Capabilities are indexed on realization: until then the provider is missing from capability
+    /// lookups, `list_mod_providers_detailed`, snapshots, health checks and the compatibility
+    /// report. Factory errors are returned as `RegistryError::ProviderInitFailed` on every access.
+    pub fn register_mod_provider_lazy(
+        &mut self,
+        id: &str,
+        source: ProviderSource,
+        factory: ProviderFactory,
+    ) -> Result<(), RegistryError> {
+        let result = normalize_id(id).and_then(|id| {
+            self.check_namespace(&id, &source)?;
+            if self.is_provider_id_taken(&id) {
+                return Err(RegistryError::ProviderAlreadyExists(id));
+            }
+            Ok(id)
+        });
+        self.record(
+            RegistryAction::RegisterLazyModProvider,
+            id,
+            Some(&source),
+            &result,
+        );
+        let id = result?;
+
+        if let Some(events) = &self.events {
+            let _ = events.send(ContextEvent::ProviderRegistered(id.clone()));
+        }
+        self.lazy_providers.insert(
+            id.clone(),
+            Arc::new(LazyProviderEntry::new(
+                id,
+                source,
+                ProviderOptions::default().priority,
+                factory,
+            )),
+        );
+        Ok(())
+    }
+
+    fn has_mod_provider(&self, id: &str) -> bool {
+        self.mod_providers.contains_key(id) || self.lazy_providers.contains_key(id)
+    }
+
+    /// Whether `id` is used by a mod provider or an alias of one
+    fn is_provider_id_taken(&self, id: &str) -> bool {
+        self.has_mod_provider(id) || self.provider_aliases.contains_key(id)
+    }
+
+    /// Whether `id` is used by a game or an alias of one
+    fn is_game_id_taken(&self, id: &str) -> bool {
+        self.games.contains_key(id) || self.game_aliases.contains_key(id)
+    }
+
+    /// Lets `alias` stand in for the registered mod provider `target`, e.g. the id a plugin used
+    /// before renaming its provider. `Context::get_mod_provider` resolves it transparently.
+    ///
+    /// Aliases can't be taken by a provider or another alias, and can't point to an alias.
+    pub fn alias_provider(&mut self, alias: &str, target: &str) -> Result<(), RegistryError> {
+        let result = self.insert_provider_alias(alias, target);
+        let id = format!("{alias} -> {target}");
+        self.record(RegistryAction::AliasProvider, &id, None, &result);
+        result
+    }
+
+    fn insert_provider_alias(&mut self, alias: &str, target: &str) -> Result<(), RegistryError> {
+        let alias = normalize_id(alias)?;
+        let target = normalize_id(target)?;
+        if self.provider_aliases.contains_key(&target) {
+            return Err(RegistryError::AliasChain { alias, target });
+        }
+        if !self.has_mod_provider(&target) {
+            return Err(RegistryError::NotFound(target));
+        }
+        if self.is_provider_id_taken(&alias) {
+            return Err(RegistryError::ProviderAlreadyExists(alias));
+        }
+        self.provider_aliases.insert(alias, target);
+        Ok(())
+    }
+
+    /// `alias_provider` for games, resolved by `Context::get_game_provider`, `activate_game`,
+    /// `get_metadata` and `restore_state`
+    pub fn alias_game(&mut self, alias: &str, target: &str) -> Result<(), RegistryError> {
+        let result = self.insert_game_alias(alias, target);
+        let id = format!("{alias} -> {target}");
+        self.record(RegistryAction::AliasGame, &id, None, &result);
+        result
+    }
+
+    fn insert_game_alias(&mut self, alias: &str, target: &str) -> Result<(), RegistryError> {
+        let alias = normalize_id(alias)?;
+        let target = normalize_id(target)?;
+        if self.game_aliases.contains_key(&target) {
+            return Err(RegistryError::AliasChain { alias, target });
+        }
+        if !self.games.contains_key(&target) {
+            return Err(RegistryError::NotFound(target));
+        }
+        if self.is_game_id_taken(&alias) {
+            return Err(RegistryError::GameAlreadyExists(alias));
+        }
+        self.game_aliases.insert(alias, target);
+        Ok(())
+    }
+
+    /// Registers the games of a manifest as `ManifestGameProvider`s. Mod providers they use that
+    /// aren't registered yet are looked up with `resolve` and registered with `source`. Mods are
+    /// installed into the games through `install`, without it installs are unsupported.
+    ///
+    /// Every entry is checked before anything is registered, errors point at the offending entry.
+    pub fn register_from_manifest(
+        &mut self,
+        manifest: ProviderManifest,
+        source: ProviderSource,
+        resolve: &dyn Fn(&str) -> Option<Arc<dyn ModProvider + Send + Sync>>,
+        install: Option<ManifestInstallHandler>,
+    ) -> Result<(), Vec<ManifestError>> {
+        let mut errors = Vec::new();
+        // Provider id -> (index of the first game using it, provider)
+        let mut resolved: HashMap<String, (usize, Arc<dyn ModProvider + Send + Sync>)> =
+            HashMap::new();
+        let mut game_ids: Vec<String> = Vec::new();
+        for (index, game) in manifest.games.iter().enumerate() {
+            let checked = self
+                .check_manifest_game(game, &source)
+                .and_then(|(id, provider_id)| {
+                    if game_ids.contains(&id) {
+                        return Err(RegistryError::GameAlreadyExists(id));
+                    }
+                    game_ids.push(id);
+
+                    let Some(provider_id) = provider_id else {
+                        return Ok(());
+                    };
+                    if let Entry::Vacant(entry) = resolved.entry(provider_id) {
+                        let provider = resolve(entry.key())
+                            .ok_or_else(|| RegistryError::NotFound(entry.key().clone()))?;
+                        validate_mod_provider(entry.key(), &provider)?;
+                        entry.insert((index, provider));
+                    }
+                    Ok(())
+                });
+            if let Err(error) = checked {
+                errors.push(ManifestError {
+                    index,
+                    game_id: game.id.clone(),
+                    error,
+                });
+            }
+        }
+        if !errors.is_empty() {
+            for e in &errors {
+                let result: Result<(), _> = Err(&e.error);
+                self.record(
+                    RegistryAction::RegisterGame,
+                    &e.game_id,
+                    Some(&source),
+                    &result,
+                );
+            }
+            return Err(errors);
+        }
 
+        let manifest_error = |index: usize, error| ManifestError {
+            index,
+            game_id: manifest.games[index].id.clone(),
+            error,
+        };
+        let mut providers: Vec<_> = resolved.into_iter().collect();
+        providers.sort_by_key(|(_, (index, _))| *index);
+        for (id, (index, provider)) in providers {
+            self.register_mod_provider(&id, provider, source.clone())
+                .map_err(|e| vec![manifest_error(index, e)])?;
+        }
+        for (index, game) in manifest.games.iter().enumerate() {
+            let mut provider = ManifestGameProvider::new(game.clone(), source.clone());
+            if let Some(install) = &install {
+                provider = provider.with_install_handler(Arc::clone(install));
+            }
+            self.register_game_provider(Arc::new(provider), source.clone())
+                .map_err(|e| vec![manifest_error(index, e)])?;
+        }
         Ok(())
     }
 
+    /// Checks a manifest game against the registered entries, returning its normalized id and
+    /// the id of its mod provider if that isn't registered yet
+    fn check_manifest_game(
+        &self,
+        game: &ManifestGame,
+        source: &ProviderSource,
+    ) -> Result<(String, Option<String>), RegistryError> {
+        let id = normalize_id(&game.id)?;
+        self.check_namespace(&id, source)?;
+        if self.is_game_id_taken(&id) {
+            return Err(RegistryError::GameAlreadyExists(id));
+        }
+
+        let provider_id = normalize_id(&game.mod_provider_id)?;
+        if self.has_mod_provider(&provider_id) {
+            return Ok((id, None));
+        }
+        // Games can't use a mod provider through an alias, and the alias blocks registering one
+        if self.provider_aliases.contains_key(&provider_id) {
+            return Err(RegistryError::NotFound(provider_id));
+        }
+        self.check_namespace(&provider_id, source)?;
+        Ok((id, Some(provider_id)))
+    }
+
+    /// Registers a game once the mod providers it uses are registered.
+    ///
+    /// Fails with `MissingRequiredCapability` if the primary provider lacks a capability from
+    /// `GameProvider::required_mod_provider_capabilities`. Lazy providers aren't checked.
     pub fn register_game_provider(
         &mut self,
         provider: Arc<dyn GameProvider + Send + Sync>,
         source: ProviderSource,
     ) -> Result<(), RegistryError> {
-        let id = normalize_id(provider.id())?;
-        if self.games.contains_key(&id) {
-            return Err(RegistryError::GameAlreadyExists(id));
+        self.register_game(provider, source, true)
+    }
+
+    /// `register_game_provider` without the capability check, missing capabilities are reported
+    /// as `ValidationWarning::MissingCapability` by `freeze_validated` instead
+    pub fn register_game_provider_lenient(
+        &mut self,
+        provider: Arc<dyn GameProvider + Send + Sync>,
+        source: ProviderSource,
+    ) -> Result<(), RegistryError> {
+        self.register_game(provider, source, false)
+    }
+
+    fn register_game(
+        &mut self,
+        provider: Arc<dyn GameProvider + Send + Sync>,
+        source: ProviderSource,
+        check_capabilities: bool,
+    ) -> Result<(), RegistryError> {
+        let result = self.check_game(&provider, &source, check_capabilities);
+        let game_id = provider.game_id();
+        self.record(
+            RegistryAction::RegisterGame,
+            game_id,
+            Some(&source),
+            &result,
+        );
+        let (id, depends_on) = result?;
+
+        for cap in provider.capabilities() {
+            cap.on_registered(&id);
         }
 
-        let depends_on = normalize_id(provider.mod_provider_id())?;
+        self.insert_game(id, provider, source, depends_on);
+        Ok(())
+    }
+
+    /// Checks a new game may be registered, returning its normalized id and the ids of the
+    /// mod providers it uses
+    fn check_game(
+        &self,
+        provider: &Arc<dyn GameProvider + Send + Sync>,
+        source: &ProviderSource,
+        check_capabilities: bool,
+    ) -> Result<(String, Vec<String>), RegistryError> {
+        let id = normalize_id(provider.game_id())?;
+        self.check_namespace(&id, source)?;
+        if self.is_game_id_taken(&id) {
+            return Err(RegistryError::GameAlreadyExists(id));
+        }
 
-        if !self.mod_providers.contains_key(&depends_on) {
-            return Err(RegistryError::NotFound(depends_on));
+        let mut depends_on: Vec<String> = Vec::new();
+        for provider_id in provider.mod_provider_ids() {
+            let provider_id = normalize_id(provider_id)?;
+            if !self.has_mod_provider(&provider_id) {
+                return Err(RegistryError::NotFound(provider_id));
+            }
+            if !depends_on.contains(&provider_id) {
+                depends_on.push(provider_id);
+            }
+        }
+        if depends_on.is_empty() {
+            return Err(RegistryError::InvalidId(format!(
+                "Game '{}' doesn't use any mod provider",
+                id
+            )));
+        }
+        if check_capabilities && let Some(primary) = self.mod_providers.get(&depends_on[0]) {
+            let missing = provider
+                .required_mod_provider_capabilities()
+                .iter()
+                .find(|c| !primary.provider.has_capability(c));
+            if let Some(capability) = missing {
+                return Err(RegistryError::MissingRequiredCapability {
+                    game: id,
+                    provider: primary.id.clone(),
+                    capability: capability.to_string(),
+                });
+            }
         }
+        Ok((id, depends_on))
+    }
 
+    fn insert_game(
+        &mut self,
+        id: String,
+        provider: Arc<dyn GameProvider + Send + Sync>,
+        source: ProviderSource,
+        depends_on: Vec<String>,
+    ) {
         self.games.insert(
             id.clone(),
             GameEntry {
                 id,
                 source,
                 game: provider,
-                required_provider_id: depends_on,
+                required_provider_ids: depends_on,
             },
         );
+    }
+
+    /// A `ProviderInUse` error for each of the `removed` mod providers games still use
+    fn providers_in_use(&self, removed: &[String]) -> Vec<RegistryError> {
+        removed
+            .iter()
+            .filter(|id| !self.has_mod_provider(id))
+            .filter_map(|id| {
+                let mut games: Vec<String> = self
+                    .games
+                    .values()
+                    .filter(|g| g.required_provider_ids.contains(id))
+                    .map(|g| g.id.clone())
+                    .collect();
+                games.sort();
+                (!games.is_empty()).then(|| {
+                    RegistryError::ProviderInUse(format!("{id} (used by {})", games.join(", ")))
+                })
+            })
+            .collect()
+    }
 
+    /// Applies a change of a `ContextUpdate`, recording what to notify once it's committed
+    fn apply(&mut self, op: UpdateOp, applied: &mut AppliedUpdate) -> Result<(), RegistryError> {
+        match op {
+            UpdateOp::AddModProvider {
+                id,
+                provider,
+                source,
+                options,
+            } => {
+                let id = self.check_mod_provider(&id, &provider, &source)?;
+                applied.registered(&id, provider.capabilities());
+                applied.touched_providers.push(id.clone());
+                applied
+                    .events
+                    .push(ContextEvent::ProviderRegistered(id.clone()));
+                self.insert_mod_provider(id, provider, source, options.priority);
+            }
+            UpdateOp::ReplaceModProvider { id, provider } => {
+                let id = normalize_id(&id)?;
+                let (source, priority) = self.take_mod_provider(&id)?;
+                validate_mod_provider(&id, &provider)?;
+                applied.registered(&id, provider.capabilities());
+                applied.touched_providers.push(id.clone());
+                applied
+                    .events
+                    .push(ContextEvent::ProviderReloaded(id.clone()));
+                self.insert_mod_provider(id, provider, source, priority);
+            }
+            UpdateOp::RemoveModProvider(id) => {
+                let id = normalize_id(&id)?;
+                self.take_mod_provider(&id)?;
+                self.provider_aliases.retain(|_, target| *target != id);
+                applied.removed_providers.push(id.clone());
+                applied.events.push(ContextEvent::ProviderRemoved(id));
+            }
+            UpdateOp::AddGame {
+                provider,
+                source,
+                check_capabilities,
+            } => {
+                let (id, depends_on) = self.check_game(&provider, &source, check_capabilities)?;
+                applied.registered(&id, provider.capabilities());
+                self.insert_game(id, provider, source, depends_on);
+            }
+            UpdateOp::ReplaceGame(provider) => {
+                let id = normalize_id(provider.game_id())?;
+                let old = self
+                    .games
+                    .remove(&id)
+                    .ok_or_else(|| RegistryError::NotFound(id.clone()))?;
+                let (id, depends_on) = self.check_game(&provider, &old.source, true)?;
+                applied.registered(&id, provider.capabilities());
+                self.insert_game(id, provider, old.source, depends_on);
+            }
+            UpdateOp::RemoveGame(id) => {
+                let id = normalize_id(&id)?;
+                if self.games.remove(&id).is_none() {
+                    return Err(RegistryError::NotFound(id));
+                }
+                self.game_aliases.retain(|_, target| *target != id);
+            }
+        }
         Ok(())
     }
 
-    pub fn freeze(self) -> Context {
-        Context {
-            mod_providers: Arc::new(self.mod_providers),
-            game_providers: Arc::new(self.games),
-            active_game: Mutex::new(None),
+    /// Adds every provider, game and alias of `other`, e.g. a registry built by a plugin in isolation.
+    ///
+    /// Collects every duplicate id, `core:` id of a non-core provider and missing game dependency
+    /// instead of stopping at the first. If there is any, `self` is left unchanged.
+    /// The supported capabilities of `self` are kept. So are its event sender and settings store,
+    /// those of `other` are only taken if `self` has none.
+    pub fn merge(&mut self, other: ContextBuilder) -> Result<(), Vec<RegistryError>> {
+        let mut errors = Vec::new();
+
+        let mut providers: Vec<(&String, &ProviderSource)> = other
+            .mod_providers
+            .values()
+            .map(|e| (&e.id, &e.source))
+            .chain(other.lazy_providers.values().map(|e| (&e.id, &e.source)))
+            .collect();
+        providers.sort_by_key(|(id, _)| *id);
+        for (id, source) in providers {
+            if let Err(e) = self.check_namespace(id, source) {
+                errors.push(e);
+            }
+            if self.is_provider_id_taken(id) {
+                errors.push(RegistryError::ProviderAlreadyExists(id.clone()));
+            }
+        }
+        let mut provider_aliases: Vec<&String> = other.provider_aliases.keys().collect();
+        provider_aliases.sort();
+        for alias in provider_aliases {
+            if self.is_provider_id_taken(alias) {
+                errors.push(RegistryError::ProviderAlreadyExists(alias.clone()));
+            }
+        }
+
+        let mut game_ids: Vec<&String> = other.games.keys().collect();
+        game_ids.sort();
+        for id in game_ids {
+            if let Err(e) = self.check_namespace(id, &other.games[id].source) {
+                errors.push(e);
+            }
+            if self.is_game_id_taken(id) {
+                errors.push(RegistryError::GameAlreadyExists(id.clone()));
+            }
+            errors.extend(
+                other.games[id]
+                    .required_provider_ids
+                    .iter()
+                    .filter(|p| !self.has_mod_provider(p) && !other.has_mod_provider(p))
+                    .map(|p| RegistryError::NotFound(p.clone())),
+            );
+        }
+        let mut game_aliases: Vec<&String> = other.game_aliases.keys().collect();
+        game_aliases.sort();
+        for alias in game_aliases {
+            if self.is_game_id_taken(alias) {
+                errors.push(RegistryError::GameAlreadyExists(alias.clone()));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
         }
+
+        self.mod_providers.extend(other.mod_providers);
+        self.lazy_providers.extend(other.lazy_providers);
+        self.games.extend(other.games);
+        self.provider_aliases.extend(other.provider_aliases);
+        self.game_aliases.extend(other.game_aliases);
+        self.reserved_namespaces.extend(other.reserved_namespaces);
+        if self.events.is_none() {
+            self.events = other.events;
+        }
+        if self.settings.is_none() {
+            self.settings = other.settings;
+        }
+        self.registry_events.extend(other.registry_events);
+        self.truncate_events();
+        Ok(())
     }
-}
 
-pub struct Context {
-    mod_providers: Arc<HashMap<String, ProviderEntry>>,
-    game_providers: Arc<HashMap<String, GameEntry>>,
-    active_game: Mutex<Option<String>>,
-}
+    /// Number of registered mod providers
+    pub fn mod_provider_count(&self) -> usize {
+        self.mod_providers.len()
+    }
 
-impl Context {
-    pub fn get_mod_provider(&self, id: &str) -> Result<Arc<dyn ModProvider>, RegistryError> {
-        let id = normalize_id(id)?;
-        self.mod_providers
-            .get(&id)
-            .map(|e| Arc::clone(&e.provider))
-            .ok_or(RegistryError::NotFound(id))
+    /// Number of registered game providers
+    pub fn game_count(&self) -> usize {
+        self.games.len()
     }
 
-    pub fn get_game_provider(
-        &self,
-        id: &str,
-    ) -> Result<Arc<dyn GameProvider + 'static>, RegistryError> {
-        let id = normalize_id(id)?;
-        self.game_providers
-            .get(&id)
-            .map(|g| Arc::clone(&g.game) as Arc<dyn GameProvider + 'static>)
-            .ok_or(RegistryError::NotFound(id))
+    /// Number of registered mod and game providers
+    pub fn len(&self) -> usize {
+        self.mod_provider_count() + self.game_count()
     }
 
-    pub fn list_mod_providers(&self) -> Vec<(String, ProviderSource)> {
-        self.mod_providers
-            .values()
-            .map(|e| (e.id.clone(), e.source.clone()))
-            .collect()
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
-    pub fn list_games(&self) -> Vec<(String, ProviderSource, String)> {
-        self.game_providers
-            .values()
-            .map(|g| {
-                (
-                    g.id.clone(),
-                    g.source.clone(),
-                    g.required_provider_id.clone(),
-                )
-            })
-            .collect()
+    /// `freeze`, additionally reporting setups that are likely mistakes.
+    /// Warnings never fail the build.
+    pub fn freeze_validated(self) -> (Context, ValidationReport) {
+        let report = self.validation_report();
+        (self.freeze(), report)
     }
 
-    pub fn activate_game(&self, id: &str) -> Result<(), RegistryError> {
-        let id = normalize_id(id)?;
-        if !self.game_providers.contains_key(&id) {
-            return Err(RegistryError::NotFound(id));
+    /// Checks that every game dependency is registered, that no id normalizes to an empty
+    /// string and that each mod provider's `Provider::id()` matches its registered id.
+    ///
+    /// `freeze` panics on these errors in debug builds.
+    pub fn validate(&self) -> Result<(), Vec<RegistryError>> {
+        let mut errors = Vec::new();
+
+        let mut providers: Vec<&ProviderEntry> = self.mod_providers.values().collect();
+        providers.sort_by(|a, b| a.id.cmp(&b.id));
+        for entry in providers {
+            errors.extend(provider_entry_errors(entry));
         }
-        let mut active = self.active_game.lock().unwrap();
-        println!("Activated game {}", &id);
-        *active = Some(id);
-        Ok(())
-    }
+        errors.extend(self.game_errors());
 
-    pub fn active_game(&self) -> Option<String> {
-        self.active_game.lock().unwrap().clone()
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
-    pub fn active_game_required_provider(&self) -> Option<String> {
-        let active = self.active_game();
-        active.and_then(|id| {
-            self.game_providers
-                .get(&id)
-                .map(|g| g.required_provider_id.clone())
-        })
+    /// `validate` for a `ContextUpdate`, checking only the mod providers it added or replaced so
+    /// entries registered before can't fail unrelated updates
+    fn validate_update(&self, touched_providers: &[String]) -> Vec<RegistryError> {
+        let mut errors: Vec<RegistryError> = touched_providers
+            .iter()
+            .filter_map(|id| self.mod_providers.get(id))
+            .flat_map(provider_entry_errors)
+            .collect();
+        errors.extend(self.game_errors());
+        errors
     }
 
-    pub fn get_metadata(&self, id: &str) -> Result<GameMetadata, RegistryError> {
-        let id = normalize_id(id)?;
-        match self.game_providers.get(&id) {
-            Some(game_entry) => {
-                let metadata = game_entry.game.metadata().clone();
-                Ok(metadata)
+    /// Invalid game ids and missing game dependencies, sorted by game id
+    fn game_errors(&self) -> Vec<RegistryError> {
+        let mut errors = Vec::new();
+        let mut games: Vec<&GameEntry> = self.games.values().collect();
+        games.sort_by(|a, b| a.id.cmp(&b.id));
+        for game in games {
+            if let Err(e) = normalize_id(&game.id) {
+                errors.push(e);
             }
-            None => Err(RegistryError::NotFound(id)),
+            errors.extend(
+                game.required_provider_ids
+                    .iter()
+                    .filter(|p| !self.has_mod_provider(p))
+                    .map(|p| RegistryError::NotFound(p.clone())),
+            );
         }
+        errors
     }
 
-    pub async fn get_extended_info(&self, id: &str) -> Result<ModExtendedMetadata, RegistryError> {
-        let id = normalize_id(id)?;
-        let provider = self
-            .active_game_required_provider()
-            .ok_or_else(|| RegistryError::NotFound("No active game".to_string()))?;
+    fn validation_report(&self) -> ValidationReport {
+        let mut warnings = Vec::new();
 
-        let provider_entry = self
+        let mut providers: Vec<(&String, &ProviderSource)> = self
             .mod_providers
-            .get(&provider)
-            .ok_or_else(|| RegistryError::NotFound(provider.clone()))?;
-        let provider = Arc::clone(&provider_entry.provider);
+            .values()
+            .map(|e| (&e.id, &e.source))
+            .chain(self.lazy_providers.values().map(|e| (&e.id, &e.source)))
+            .collect();
+        providers.sort_by_key(|(id, _)| *id);
+        for (id, source) in providers {
+            if matches!(source, ProviderSource::Core) && !is_core_id(id) {
+                warnings.push(ValidationWarning::NamespaceMismatch {
+                    id: id.clone(),
+                    source: source.clone(),
+                });
+            }
+            if !self
+                .games
+                .values()
+                .any(|g| g.required_provider_ids.contains(id))
+            {
+                warnings.push(ValidationWarning::OrphanProvider(id.clone()));
+            }
+        }
 
-        Ok(provider.get_extended_mod(&id).await)
+        let mut games: Vec<&GameEntry> = self.games.values().collect();
+        games.sort_by(|a, b| a.id.cmp(&b.id));
+        for game in games {
+            let provider_id = &game.required_provider_ids[0];
+            // Lazy providers aren't constructed just to validate them
+            let Some(ProviderEntry { provider, .. }) = self.mod_providers.get(provider_id) else {
+                continue;
+            };
+            for capability in game.game.required_mod_provider_capabilities() {
+                if provider.find_capability(capability).is_none() {
+                    warnings.push(ValidationWarning::MissingCapability {
+                        game: game.id.clone(),
+                        provider: provider_id.clone(),
+                        capability: capability.to_string(),
+                    });
+                }
+            }
+        }
+
+        ValidationReport { warnings }
     }
 
-    #[cfg(debug_assertions)]
-    pub fn debug_dump(&self) {
-        println!("Context dump\n ---> Providers");
-        for (id, provider) in self.mod_providers.iter() {
-            println!("\t{} ({:?})", id, provider.source)
+    /// Builds the `Context` and calls `Capability::on_context_ready` on every capability,
+    /// mod providers first, then games.
+    ///
+    /// Capability version mismatches don't fail the build, see `Context::compatibility_report`.
+    /// In debug builds this panics if `validate` fails.
+    pub fn freeze(mut self) -> Context {
+        #[cfg(debug_assertions)]
+        if let Err(errors) = self.validate() {
+            panic!("Invalid context configuration: {errors:?}");
         }
-        println!("\n ---> Games");
-        for (id, game) in self.game_providers.iter() {
-            println!(
-                "\t{} ({:?}) -> Depends on {}",
-                id, game.source, game.required_provider_id
-            )
+
+        let events = self
+            .events
+            .take()
+            .unwrap_or_else(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0);
+        let settings = self
+            .settings
+            .take()
+            .unwrap_or_else(|| Arc::new(InMemorySettingsStore::default()));
+        let supported_capabilities = self.supported_capabilities.clone();
+        let registry_events = std::mem::take(&mut self.registry_events);
+        let ctx = Context {
+            registry: RwLock::new(Registry::build(self, None)),
+            supported_capabilities,
+            active_game: watch::Sender::new(None),
+            events,
+            settings,
+            registry_events,
+            cancellation: CancellationToken::new(),
+            tasks: TaskTracker::new(),
+            shut_down: AtomicBool::new(false),
+        };
+
+        let caps: Vec<CapabilityRef> = {
+            let registry = read_lock(&ctx.registry);
+            let mod_caps = registry
+                .mod_providers
+                .values()
+                .flat_map(|e| e.provider.capabilities().iter().cloned());
+            let game_caps = registry
+                .games
+                .values()
+                .flat_map(|g| g.game.capabilities().iter().cloned());
+            mod_caps.chain(game_caps).collect()
+        };
+        for cap in caps {
+            cap.on_context_ready(&ctx);
+        }
+
+        ctx
+    }
+}
+
+/// Runs `Provider::validate` of a new mod provider in debug builds
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+fn validate_mod_provider(
+    id: &str,
+    provider: &Arc<dyn ModProvider + Send + Sync>,
+) -> Result<(), RegistryError> {
+    #[cfg(debug_assertions)]
+    if let Err(errors) = provider.validate() {
+        let messages: Vec<String> = errors
+            .into_iter()
+            .map(|e| match e.capability {
+                Some(cap) => format!("{} ({})", e.message, cap),
+                None => e.message,
+            })
+            .collect();
+        return Err(RegistryError::ValidationFailed(format!(
+            "{}: {}",
+            id,
+            messages.join("; ")
+        )));
+    }
+    Ok(())
+}
+
+/// An invalid registry id, or a `Provider::id()` not matching it
+fn provider_entry_errors(entry: &ProviderEntry) -> Vec<RegistryError> {
+    let mut errors = Vec::new();
+    if let Err(e) = normalize_id(&entry.id) {
+        errors.push(e);
+    }
+    match normalize_id(entry.provider.id()) {
+        Ok(id) if id == entry.id => {}
+        _ => errors.push(RegistryError::IdMismatch {
+            registered: entry.id.clone(),
+            provider_id: entry.provider.id().to_string(),
+        }),
+    }
+    errors
+}
+
+fn sorted_aliases(aliases: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut aliases: Vec<(String, String)> = aliases
+        .iter()
+        .map(|(alias, id)| (alias.clone(), id.clone()))
+        .collect();
+    aliases.sort();
+    aliases
+}
+
+/// The metadata of a game, with the fields it left empty filled from the provider
+fn resolve_metadata(entry: &GameEntry) -> GameMetadata {
+    let mut metadata = entry.game.metadata();
+    if metadata.version.is_none() {
+        metadata.version = entry.game.get_game_version();
+    }
+    if metadata.install_path.is_none() {
+        metadata.install_path = entry.game.game_root_path();
+    }
+    metadata
+}
+
+/// Capability id -> ids of the providers having it, in the order the providers are given
+fn index_capabilities<'a>(
+    providers: impl Iterator<Item = (&'a String, &'a [CapabilityRef])>,
+) -> HashMap<String, Vec<String>> {
+    let mut index: HashMap<String, Vec<String>> = HashMap::new();
+    for (id, caps) in providers {
+        for cap in caps {
+            let ids = index.entry(cap.id().to_string()).or_default();
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
         }
     }
+    index
+}
+
+/// Compares the version of every capability against `supported`, capabilities unknown to the
+/// host are skipped
+fn check_compatibility(
+    mod_providers: &HashMap<String, ProviderEntry>,
+    games: &HashMap<String, GameEntry>,
+    supported: &SupportedCapabilities,
+) -> CompatibilityReport {
+    let mods = mod_providers
+        .values()
+        .map(|e| (&e.id, e.provider.capabilities()));
+    let games = games.values().map(|g| (&g.id, g.game.capabilities()));
+
+    let mut mismatches: Vec<CapabilityMismatch> = mods
+        .chain(games)
+        .flat_map(|(id, caps)| caps.iter().map(move |c| (id, c)))
+        .filter_map(|(id, cap)| {
+            let supported = supported.get(cap.id())?;
+            (!supported.contains(cap.version())).then(|| CapabilityMismatch {
+                provider_id: id.clone(),
+                capability_id: cap.id().to_string(),
+                provided_version: cap.version(),
+                supported_range: supported,
+            })
+        })
+        .collect();
+    mismatches.sort_by(|a, b| {
+        (&a.provider_id, &a.capability_id).cmp(&(&b.provider_id, &b.capability_id))
+    });
+    CompatibilityReport { mismatches }
+}
+
+/// Lookup tables over the mod providers, rebuilt whenever a provider is reloaded
+struct ModProviderIndex {
+    /// Capability id -> mod provider ids, both sorted by `ProviderEntry::cmp_order`
+    capabilities: HashMap<String, Vec<String>>,
+    descriptors: Vec<ProviderDescriptor>,
+}
+
+impl ModProviderIndex {
+    fn build(providers: &HashMap<String, ProviderEntry>) -> Self {
+        let mut entries: Vec<&ProviderEntry> = providers.values().collect();
+        entries.sort_by(|a, b| a.cmp_order(b));
+
+        let capabilities =
+            index_capabilities(entries.iter().map(|e| (&e.id, e.provider.capabilities())));
+        let descriptors = entries
+            .iter()
+            .map(|e| ProviderDescriptor {
+                id: e.id.clone(),
+                source: e.source.clone(),
+                capability_ids: e
+                    .provider
+                    .capabilities()
+                    .iter()
+                    .map(|c| c.id().to_string())
+                    .collect(),
+                priority: e.priority,
+                display_name: e.display_name.clone(),
+            })
+            .collect();
+        Self {
+            capabilities,
+            descriptors,
+        }
+    }
+}
+
+/// The registered entries of a `Context` and the tables built from them. A `ContextUpdate`
+/// replaces it as a whole, so readers see either all of an update or none of it.
+struct Registry {
+    mod_providers: HashMap<String, ProviderEntry>,
+    /// Kept after realization, the realized provider is added to `mod_providers`
+    lazy_providers: HashMap<String, Arc<LazyProviderEntry>>,
+    games: HashMap<String, GameEntry>,
+    /// Alias -> id, see `ContextBuilder::alias_provider`
+    provider_aliases: HashMap<String, String>,
+    /// Alias -> id, see `ContextBuilder::alias_game`
+    game_aliases: HashMap<String, String>,
+    /// Checked again by `ContextUpdate`s
+    reserved_namespaces: Vec<(String, ProviderSourceMatcher)>,
+    mod_index: ModProviderIndex,
+    /// Capability id -> game ids
+    game_capabilities: HashMap<String, Vec<String>>,
+    /// Sorted by id. Holds the only copy of each game's `GameMetadata`, `GameProvider::metadata`
+    /// is only called again for games a `ContextUpdate` adds or replaces
+    game_descriptors: Vec<GameDescriptor>,
+    compatibility: CompatibilityReport,
+}
+
+impl Registry {
+    /// Indexes the entries of `builder`, keeping the metadata of the games `previous` holds
+    fn build(builder: ContextBuilder, previous: Option<&Registry>) -> Self {
+        let mod_index = ModProviderIndex::build(&builder.mod_providers);
+        let mut games: Vec<&GameEntry> = builder.games.values().collect();
+        games.sort_by(|a, b| a.id.cmp(&b.id));
+        let game_capabilities =
+            index_capabilities(games.iter().map(|g| (&g.id, g.game.capabilities())));
+        let game_descriptors = games
+            .iter()
+            .map(|g| GameDescriptor {
+                id: g.id.clone(),
+                source: g.source.clone(),
+                required_provider_ids: g.required_provider_ids.clone(),
+                metadata: previous
+                    .and_then(|p| p.cached_metadata(g))
+                    .unwrap_or_else(|| resolve_metadata(g)),
+            })
+            .collect();
+
+        let compatibility = check_compatibility(
+            &builder.mod_providers,
+            &builder.games,
+            &builder.supported_capabilities,
+        );
+        #[cfg(feature = "tracing")]
+        for m in &compatibility.mismatches {
+            tracing::warn!(
+                "Provider {} uses version {} of {}, supported are {}..={}",
+                m.provider_id,
+                m.provided_version,
+                m.capability_id,
+                m.supported_range.min,
+                m.supported_range.max
+            );
+        }
+
+        Self {
+            mod_providers: builder.mod_providers,
+            lazy_providers: builder.lazy_providers,
+            games: builder.games,
+            provider_aliases: builder.provider_aliases,
+            game_aliases: builder.game_aliases,
+            reserved_namespaces: builder.reserved_namespaces,
+            mod_index,
+            game_capabilities,
+            game_descriptors,
+            compatibility,
+        }
+    }
+
+    /// A builder holding the registered entries, to apply a `ContextUpdate` to
+    fn to_builder(&self, supported: &SupportedCapabilities) -> ContextBuilder {
+        ContextBuilder {
+            mod_providers: self.mod_providers.clone(),
+            lazy_providers: self.lazy_providers.clone(),
+            games: self.games.clone(),
+            provider_aliases: self.provider_aliases.clone(),
+            game_aliases: self.game_aliases.clone(),
+            reserved_namespaces: self.reserved_namespaces.clone(),
+            supported_capabilities: supported.clone(),
+            events: None,
+            settings: None,
+            registry_events: Vec::new(),
+        }
+    }
+
+    /// The metadata of `game` if the same provider is registered here
+    fn cached_metadata(&self, game: &GameEntry) -> Option<GameMetadata> {
+        let registered = self.games.get(&game.id)?;
+        if !Arc::ptr_eq(&registered.game, &game.game) {
+            return None;
+        }
+        self.descriptor(&game.id).map(|d| d.metadata.clone())
+    }
+
+    fn descriptor(&self, id: &str) -> Option<&GameDescriptor> {
+        self.game_descriptors
+            .binary_search_by(|g| g.id.as_str().cmp(id))
+            .ok()
+            .map(|index| &self.game_descriptors[index])
+    }
+
+    fn has_mod_provider(&self, id: &str) -> bool {
+        self.mod_providers.contains_key(id) || self.lazy_providers.contains_key(id)
+    }
+
+    /// Normalizes a mod provider id and resolves it if it's an alias
+    fn resolve_provider_id(&self, id: &str) -> Result<String, RegistryError> {
+        let id = normalize_id(id)?;
+        Ok(self.provider_aliases.get(&id).cloned().unwrap_or(id))
+    }
+
+    /// Normalizes a game id and resolves it if it's an alias
+    fn resolve_game_id(&self, id: &str) -> Result<String, RegistryError> {
+        let id = normalize_id(id)?;
+        Ok(self.game_aliases.get(&id).cloned().unwrap_or(id))
+    }
+
+    fn game_not_found(&self, id: String) -> RegistryError {
+        RegistryError::not_found(id, self.games.keys().map(String::as_str))
+    }
+
+    /// The mod providers of a game by priority, keeping the game's order on ties
+    fn ordered_providers(&self, game: &GameEntry) -> Vec<String> {
+        let mut ids = game.required_provider_ids.clone();
+        let priority = |id: &String| match self.mod_providers.get(id) {
+            Some(e) => e.priority,
+            None => self.lazy_providers.get(id).map_or(0, |e| e.priority),
+        };
+        ids.sort_by_key(|id| std::cmp::Reverse(priority(id)));
+        ids
+    }
+}
+
+pub struct Context {
+    /// Read by every lookup. Replaced by `ContextUpdate::commit`, single entries are changed by
+    /// `reload_provider` and when a lazy provider is realized
+    registry: RwLock<Registry>,
+    supported_capabilities: SupportedCapabilities,
+    active_game: watch::Sender<Option<String>>,
+    events: broadcast::Sender<ContextEvent>,
+    settings: Arc<dyn SettingsStore>,
+    /// The `ContextBuilder::events` it was frozen with
+    registry_events: Vec<RegistryEvent>,
+    /// Cancelled by `shutdown` or on drop
+    cancellation: CancellationToken,
+    /// Tasks from `spawn_tracked`
+    tasks: TaskTracker,
+    shut_down: AtomicBool,
+}
+
+/// Locks ignore poisoning, the registry is only ever replaced as a whole or per entry
+fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Context {
+    /// Starts a set of changes to the registered mod providers and games, applied at once by
+    /// `ContextUpdate::commit`, e.g. when a plugin is installed, updated or removed at runtime
+    pub fn begin_update(&self) -> ContextUpdate<'_> {
+        ContextUpdate {
+            ctx: self,
+            ops: Vec::new(),
+        }
+    }
+
+    /// The registration calls on the `ContextBuilder` this was frozen from, see
+    /// `ContextBuilder::events`. Changes through `begin_update` aren't included.
+    pub fn registration_events(&self) -> &[RegistryEvent] {
+        &self.registry_events
+    }
+
+    /// Mod provider aliases and the ids they stand in for, sorted by alias
+    pub fn list_aliases(&self) -> Vec<(String, String)> {
+        sorted_aliases(&read_lock(&self.registry).provider_aliases)
+    }
+
+    /// Game aliases and the ids they stand in for, sorted by alias
+    pub fn list_game_aliases(&self) -> Vec<(String, String)> {
+        sorted_aliases(&read_lock(&self.registry).game_aliases)
+    }
+
+    /// Aliases are resolved, see `ContextBuilder::alias_provider`
+    pub fn get_mod_provider(&self, id: &str) -> Result<Arc<dyn ModProvider>, RegistryError> {
+        let lazy = {
+            let registry = read_lock(&self.registry);
+            let id = registry.resolve_provider_id(id)?;
+            if let Some(e) = registry.mod_providers.get(&id) {
+                return Ok(Arc::clone(&e.provider));
+            }
+            match registry.lazy_providers.get(&id) {
+                Some(lazy) => Arc::clone(lazy),
+                None => {
+                    let known = registry
+                        .mod_providers
+                        .keys()
+                        .chain(registry.lazy_providers.keys());
+                    return Err(RegistryError::not_found(id, known.map(String::as_str)));
+                }
+            }
+        };
+        self.realize(&lazy)
+    }
+
+    /// Constructs a lazy provider and indexes it like a registered one
+    fn realize(
+        &self,
+        lazy: &Arc<LazyProviderEntry>,
+    ) -> Result<Arc<dyn ModProvider>, RegistryError> {
+        let provider = lazy
+            .get_or_init()
+            .map_err(|message| RegistryError::ProviderInitFailed {
+                id: lazy.id.clone(),
+                message,
+            })?;
+
+        {
+            let mut registry = write_lock(&self.registry);
+            if let Some(e) = registry.mod_providers.get(&lazy.id) {
+                // Realized by a concurrent call, or replaced by `reload_provider`
+                return Ok(Arc::clone(&e.provider));
+            }
+            if !registry
+                .lazy_providers
+                .get(&lazy.id)
+                .is_some_and(|registered| Arc::ptr_eq(registered, lazy))
+            {
+                // Removed or replaced by a `ContextUpdate` in the meantime
+                return Err(RegistryError::NotFound(lazy.id.clone()));
+            }
+            for cap in provider.capabilities() {
+                cap.on_registered(&lazy.id);
+            }
+            registry.mod_providers.insert(
+                lazy.id.clone(),
+                ProviderEntry {
+                    id: lazy.id.clone(),
+                    source: lazy.source.clone(),
+                    provider: Arc::clone(&provider),
+                    priority: lazy.priority,
+                    display_name: provider.display_name().map(str::to_string),
+                    registered_at: lazy.registered_at,
+                    version: provider.version().map(str::to_string),
+                },
+            );
+            registry.mod_index = ModProviderIndex::build(&registry.mod_providers);
+        }
+
+        for cap in provider.capabilities() {
+            cap.on_context_ready(self);
+        }
+        Ok(provider)
+    }
+
+    /// Unregisters a mod provider and its aliases, e.g. when its plugin is uninstalled, and
+    /// publishes `ContextEvent::ProviderRemoved`.
+    ///
+    /// Fails with `RegistryError::ProviderInUse` while games use the provider. To remove both at
+    /// once, use `begin_update`.
+    pub fn remove_mod_provider(&self, id: &str) -> Result<(), RegistryError> {
+        let mut update = self.begin_update();
+        update.remove_mod_provider(id);
+        update.commit().map_err(|mut errors| errors.swap_remove(0))
+    }
+
+    /// Swaps the implementation of a registered mod provider, e.g. after a plugin updated itself.
+    ///
+    /// The new provider has to report the same `Provider::id()` as the one it replaces and keeps its
+    /// registry id, source and registration time. Its capabilities receive `on_registered` and `on_context_ready`,
+    /// and `ContextEvent::ProviderReloaded` is published if an event sender is attached.
+    /// The compatibility report isn't re-checked, see `begin_update` to also rebuild it.
+    pub fn reload_provider(
+        &self,
+        id: &str,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+    ) -> Result<(), RegistryError> {
+        let id = normalize_id(id)?;
+        let lazy = read_lock(&self.registry).lazy_providers.get(&id).cloned();
+        if let Some(lazy) = lazy {
+            self.realize(&lazy)?;
+        }
+        {
+            let mut registry = write_lock(&self.registry);
+            let registry = &mut *registry;
+            let entry = registry
+                .mod_providers
+                .get_mut(&id)
+                .ok_or_else(|| RegistryError::NotFound(id.clone()))?;
+            if entry.provider.id() != provider.id() {
+                return Err(RegistryError::ProviderMismatch {
+                    id,
+                    expected: entry.provider.id().to_string(),
+                    found: provider.id().to_string(),
+                });
+            }
+
+            for cap in provider.capabilities() {
+                cap.on_registered(&id);
+            }
+            entry.provider = Arc::clone(&provider) as Arc<dyn ModProvider>;
+            entry.display_name = provider.display_name().map(str::to_string);
+            entry.version = provider.version().map(str::to_string);
+            registry.mod_index = ModProviderIndex::build(&registry.mod_providers);
+        }
+
+        for cap in provider.capabilities() {
+            cap.on_context_ready(self);
+        }
+        self.publish(ContextEvent::ProviderReloaded(id));
+        Ok(())
+    }
+
+    pub fn get_game_provider(
+        &self,
+        id: &str,
+    ) -> Result<Arc<dyn GameProvider + 'static>, RegistryError> {
+        let registry = read_lock(&self.registry);
+        let id = registry.resolve_game_id(id)?;
+        registry
+            .games
+            .get(&id)
+            .map(|g| Arc::clone(&g.game) as Arc<dyn GameProvider + 'static>)
+            .ok_or_else(|| registry.game_not_found(id))
+    }
+
+    /// A registered mod provider with its source and capabilities. Aliases are resolved and
+    /// lazy providers constructed, like `get_mod_provider`.
+    pub fn get_provider_entry(&self, id: &str) -> Result<ProviderEntryView, RegistryError> {
+        let id = read_lock(&self.registry).resolve_provider_id(id)?;
+        self.get_mod_provider(&id)?;
+        read_lock(&self.registry)
+            .mod_providers
+            .get(&id)
+            .map(ProviderEntryView::new)
+            .ok_or(RegistryError::NotFound(id))
+    }
+
+    /// A registered game with its source, mod providers and metadata. Aliases are resolved.
+    pub fn get_game_entry(&self, id: &str) -> Result<GameEntryView, RegistryError> {
+        let registry = read_lock(&self.registry);
+        let id = registry.resolve_game_id(id)?;
+        match (registry.games.get(&id), registry.descriptor(&id)) {
+            (Some(entry), Some(descriptor)) => {
+                Ok(GameEntryView::new(entry, descriptor.metadata.clone()))
+            }
+            _ => Err(registry.game_not_found(id)),
+        }
+    }
+
+    /// Compares the version of every capability against the supported ones.
+    /// Capabilities unknown to the host are skipped.
+    pub fn check_capability_compatibility(&self) -> CompatibilityReport {
+        let registry = read_lock(&self.registry);
+        check_compatibility(
+            &registry.mod_providers,
+            &registry.games,
+            &self.supported_capabilities,
+        )
+    }
+
+    /// The result of `check_capability_compatibility` when the context was frozen or last
+    /// updated through `begin_update`
+    pub fn compatibility_report(&self) -> CompatibilityReport {
+        read_lock(&self.registry).compatibility.clone()
+    }
+
+    /// Every mod provider having the given capability, highest priority first, then by id
+    pub fn providers_with_capability(&self, id: &str) -> Vec<(String, Arc<dyn ModProvider>)> {
+        let registry = read_lock(&self.registry);
+        registry
+            .mod_index
+            .capabilities
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|pid| {
+                registry
+                    .mod_providers
+                    .get(pid)
+                    .map(|e| (pid.clone(), Arc::clone(&e.provider) as Arc<dyn ModProvider>))
+            })
+            .collect()
+    }
+
+    /// Every game provider having the given capability, sorted by id
+    pub fn games_with_capability(&self, id: &str) -> Vec<(String, Arc<dyn GameProvider>)> {
+        let registry = read_lock(&self.registry);
+        registry
+            .game_capabilities
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|gid| {
+                registry
+                    .games
+                    .get(gid)
+                    .map(|g| (gid.clone(), Arc::clone(&g.game) as Arc<dyn GameProvider>))
+            })
+            .collect()
+    }
+
+    pub fn provider_has_capability(
+        &self,
+        provider_id: &str,
+        cap_id: &str,
+    ) -> Result<bool, RegistryError> {
+        let provider_id = normalize_id(provider_id)?;
+        let lazy = {
+            let registry = read_lock(&self.registry);
+            if !registry.has_mod_provider(&provider_id) {
+                return Err(RegistryError::NotFound(provider_id));
+            }
+            registry.lazy_providers.get(&provider_id).cloned()
+        };
+        if let Some(lazy) = lazy {
+            self.realize(&lazy)?;
+        }
+        Ok(read_lock(&self.registry)
+            .mod_index
+            .capabilities
+            .get(cap_id)
+            .is_some_and(|ids| ids.contains(&provider_id)))
+    }
+
+    /// Returns the capability of type `C` of a mod provider, if it has one
+    pub fn capability_of<C: Capability + 'static>(
+        &self,
+        provider_id: &str,
+    ) -> Result<Option<Arc<C>>, RegistryError> {
+        let provider_id = normalize_id(provider_id)?;
+        let provider = self.get_mod_provider(&provider_id)?;
+        Ok(provider.capabilities().iter().find_map(|c| {
+            (Arc::clone(c) as Arc<dyn Any + Send + Sync>)
+                .downcast::<C>()
+                .ok()
+        }))
+    }
+
+    /// Lists every mod provider with its capabilities, highest priority first, then by id
+    pub fn list_mod_providers_detailed(&self) -> Vec<ProviderDescriptor> {
+        read_lock(&self.registry).mod_index.descriptors.clone()
+    }
+
+    /// Lists every game with its metadata, sorted by id
+    pub fn list_games_detailed(&self) -> Vec<GameDescriptor> {
+        read_lock(&self.registry).game_descriptors.clone()
+    }
+
+    #[deprecated(since = "0.3.0", note = "Use list_mod_providers_detailed instead")]
+    pub fn list_mod_providers(&self) -> Vec<(String, ProviderSource)> {
+        self.list_mod_providers_detailed()
+            .into_iter()
+            .map(|d| (d.id, d.source))
+            .collect()
+    }
+
+    #[deprecated(since = "0.3.0", note = "Use list_games_detailed instead")]
+    pub fn list_games(&self) -> Vec<(String, ProviderSource, String)> {
+        read_lock(&self.registry)
+            .games
+            .values()
+            .map(|g| {
+                (
+                    g.id.clone(),
+                    g.source.clone(),
+                    g.required_provider_ids[0].clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Looks up the game linked to an entry of an external catalog, see
+    /// `GameProvider::get_external_id`. If several games match, the lowest id wins.
+    pub fn find_game_by_external_id(&self, external_id: &str) -> Option<String> {
+        read_lock(&self.registry)
+            .games
+            .values()
+            .filter(|g| g.game.get_external_id() == external_id)
+            .map(|g| g.id.clone())
+            .min()
+    }
+
+    /// Lists the ids of the games depending on a mod provider, sorted
+    pub fn list_games_for_provider(&self, provider_id: &str) -> Result<Vec<String>, RegistryError> {
+        let provider_id = normalize_id(provider_id)?;
+        let registry = read_lock(&self.registry);
+        if !registry.has_mod_provider(&provider_id) {
+            return Err(RegistryError::NotFound(provider_id));
+        }
+
+        let mut games: Vec<String> = registry
+            .games
+            .values()
+            .filter(|g| g.required_provider_ids.contains(&provider_id))
+            .map(|g| g.id.clone())
+            .collect();
+        games.sort();
+        Ok(games)
+    }
+
+    /// Makes `id` the active game, returning the previously active one.
+    /// For an alias the game it stands in for becomes active.
+    pub fn activate_game(&self, id: &str) -> Result<Option<String>, RegistryError> {
+        let (id, previous) = {
+            // Held until the game is active, so a `ContextUpdate` removing it deactivates it
+            let registry = read_lock(&self.registry);
+            let id = registry.resolve_game_id(id)?;
+            if !registry.games.contains_key(&id) {
+                return Err(registry.game_not_found(id));
+            }
+            let previous = self.active_game.send_replace(Some(id.clone()));
+            (id, previous)
+        };
+        #[cfg(feature = "tracing")]
+        tracing::info!(game = %id, "Activated game");
+        self.publish(ContextEvent::GameActivated(id));
+        Ok(previous)
+    }
+
+    /// Clears the active game, returning the previously active one
+    pub fn deactivate_game(&self) -> Option<String> {
+        let previous = self.active_game.send_replace(None);
+        if previous.is_some() {
+            self.publish(ContextEvent::GameDeactivated);
+        }
+        previous
+    }
+
+    /// The settings of every provider and game, see `SettingsStore`
+    pub fn settings(&self) -> Arc<dyn SettingsStore> {
+        Arc::clone(&self.settings)
+    }
+
+    /// Cancelled by `shutdown` and when the context is dropped. Services and clients should stop
+    /// their work once it fires, e.g. by selecting on `CancellationToken::cancelled`.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Spawns a task on the current tokio runtime that `shutdown` waits for. It should stop once
+    /// `cancellation_token` is cancelled.
+    ///
+    /// Panics outside of a tokio runtime, like `tokio::spawn`.
+    pub fn spawn_tracked<F>(&self, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.tasks.spawn(task)
+    }
+
+    /// `shutdown_with_timeout` with `SHUTDOWN_TIMEOUT`
+    pub async fn shutdown(&self) -> ShutdownReport {
+        self.shutdown_with_timeout(SHUTDOWN_TIMEOUT).await
+    }
+
+    /// Cancels `cancellation_token`, waits up to `timeout` for the tasks from `spawn_tracked`,
+    /// then calls `Provider::on_shutdown` of every mod provider and game. Providers are only
+    /// torn down once the tracked tasks stopped using them.
+    ///
+    /// Lazy providers that were never constructed aren't notified. Later calls only wait for
+    /// the tasks again.
+    pub async fn shutdown_with_timeout(&self, timeout: Duration) -> ShutdownReport {
+        self.cancellation.cancel();
+        self.tasks.close();
+        let pending_tasks = match tokio::time::timeout(timeout, self.tasks.wait()).await {
+            Ok(()) => 0,
+            Err(_) => self.tasks.len(),
+        };
+        let mut report = ShutdownReport {
+            notified: Vec::new(),
+            pending_tasks,
+        };
+        if self.shut_down.swap(true, Ordering::SeqCst) {
+            return report;
+        }
+
+        let (mods, games) = {
+            let registry = read_lock(&self.registry);
+            let mut mods: Vec<(String, Arc<dyn ModProvider>)> = registry
+                .mod_providers
+                .values()
+                .map(|e| (e.id.clone(), Arc::clone(&e.provider)))
+                .collect();
+            mods.sort_by(|a, b| a.0.cmp(&b.0));
+            let mut games: Vec<(String, Arc<dyn GameProvider + Send + Sync>)> = registry
+                .games
+                .values()
+                .map(|g| (g.id.clone(), Arc::clone(&g.game)))
+                .collect();
+            games.sort_by(|a, b| a.0.cmp(&b.0));
+            (mods, games)
+        };
+        for (id, provider) in mods {
+            provider.on_shutdown();
+            report.notified.push(id);
+        }
+        for (id, game) in games {
+            game.on_shutdown();
+            report.notified.push(id);
+        }
+        report
+    }
+
+    /// Subscribes to the `ContextEvent`s published from now on
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ContextEvent> {
+        self.events.subscribe()
+    }
+
+    fn publish(&self, event: ContextEvent) {
+        // Nobody listening isn't an error
+        let _ = self.events.send(event);
+    }
+
+    pub fn active_game(&self) -> Option<String> {
+        self.active_game.borrow().clone()
+    }
+
+    /// Subscribes to changes of the active game
+    pub fn watch_active_game(&self) -> watch::Receiver<Option<String>> {
+        self.active_game.subscribe()
+    }
+
+    /// Captures the session state for the host to persist, see `restore_state`
+    pub fn export_state(&self) -> RuntimeState {
+        RuntimeState {
+            active_game: self.active_game(),
+        }
+    }
+
+    /// Applies a state saved with `export_state`, e.g. on the next launch.
+    ///
+    /// Games that aren't registered anymore are reported instead of failing the restore,
+    /// the active game is left unset then. Only malformed ids are an error.
+    pub fn restore_state(&self, state: RuntimeState) -> Result<RestoreReport, RegistryError> {
+        let mut report = RestoreReport::default();
+        match state.active_game {
+            Some(id) => {
+                let id = read_lock(&self.registry).resolve_game_id(&id)?;
+                if self.activate_game(&id).is_ok() {
+                    report.active_game = Some(id);
+                } else {
+                    self.deactivate_game();
+                    report.missing_game = Some(id);
+                }
+            }
+            None => {
+                self.deactivate_game();
+            }
+        }
+        Ok(report)
+    }
+
+    /// Every mod provider of the active game, the primary one first
+    pub fn active_game_required_providers(&self) -> Vec<String> {
+        let Some(id) = self.active_game() else {
+            return Vec::new();
+        };
+        let registry = read_lock(&self.registry);
+        registry
+            .games
+            .get(&id)
+            .map(|g| registry.ordered_providers(g))
+            .unwrap_or_default()
+    }
+
+    /// The primary mod provider of the active game
+    pub fn active_game_primary_provider(&self) -> Option<String> {
+        self.active_game_required_providers().into_iter().next()
+    }
+
+    /// The mod provider of a game with the highest priority.
+    /// On ties the one the game lists first wins.
+    pub fn primary_provider_for_game(&self, game_id: &str) -> Result<String, RegistryError> {
+        let registry = read_lock(&self.registry);
+        let game_id = registry.resolve_game_id(game_id)?;
+        let game = registry
+            .games
+            .get(&game_id)
+            .ok_or_else(|| registry.game_not_found(game_id))?;
+        Ok(registry.ordered_providers(game).swap_remove(0))
+    }
+
+    #[deprecated(since = "0.3.0", note = "Use active_game_primary_provider instead")]
+    pub fn active_game_required_provider(&self) -> Option<String> {
+        self.active_game_primary_provider()
+    }
+
+    pub fn get_metadata(&self, id: &str) -> Result<GameMetadata, RegistryError> {
+        let registry = read_lock(&self.registry);
+        let id = registry.resolve_game_id(id)?;
+        match registry.descriptor(&id) {
+            Some(descriptor) => Ok(descriptor.metadata.clone()),
+            None => Err(registry.game_not_found(id)),
+        }
+    }
+
+    /// The metadata of every game, sorted by display name and then id
+    pub fn all_game_metadata(&self) -> Vec<GameMetadata> {
+        let mut metadata: Vec<GameMetadata> = read_lock(&self.registry)
+            .game_descriptors
+            .iter()
+            .map(|g| g.metadata.clone())
+            .collect();
+        metadata.sort_by(|a, b| {
+            a.display_name
+                .cmp(&b.display_name)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        metadata
+    }
+
+    /// `get_metadata` for several games at once, in the order of `ids`
+    pub fn metadata_for(&self, ids: &[&str]) -> Vec<Result<GameMetadata, RegistryError>> {
+        ids.iter().map(|id| self.get_metadata(id)).collect()
+    }
+
+    /// Fetches the extended metadata of a mod from the primary provider of the active game,
+    /// see `get_extended_info_from` for the other providers.
+    ///
+    /// Fails with `NoActiveGame` while no game is active.
+    pub async fn get_extended_info(&self, id: &str) -> Result<ModExtendedMetadata, RegistryError> {
+        let id = normalize_id(id)?;
+        let provider = self.get_required_provider_for_active_game()?;
+
+        Ok(provider.get_extended_mod(&id).await)
+    }
+
+    /// The primary mod provider of the active game
+    pub fn get_required_provider_for_active_game(
+        &self,
+    ) -> Result<Arc<dyn ModProvider>, RegistryError> {
+        let provider_id = self
+            .active_game_primary_provider()
+            .ok_or(RegistryError::NoActiveGame)?;
+        self.get_mod_provider(&provider_id)
+    }
+
+    /// Fetches the extended metadata of a mod from the given provider, whether or not it serves
+    /// the active game.
+    ///
+    /// `ModProvider::get_extended_mod` can't fail, so the errors returned here only come from
+    /// the lookup. A provider that can't reach its backend returns whatever placeholder metadata
+    /// it builds.
+    pub async fn get_extended_info_from(
+        &self,
+        provider_id: &str,
+        id: &str,
+    ) -> Result<ModExtendedMetadata, RegistryError> {
+        let id = normalize_id(id)?;
+        let provider = self.get_mod_provider(provider_id)?;
+
+        Ok(provider.get_extended_mod(&id).await)
+    }
+
+    /// Installs a mod archive into the active game.
+    ///
+    /// Returns `false` if the mod is already installed and the install was skipped. With `force`,
+    /// the mod is installed through `GameProvider::reinstall_mod`, replacing the installed version.
+    pub fn install_mod_for_active_game(
+        &self,
+        path: &Path,
+        force: bool,
+    ) -> Result<bool, RegistryError> {
+        let id = self.active_game().ok_or(RegistryError::NoActiveGame)?;
+        let game = self.get_game_provider(&id)?;
+
+        if force {
+            return game
+                .reinstall_mod(path)
+                .map(|()| true)
+                .map_err(|e| RegistryError::GameProviderError(e.to_string()));
+        }
+        match game.install_mod(path) {
+            Ok(()) => Ok(true),
+            Err(GameInstallError::AlreadyInstalled { .. }) => Ok(false),
+            Err(e) => Err(RegistryError::GameProviderError(e.to_string())),
+        }
+    }
+
+    /// Returns the load order of the active game
+    pub fn get_load_order(&self) -> Result<Vec<String>, RegistryError> {
+        let id = self.active_game().ok_or(RegistryError::NoActiveGame)?;
+        Ok(self.get_game_provider(&id)?.get_load_order())
+    }
+
+    /// Sets the load order of the active game
+    pub fn set_load_order(&self, order: Vec<String>) -> Result<(), RegistryError> {
+        let id = self.active_game().ok_or(RegistryError::NoActiveGame)?;
+        let game = self.get_game_provider(&id)?;
+
+        game.set_load_order(order)
+            .map_err(|e| RegistryError::GameProviderError(e.to_string()))
+    }
+
+    /// Lists the mods installed into the active game
+    pub fn list_installed_mods(&self) -> Result<Vec<InstalledModInfo>, RegistryError> {
+        let id = self.active_game().ok_or(RegistryError::NoActiveGame)?;
+        let game = self.get_game_provider(&id)?;
+
+        game.list_installed_mods()
+            .map_err(|e| RegistryError::GameProviderError(e.to_string()))
+    }
+
+    /// Verifies the files of the active game
+    pub async fn verify_active_game(&self) -> Result<GameVerifyReport, RegistryError> {
+        let id = self.active_game().ok_or(RegistryError::NoActiveGame)?;
+        let game = self.get_game_provider(&id)?;
+
+        game.verify_game_files()
+            .await
+            .map_err(|e| RegistryError::GameProviderError(e.to_string()))
+    }
+
+    /// Launches the active game.
+    ///
+    /// Uses the `GAME_LAUNCHER` capability of the game provider or, failing that, of the game's
+    /// primary mod provider. Without one the game provider's `launch_game` is called.
+    pub async fn launch_active_game(
+        &self,
+        args: Vec<String>,
+    ) -> Result<GameLaunchHandle, RegistryError> {
+        let id = self.active_game().ok_or(RegistryError::NoActiveGame)?;
+        let game = self.get_game_provider(&id)?;
+        let provider = self.get_required_provider_for_active_game()?;
+
+        let launcher = game
+            .find_capability(ids::GAME_LAUNCHER)
+            .or_else(|| provider.find_capability(ids::GAME_LAUNCHER))
+            .and_then(|c| c.as_launches_game());
+        let result = match launcher {
+            Some(launcher) => launcher.launch(&args).await.map(GameLaunchHandle::from_pid),
+            None => game.launch_game(args).await,
+        };
+        result.map_err(|e| RegistryError::GameProviderError(e.to_string()))
+    }
+
+    /// Runs `Provider::health_check` of every mod provider concurrently
+    pub async fn health_check_all_providers(&self) -> HashMap<String, ProviderHealthStatus> {
+        let providers: Vec<(String, Arc<dyn ModProvider>)> = read_lock(&self.registry)
+            .mod_providers
+            .values()
+            .map(|e| (e.id.clone(), Arc::clone(&e.provider)))
+            .collect();
+
+        let statuses = join_all(providers.iter().map(|(_, p)| p.health_check())).await;
+        providers
+            .into_iter()
+            .map(|(id, _)| id)
+            .zip(statuses)
+            .collect()
+    }
+
+    /// Discovers mods from the primary provider of the active game,
+    /// see `discover_merged` to include the other providers.
+    ///
+    /// Results of providers without `FULL_TEXT_SEARCH` are filtered by `query.search` here.
+    pub async fn discover_mods(
+        &self,
+        query: DiscoveryQuery,
+    ) -> Result<DiscoveryResult, DiscoveryError> {
+        if self.active_game().is_none() {
+            return Err(DiscoveryError::InvalidQuery("No active game".into()));
+        }
+        let provider = self
+            .get_required_provider_for_active_game()
+            .map_err(|_| DiscoveryError::ProviderUnavailable)?;
+
+        let mut result = provider.discover(&query).await?;
+        if !provider.has_capability(ids::FULL_TEXT_SEARCH) {
+            result.mods.retain(|m| query.matches_search(m));
+        }
+        Ok(result)
+    }
+
+    /// Discovers mods from every provider serving the active game and merges the results,
+    /// see `DiscoveryResult::merge`
+    pub async fn discover_merged(
+        &self,
+        query: &DiscoveryQuery,
+    ) -> Result<DiscoveryResult, DiscoveryError> {
+        let provider_ids = self.active_game_required_providers();
+        if provider_ids.is_empty() {
+            return Err(DiscoveryError::InvalidQuery("No active game".into()));
+        }
+
+        let providers = provider_ids
+            .iter()
+            .map(|id| {
+                self.get_mod_provider(id)
+                    .map_err(|_| DiscoveryError::ProviderUnavailable)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let results = join_all(providers.iter().map(|p| p.discover(query))).await;
+        let mut merged: Option<DiscoveryResult> = None;
+        for result in results {
+            let result = result?;
+            merged = Some(match merged {
+                Some(m) => m.merge(result),
+                None => result,
+            });
+        }
+        merged.ok_or(DiscoveryError::ProviderUnavailable)
+    }
+
+    /// Captures the registered providers, games and the active game for diagnostics.
+    ///
+    /// Only ids, capability ids, versions and registration times are included, never provider
+    /// settings like API keys.
+    pub fn snapshot(&self) -> ContextSnapshot {
+        let registry = read_lock(&self.registry);
+        let mod_providers = registry
+            .mod_index
+            .descriptors
+            .iter()
+            .map(|p| {
+                let entry = &registry.mod_providers[&p.id];
+                ProviderSnapshot {
+                    id: p.id.clone(),
+                    source: p.source.clone(),
+                    capability_ids: p.capability_ids.clone(),
+                    display_name: p.display_name.clone(),
+                    registered_at: entry
+                        .registered_at
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                    version: entry.version.clone(),
+                }
+            })
+            .collect();
+        let games = registry
+            .game_descriptors
+            .iter()
+            .map(|g| GameSnapshot {
+                id: g.id.clone(),
+                source: g.source.clone(),
+                required_provider_id: g.required_provider_ids[0].clone(),
+            })
+            .collect();
+
+        ContextSnapshot {
+            mod_providers,
+            games,
+            active_game: self.active_game(),
+        }
+    }
+
+    /// `snapshot` as pretty printed JSON
+    pub fn export_to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.snapshot())
+    }
+
+    /// Human readable listing of the registered mod providers and games, e.g. for a plugin's
+    /// log. Lazy providers show up once constructed.
+    pub fn dump_string(&self) -> String {
+        let (providers, games): (Vec<ProviderEntryView>, Vec<GameEntryView>) = {
+            let registry = read_lock(&self.registry);
+            let providers = registry
+                .mod_index
+                .descriptors
+                .iter()
+                .filter_map(|d| registry.mod_providers.get(&d.id))
+                .map(ProviderEntryView::new)
+                .collect();
+            let games = registry
+                .game_descriptors
+                .iter()
+                .filter_map(|d| {
+                    let entry = registry.games.get(&d.id)?;
+                    Some(GameEntryView::new(entry, d.metadata.clone()))
+                })
+                .collect();
+            (providers, games)
+        };
+
+        let mut dump = String::from("Context dump\n ---> Providers\n");
+        for provider in &providers {
+            dump.push_str(&format!("\t{provider}\n"));
+        }
+        dump.push_str("\n ---> Games\n");
+        for game in &games {
+            dump.push_str(&format!("\t{game}\n"));
+        }
+        if let Some(active) = self.active_game() {
+            dump.push_str(&format!("\n ---> Active game: {active}\n"));
+        }
+        dump
+    }
+
+    #[cfg(debug_assertions)]
+    pub fn debug_dump(&self) {
+        print!("{}", self.dump_string());
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        self.cancellation.cancel();
+        #[cfg(all(debug_assertions, feature = "tracing"))]
+        if !self.shut_down.load(Ordering::SeqCst) {
+            tracing::warn!("Context dropped without shutdown, providers weren't notified");
+        }
+    }
+}
+
+/// A change staged on a `ContextUpdate`
+enum UpdateOp {
+    AddModProvider {
+        id: String,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+        source: ProviderSource,
+        options: ProviderOptions,
+    },
+    ReplaceModProvider {
+        id: String,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+    },
+    RemoveModProvider(String),
+    AddGame {
+        provider: Arc<dyn GameProvider + Send + Sync>,
+        source: ProviderSource,
+        check_capabilities: bool,
+    },
+    ReplaceGame(Arc<dyn GameProvider + Send + Sync>),
+    RemoveGame(String),
+}
+
+/// What a `ContextUpdate` changed, notified once it's committed
+#[derive(Default)]
+struct AppliedUpdate {
+    /// Registry id and capabilities of every added or replaced provider and game
+    capabilities: Vec<(String, CapabilityRef)>,
+    events: Vec<ContextEvent>,
+    /// Added or replaced mod providers, checked by `ContextBuilder::validate_update`
+    touched_providers: Vec<String>,
+    removed_providers: Vec<String>,
+}
+
+impl AppliedUpdate {
+    fn registered(&mut self, id: &str, caps: &[CapabilityRef]) {
+        self.capabilities
+            .extend(caps.iter().map(|cap| (id.to_string(), Arc::clone(cap))));
+    }
+}
+
+/// Changes to the mod providers and games of a running `Context`, see `Context::begin_update`.
+///
+/// Nothing is checked or visible before `commit`, which applies every change with the checks
+/// of `ContextBuilder`.
+pub struct ContextUpdate<'a> {
+    ctx: &'a Context,
+    ops: Vec<UpdateOp>,
+}
+
+impl ContextUpdate<'_> {
+    /// See `ContextBuilder::register_mod_provider`
+    pub fn add_mod_provider(
+        &mut self,
+        id: &str,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+        source: ProviderSource,
+    ) -> &mut Self {
+        self.add_mod_provider_with(id, provider, source, ProviderOptions::default())
+    }
+
+    /// See `ContextBuilder::register_mod_provider_with`
+    pub fn add_mod_provider_with(
+        &mut self,
+        id: &str,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+        source: ProviderSource,
+        options: ProviderOptions,
+    ) -> &mut Self {
+        self.ops.push(UpdateOp::AddModProvider {
+            id: id.to_string(),
+            provider,
+            source,
+            options,
+        });
+        self
+    }
+
+    /// Swaps a registered mod provider, keeping its source and priority. Unlike
+    /// `Context::reload_provider` a lazy provider is replaced without constructing it first.
+    pub fn replace_mod_provider(
+        &mut self,
+        id: &str,
+        provider: Arc<dyn ModProvider + Send + Sync>,
+    ) -> &mut Self {
+        self.ops.push(UpdateOp::ReplaceModProvider {
+            id: id.to_string(),
+            provider,
+        });
+        self
+    }
+
+    /// Unregisters a mod provider and its aliases. Games still using it fail the commit with
+    /// `RegistryError::ProviderInUse`.
+    pub fn remove_mod_provider(&mut self, id: &str) -> &mut Self {
+        self.ops.push(UpdateOp::RemoveModProvider(id.to_string()));
+        self
+    }
+
+    /// See `ContextBuilder::register_game_provider`
+    pub fn add_game_provider(
+        &mut self,
+        provider: Arc<dyn GameProvider + Send + Sync>,
+        source: ProviderSource,
+    ) -> &mut Self {
+        self.ops.push(UpdateOp::AddGame {
+            provider,
+            source,
+            check_capabilities: true,
+        });
+        self
+    }
+
+    /// See `ContextBuilder::register_game_provider_lenient`
+    pub fn add_game_provider_lenient(
+        &mut self,
+        provider: Arc<dyn GameProvider + Send + Sync>,
+        source: ProviderSource,
+    ) -> &mut Self {
+        self.ops.push(UpdateOp::AddGame {
+            provider,
+            source,
+            check_capabilities: false,
+        });
+        self
+    }
+
+    /// Swaps the registered game with the `GameProvider::game_id` of `provider`, keeping its
+    /// source. The new provider is checked like one passed to `add_game_provider`.
+    pub fn replace_game_provider(
+        &mut self,
+        provider: Arc<dyn GameProvider + Send + Sync>,
+    ) -> &mut Self {
+        self.ops.push(UpdateOp::ReplaceGame(provider));
+        self
+    }
+
+    /// Unregisters a game and its aliases
+    pub fn remove_game(&mut self, id: &str) -> &mut Self {
+        self.ops.push(UpdateOp::RemoveGame(id.to_string()));
+        self
+    }
+
+    /// Applies the staged changes in order.
+    ///
+    /// Collects the error of every failing change and of `ContextBuilder::validate`, e.g. a game
+    /// left without its mod provider. If there is any the context is left unchanged. Otherwise
+    /// lookups see every change at once, with the capability indexes, game descriptors and
+    /// compatibility report rebuilt. The active game is deactivated only if its game was removed.
+    ///
+    /// Capabilities of the added and replaced providers and games receive `on_registered` and
+    /// `on_context_ready`. `ProviderRegistered`, `ProviderReloaded` and `ProviderRemoved` are
+    /// published for the mod providers.
+    pub fn commit(self) -> Result<(), Vec<RegistryError>> {
+        let ctx = self.ctx;
+        let mut applied = AppliedUpdate::default();
+        let deactivated = {
+            let mut registry = write_lock(&ctx.registry);
+            let mut staged = registry.to_builder(&ctx.supported_capabilities);
+            let mut errors: Vec<RegistryError> = self
+                .ops
+                .into_iter()
+                .filter_map(|op| staged.apply(op, &mut applied).err())
+                .collect();
+            if errors.is_empty() {
+                errors = staged.providers_in_use(&applied.removed_providers);
+            }
+            if errors.is_empty() {
+                errors = staged.validate_update(&applied.touched_providers);
+            }
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+
+            for (id, cap) in &applied.capabilities {
+                cap.on_registered(id);
+            }
+            let next = Registry::build(staged, Some(&registry));
+            let removed_active = ctx
+                .active_game
+                .borrow()
+                .as_ref()
+                .is_some_and(|id| !next.games.contains_key(id));
+            if removed_active {
+                ctx.active_game.send_replace(None);
+            }
+            *registry = next;
+            removed_active
+        };
+
+        for (_, cap) in &applied.capabilities {
+            cap.on_context_ready(ctx);
+        }
+        for event in applied.events {
+            ctx.publish(event);
+        }
+        if deactivated {
+            ctx.publish(ContextEvent::GameDeactivated);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Changes to a `Context`, see `Context::subscribe_events`.
+///
+/// Subscribers that fall behind receive `RecvError::Lagged` and should resync from
+/// `Context::snapshot`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ContextEvent {
+    GameActivated(String),
+    GameDeactivated,
+    /// A mod provider was registered while an event sender was attached to the `ContextBuilder`,
+    /// or by a `ContextUpdate`
+    ProviderRegistered(String),
+    /// A mod provider was removed by a `ContextUpdate`
+    ProviderRemoved(String),
+    /// A mod provider was replaced through `Context::reload_provider` or by a `ContextUpdate`
+    ProviderReloaded(String),
+}
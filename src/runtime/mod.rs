@@ -1,3 +1,11 @@
 pub mod context;
+pub mod events;
+pub mod settings;
+pub mod shutdown;
+pub mod state;
 
 pub use context::*;
+pub use events::*;
+pub use settings::*;
+pub use shutdown::*;
+pub use state::*;
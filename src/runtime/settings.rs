@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    sync::{PoisonError, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Whose values a `SettingsStore` entry belongs to, by registered (normalized) id
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum SettingsScope {
+    Provider(String),
+    Game(String),
+}
+
+/// Small key-value settings of providers and games, e.g. the last sync time or a preferred
+/// mirror. Reached through `Context::settings`.
+///
+/// Keys are separate per scope, the same key in another scope is a different entry.
+/// Hosts persisting settings pass their own store to `ContextBuilder::with_settings_store`.
+pub trait SettingsStore: Send + Sync {
+    fn get(&self, scope: SettingsScope, key: &str) -> Option<String>;
+    /// Stores `value`, replacing the previous one
+    fn set(&self, scope: SettingsScope, key: &str, value: String);
+    /// Removes an entry, returning its value
+    fn remove(&self, scope: SettingsScope, key: &str) -> Option<String>;
+    /// The keys set in `scope`, sorted
+    fn keys(&self, scope: SettingsScope) -> Vec<String>;
+}
+
+/// The store used unless the host supplies one, values are lost on exit
+#[derive(Debug, Default)]
+pub struct InMemorySettingsStore {
+    values: RwLock<HashMap<SettingsScope, HashMap<String, String>>>,
+}
+
+impl SettingsStore for InMemorySettingsStore {
+    fn get(&self, scope: SettingsScope, key: &str) -> Option<String> {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+        values.get(&scope)?.get(key).cloned()
+    }
+
+    fn set(&self, scope: SettingsScope, key: &str, value: String) {
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        values
+            .entry(scope)
+            .or_default()
+            .insert(key.to_string(), value);
+    }
+
+    fn remove(&self, scope: SettingsScope, key: &str) -> Option<String> {
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        let scoped = values.get_mut(&scope)?;
+        let value = scoped.remove(key);
+        if scoped.is_empty() {
+            values.remove(&scope);
+        }
+        value
+    }
+
+    fn keys(&self, scope: SettingsScope) -> Vec<String> {
+        let values = self.values.read().unwrap_or_else(PoisonError::into_inner);
+        let mut keys: Vec<String> = values
+            .get(&scope)
+            .map(|scoped| scoped.keys().cloned().collect())
+            .unwrap_or_default();
+        keys.sort();
+        keys
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How long `Context::shutdown` waits for tracked tasks
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What `Context::shutdown` did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ShutdownReport {
+    /// Mod providers and games whose `Provider::on_shutdown` was called, mod providers first.
+    /// Empty if the context was already shut down.
+    pub notified: Vec<String>,
+    /// Tasks from `Context::spawn_tracked` still running when the timeout elapsed
+    pub pending_tasks: usize,
+}
+
+impl ShutdownReport {
+    /// Whether every tracked task stopped in time
+    pub fn is_clean(&self) -> bool {
+        self.pending_tasks == 0
+    }
+}
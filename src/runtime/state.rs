@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Session state a host persists between launches, see `Context::export_state`.
+///
+/// Fields missing from older saves fall back to their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(default)]
+pub struct RuntimeState {
+    pub active_game: Option<String>,
+}
+
+/// What `Context::restore_state` could and couldn't apply
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RestoreReport {
+    /// The active game after the restore
+    pub active_game: Option<String>,
+    /// The saved active game, if it isn't registered anymore
+    pub missing_game: Option<String>,
+}
+
+impl RestoreReport {
+    /// Whether everything in the state was restored
+    pub fn is_complete(&self) -> bool {
+        self.missing_game.is_none()
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use reqwest::Url;
+use tokio::{
+    fs::{self, File},
+    io::AsyncWriteExt,
+    sync::{Semaphore, broadcast, watch},
+};
+use uuid::Uuid;
+
+use crate::{
+    net::DEFAULT_USER_AGENT,
+    services::download_service::{DownloadEvent, DownloadService},
+    traits::mod_provider::{DownloadProgress, ModDownloadResult},
+};
+
+/// Capacity of the channel behind `DownloadService::subscribe_events`
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Without a known size, progress is published every this many bytes
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// Settings of a `DefaultDownloadService`
+#[derive(Debug, Clone)]
+pub struct DownloadServiceConfig {
+    /// Finished downloads are saved here, the folder is created on the first download
+    pub download_dir: PathBuf,
+    /// Downloads queued beyond this wait for a running one to finish
+    pub max_concurrent: usize,
+    pub user_agent: String,
+}
+
+impl Default for DownloadServiceConfig {
+    fn default() -> Self {
+        Self {
+            download_dir: std::env::temp_dir().join("vmm-downloads"),
+            max_concurrent: 3,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+        }
+    }
+}
+
+/// Streams downloads to `DownloadServiceConfig::download_dir` with reqwest.
+///
+/// Files are named `<id>-<name>` after the download id and the last segment of the url, or just
+/// the id if the url has none, so downloads never overwrite each other. Incomplete files are kept
+/// as `<id>.part` and removed if the download fails.
+pub struct DefaultDownloadService {
+    client: reqwest::Client,
+    download_dir: PathBuf,
+    permits: Arc<Semaphore>,
+    events: broadcast::Sender<DownloadEvent>,
+}
+
+impl DefaultDownloadService {
+    /// Fails if the HTTP client can't be built, e.g. for a user agent that isn't a valid header
+    pub fn new(config: DownloadServiceConfig) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .user_agent(config.user_agent)
+            .build()?;
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Ok(Self {
+            client,
+            download_dir: config.download_dir,
+            permits: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+            events,
+        })
+    }
+}
+
+#[async_trait]
+impl DownloadService for DefaultDownloadService {
+    /// Returns right away, the download runs on a spawned task once a slot is free
+    async fn queue_download(&self, url: String) -> watch::Receiver<ModDownloadResult> {
+        let id = Uuid::new_v4();
+        let (progress, rx) = watch::channel(DownloadProgress::default().into());
+        let _ = self.events.send(DownloadEvent::Queued {
+            id,
+            url: url.clone(),
+        });
+
+        let download = Download {
+            id,
+            url,
+            client: self.client.clone(),
+            download_dir: self.download_dir.clone(),
+            events: self.events.clone(),
+            progress,
+        };
+        let permits = Arc::clone(&self.permits);
+        tokio::spawn(async move {
+            // The semaphore is never closed
+            let Ok(_permit) = permits.acquire_owned().await else {
+                return;
+            };
+            download.run().await;
+        });
+        rx
+    }
+
+    fn subscribe_events(&self) -> broadcast::Receiver<DownloadEvent> {
+        self.events.subscribe()
+    }
+}
+
+/// A queued download, run by the task `queue_download` spawns
+struct Download {
+    id: Uuid,
+    url: String,
+    client: reqwest::Client,
+    download_dir: PathBuf,
+    events: broadcast::Sender<DownloadEvent>,
+    progress: watch::Sender<ModDownloadResult>,
+}
+
+impl Download {
+    async fn run(self) {
+        let _ = self.events.send(DownloadEvent::Started { id: self.id });
+        let part = self.download_dir.join(format!("{}.part", self.id));
+        match self.fetch(&part).await {
+            Ok(path) => {
+                self.progress
+                    .send_replace(ModDownloadResult::Completed(path.clone()));
+                let _ = self
+                    .events
+                    .send(DownloadEvent::Completed { id: self.id, path });
+            }
+            Err(reason) => {
+                let _ = fs::remove_file(&part).await;
+                self.progress
+                    .send_replace(ModDownloadResult::Failed(reason.clone()));
+                let _ = self.events.send(DownloadEvent::Failed {
+                    id: self.id,
+                    reason,
+                });
+            }
+        }
+    }
+
+    /// Streams the response to `part` and moves it to its final path once complete
+    async fn fetch(&self, part: &Path) -> Result<PathBuf, String> {
+        let mut response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("status {}", status));
+        }
+        let total = response.content_length();
+        let started = Instant::now();
+
+        fs::create_dir_all(&self.download_dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut file = File::create(part).await.map_err(|e| e.to_string())?;
+        let mut received = 0;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            let previous = received;
+            received += chunk.len() as u64;
+            if progress_changed(previous, received, total) {
+                let secs = started.elapsed().as_secs_f64();
+                self.publish_progress(DownloadProgress {
+                    bytes_downloaded: received,
+                    total_bytes: total,
+                    bytes_per_second: (secs > 0.0).then(|| (received as f64 / secs) as u64),
+                });
+            }
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        drop(file);
+
+        let path = self.download_dir.join(self.file_name());
+        fs::rename(part, &path).await.map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+
+    fn publish_progress(&self, progress: DownloadProgress) {
+        self.progress.send_replace(progress.into());
+        let _ = self.events.send(DownloadEvent::Progress {
+            id: self.id,
+            bytes: progress.bytes_downloaded,
+            total: progress.total_bytes,
+        });
+    }
+
+    /// The download id followed by the last segment of the url with everything but ASCII letters,
+    /// digits, `.`, `-` and `_` dropped
+    fn file_name(&self) -> String {
+        let name: String = Url::parse(&self.url)
+            .ok()
+            .and_then(|url| {
+                url.path_segments()
+                    .and_then(|mut segments| segments.next_back().map(str::to_string))
+            })
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+            .collect();
+        match name.trim_start_matches('.') {
+            "" => self.id.to_string(),
+            name => format!("{}-{}", self.id, name),
+        }
+    }
+}
+
+/// Whether going from `previous` to `received` bytes is worth publishing: a new whole percent
+/// of `total`, or another `PROGRESS_STEP_BYTES` if the size is unknown
+fn progress_changed(previous: u64, received: u64, total: Option<u64>) -> bool {
+    match total {
+        Some(total) if total > 0 => previous * 100 / total != received * 100 / total,
+        _ => previous / PROGRESS_STEP_BYTES != received / PROGRESS_STEP_BYTES,
+    }
+}
@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use async_trait::async_trait;
-use tokio::sync::watch;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch};
+use uuid::Uuid;
 
 use crate::traits::mod_provider::ModDownloadResult;
 
@@ -8,7 +12,56 @@ pub struct QueuedDownload {
     pub url: String,
 }
 
+/// Global download lifecycle events, used by the host to observe every download at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum DownloadEvent {
+    Queued {
+        id: Uuid,
+        url: String,
+    },
+    Started {
+        id: Uuid,
+    },
+    Progress {
+        id: Uuid,
+        bytes: u64,
+        total: Option<u64>,
+    },
+    Completed {
+        id: Uuid,
+        path: PathBuf,
+    },
+    Failed {
+        id: Uuid,
+        reason: String,
+    },
+    Cancelled {
+        id: Uuid,
+    },
+}
+
+impl DownloadEvent {
+    /// The id of the download this event belongs to
+    pub fn id(&self) -> Uuid {
+        match self {
+            DownloadEvent::Queued { id, .. }
+            | DownloadEvent::Started { id }
+            | DownloadEvent::Progress { id, .. }
+            | DownloadEvent::Completed { id, .. }
+            | DownloadEvent::Failed { id, .. }
+            | DownloadEvent::Cancelled { id } => *id,
+        }
+    }
+}
+
 #[async_trait]
 pub trait DownloadService: Send + Sync {
     async fn queue_download(&self, url: String) -> watch::Receiver<ModDownloadResult>;
+
+    /// Subscribes to the events of every download handled by this service.
+    ///
+    /// Each call returns an independent receiver (see `tokio::sync::broadcast`), so
+    /// implementations should send a `DownloadEvent` at each state transition.
+    fn subscribe_events(&self) -> broadcast::Receiver<DownloadEvent>;
 }
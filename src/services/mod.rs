@@ -1,3 +1,7 @@
+#[cfg(feature = "default-services")]
+pub mod default_download_service;
 pub mod download_service;
 
-pub use download_service::{DownloadService, QueuedDownload};
+#[cfg(feature = "default-services")]
+pub use default_download_service::{DefaultDownloadService, DownloadServiceConfig};
+pub use download_service::{DownloadEvent, DownloadService, QueuedDownload};
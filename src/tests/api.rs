@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use crate::{
+    api::{DefaultProviderApi, ProviderApi},
+    registry::{RegistryError, model::ProviderSource},
+    runtime::context::{Context, ContextBuilder},
+    tests::dummy::{DummyDownloadService, DummyGameProvider, DummyModProvider},
+};
+
+fn context() -> Arc<Context> {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    Arc::new(b.freeze())
+}
+
+fn api(ctx: Arc<Context>) -> Arc<dyn ProviderApi> {
+    let api = DefaultProviderApi::new(Arc::new(DummyDownloadService::new())).into_arc();
+    api.set_context(ctx);
+    api
+}
+
+#[tokio::test]
+async fn user_profile_requires_active_game() {
+    let api = api(context());
+    let err = api.get_active_provider_user_profile().await.unwrap_err();
+    assert_eq!(err, RegistryError::NoActiveGame);
+}
+
+#[tokio::test]
+async fn user_profile_surfaces_provider_errors() {
+    let ctx = context();
+    ctx.activate_game("game-a").unwrap();
+    let api = api(ctx);
+
+    // The dummy provider has no stored key, so it can't resolve a profile
+    let err = api.get_active_provider_user_profile().await.unwrap_err();
+    assert!(matches!(err, RegistryError::ModProviderError(_)));
+}
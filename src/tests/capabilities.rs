@@ -1,17 +1,33 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU32, Ordering},
+};
 
 use crate::{
     capabilities::{
         api_key_capability::{
-            ApiKeyCapability, ApiKeyValidationError, ApiSubmitResponse, KeyAction, RequiresApiKey,
+            ApiKeyCapability, ApiKeyValidationError, ApiSubmitResponse, KeyAction, RejectAction,
+            RequiresApiKey,
+        },
+        base::{Capability, CapabilityCastExt, CapabilityDescriptor, CapabilityRef},
+        builder::{CapabilityBuilder, CapabilityError},
+        configurable_mods_capability::{ConfigurableModsBehavior, ConfigurationError},
+        form::{
+            Field, FieldError, FormResponse, FormSchema, FormValidationError, FormValue,
+            responses_to_json,
         },
-        base::{Capability, CapabilityCastExt, CapabilityRef},
-        builder::CapabilityBuilder,
         ids,
+        oauth_capability::{OAuthCallback, OAuthCapability, OAuthPrompt, RequiresOAuth},
+        profiles_capability::ProfileError,
+        search_capability::{FullTextSearchCapability, SupportsFullTextSearch},
+        update_checker_capability::ChecksForUpdates,
     },
     capability,
-    tests::dummy::DummyModProvider,
-    traits::provider::Provider,
+    tests::dummy::{DummyGameProvider, DummyModProvider},
+    traits::{
+        discovery::{DiscoveryError, InstalledMod, UpdateInfo},
+        provider::Provider,
+    },
 };
 
 #[test]
@@ -35,7 +51,7 @@ fn api_key_validation_whitespace_handling() {
         value: "    \t\n    ".to_string(),
     };
     assert!(matches!(
-        api_cap.on_provided(&vec![resp_ws]),
+        api_cap.on_provided(&[resp_ws]),
         Err(ApiKeyValidationError::Empty)
     ));
 
@@ -44,7 +60,7 @@ fn api_key_validation_whitespace_handling() {
         value: "     ABCDEFGHIJKLMNOP   ".to_string(),
     };
 
-    let result = api_cap.on_provided(&vec![resp_padded]);
+    let result = api_cap.on_provided(&[resp_padded]);
     assert!(result.is_ok());
 }
 
@@ -193,3 +209,967 @@ fn capability_macro_assigns_id_and_downcast() {
     let dyn_ref: &dyn Capability = &*cap;
     assert!(dyn_ref.get::<SimpleCap>().is_some());
 }
+
+#[test]
+fn finish_checked_rejects_duplicate_ids() {
+    let provider = DummyModProvider::new("builder-test");
+    let Err(err) = CapabilityBuilder::new_from_arc(&provider)
+        .api_key()
+        .with_cap(SimpleCap)
+        .api_key()
+        .finish_checked()
+    else {
+        panic!("duplicate api key capability wasn't detected");
+    };
+    assert_eq!(
+        err,
+        CapabilityError::DuplicateCapability(ids::REQUIRES_API_KEY.into())
+    );
+
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(serde_json::from_str::<CapabilityError>(&json).unwrap(), err);
+
+    let caps = CapabilityBuilder::new_from_arc(&provider)
+        .api_key()
+        .with_cap(SimpleCap)
+        .finish_checked()
+        .unwrap();
+    assert_eq!(caps.len(), 2);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "DuplicateCapability")]
+fn finish_panics_on_duplicate_ids_in_debug_builds() {
+    let provider = DummyModProvider::new("builder-test");
+    CapabilityBuilder::new_from_arc(&provider)
+        .with_cap(SimpleCap)
+        .with_cap(SimpleCap)
+        .finish();
+}
+
+#[test]
+fn capability_builder_adds_prebuilt_caps() {
+    let provider = DummyModProvider::new("builder-test");
+    let caps = CapabilityBuilder::new_from_arc(&provider)
+        .api_key()
+        .with_cap(SimpleCap)
+        .with_cap_if(false, SimpleCap)
+        .finish();
+
+    let ids: Vec<&str> = caps.iter().map(|c| c.id()).collect();
+    assert_eq!(ids, [ids::REQUIRES_API_KEY, "test.simple"]);
+    assert!(caps[1].get::<SimpleCap>().is_some());
+}
+
+#[test]
+fn api_key_cap_default_render_flow_is_single_step() {
+    let provider = DummyModProvider::new("dummy");
+    let cap = provider.capabilities()[0].clone();
+    let api_key = cap.as_requires_api_key().expect("should expose behavior");
+
+    let flow = api_key.render_flow().expect("flow should exist");
+    assert_eq!(flow.steps.len(), 1);
+    assert_eq!(flow.steps[0].schema.fields[0].id, "api_key");
+}
+
+/// Device code provider which authorizes on the second poll
+struct DeviceCodeProvider {
+    polls: AtomicU32,
+}
+
+#[async_trait::async_trait]
+impl RequiresOAuth for DeviceCodeProvider {
+    fn begin_auth(&self) -> Result<OAuthPrompt, CapabilityError> {
+        Ok(OAuthPrompt::DeviceCode {
+            device_code: "device".into(),
+            user_code: "ABCD-1234".into(),
+            verification_uri: "https://example.com/activate".into(),
+            interval_secs: 5,
+            expires_in_secs: Some(900),
+        })
+    }
+
+    async fn complete_auth(
+        &self,
+        callback: OAuthCallback,
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        match callback {
+            OAuthCallback::DevicePoll { device_code } if device_code == "device" => {
+                if self.polls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(ApiKeyValidationError::AuthorizationPending)
+                } else {
+                    Ok(KeyAction::Store)
+                }
+            }
+            _ => Err(ApiKeyValidationError::Invalid),
+        }
+    }
+
+    fn needs_prompt(&self, existing_token: Option<&str>) -> bool {
+        existing_token.is_none()
+    }
+}
+
+#[tokio::test]
+async fn oauth_cap_device_code_flow() {
+    let provider = Arc::new(DeviceCodeProvider {
+        polls: AtomicU32::new(0),
+    });
+    let caps = CapabilityBuilder::new_from_arc(&provider).oauth().finish();
+    assert_eq!(caps[0].id(), ids::REQUIRES_OAUTH);
+    assert!(caps[0].as_requires_api_key().is_none());
+
+    let oauth = caps[0].as_requires_oauth().expect("should expose behavior");
+    assert!(oauth.needs_prompt(None));
+
+    let OAuthPrompt::DeviceCode { device_code, .. } = oauth.begin_auth().unwrap() else {
+        panic!("expected a device code prompt");
+    };
+    let poll = || OAuthCallback::DevicePoll {
+        device_code: device_code.clone(),
+    };
+    assert_eq!(
+        oauth.complete_auth(poll()).await,
+        Err(ApiKeyValidationError::AuthorizationPending)
+    );
+    assert_eq!(oauth.complete_auth(poll()).await, Ok(KeyAction::Store));
+    assert!(oauth.refresh_auth().await.is_err());
+}
+
+#[tokio::test]
+async fn oauth_cap_provider_dropped() {
+    let cap: CapabilityRef = {
+        let provider = Arc::new(DeviceCodeProvider {
+            polls: AtomicU32::new(0),
+        });
+        Arc::new(OAuthCapability::new(Arc::downgrade(&provider)))
+    };
+    let oauth = cap.as_requires_oauth().unwrap();
+
+    assert!(matches!(
+        oauth.begin_auth(),
+        Err(CapabilityError::ProviderDropped)
+    ));
+    assert_eq!(
+        oauth
+            .complete_auth(OAuthCallback::Redirect {
+                code: "code".into(),
+                state: None,
+            })
+            .await,
+        Err(ApiKeyValidationError::ProviderError)
+    );
+}
+
+/// A game whose mods have a single "enabled" toggle
+struct ConfigurableGame {
+    applied: Mutex<Vec<(String, Vec<FormResponse>)>>,
+    caps: Vec<CapabilityRef>,
+}
+
+impl ConfigurableGame {
+    fn new() -> Arc<Self> {
+        Arc::new_cyclic(|weak| ConfigurableGame {
+            applied: Mutex::new(Vec::new()),
+            caps: CapabilityBuilder::new_from_weak(weak.clone())
+                .configurable_mods()
+                .finish(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl ConfigurableModsBehavior for ConfigurableGame {
+    fn get_configurable(&self, mod_id: &str) -> Result<Option<FormSchema>, CapabilityError> {
+        Ok((mod_id == "mod-1").then(|| {
+            FormSchema::builder("Settings")
+                .field(Field::checkbox("enabled", true))
+                .build()
+                .unwrap()
+        }))
+    }
+
+    async fn apply_configuration(
+        &self,
+        mod_id: &str,
+        responses: Vec<FormResponse>,
+    ) -> Result<(), ConfigurationError> {
+        let schema = self
+            .get_configurable(mod_id)?
+            .ok_or_else(|| ConfigurationError::ModNotFound(mod_id.to_string()))?;
+        schema
+            .validate(&responses)
+            .map_err(|e| ConfigurationError::InvalidField {
+                id: "enabled".into(),
+                reason: e.for_field("enabled").unwrap_or_default().to_string(),
+            })?;
+
+        self.applied
+            .lock()
+            .unwrap()
+            .push((mod_id.to_string(), responses));
+        Ok(())
+    }
+
+    fn export_configuration(&self, mod_id: &str) -> Option<serde_json::Value> {
+        let applied = self.applied.lock().unwrap();
+        let (_, responses) = applied.iter().rev().find(|(id, _)| id == mod_id)?;
+        Some(responses_to_json(responses))
+    }
+}
+
+fn enabled(value: &str) -> Vec<FormResponse> {
+    vec![FormResponse {
+        id: "enabled".into(),
+        value: value.into(),
+    }]
+}
+
+#[tokio::test]
+async fn configurable_mods_cap_through_accessor() {
+    let game = ConfigurableGame::new();
+    let cap: CapabilityRef = game.caps[0].clone();
+    assert_eq!(cap.id(), ids::CONFIGURABLE_MODS);
+
+    let configurable = cap.as_configurable_mods().expect("should expose behavior");
+    assert!(configurable.get_configurable("other").unwrap().is_none());
+    let schema = configurable.get_configurable("mod-1").unwrap().unwrap();
+    assert_eq!(schema.fields[0].id, "enabled");
+
+    configurable
+        .apply_configuration("mod-1", enabled("false"))
+        .await
+        .unwrap();
+    assert_eq!(game.applied.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn configurable_mods_cap_reports_failures() {
+    let game = ConfigurableGame::new();
+    let configurable = game.caps[0].as_configurable_mods().unwrap();
+
+    assert_eq!(
+        configurable
+            .apply_configuration("missing", enabled("true"))
+            .await,
+        Err(ConfigurationError::ModNotFound("missing".into()))
+    );
+    assert!(matches!(
+        configurable
+            .apply_configuration("mod-1", enabled("maybe"))
+            .await,
+        Err(ConfigurationError::InvalidField { .. })
+    ));
+    assert!(game.applied.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn configurable_mods_cap_provider_dropped() {
+    let cap = ConfigurableGame::new().caps[0].clone();
+    let configurable = cap.as_configurable_mods().unwrap();
+
+    assert_eq!(
+        configurable.get_configurable("mod-1").unwrap_err(),
+        CapabilityError::ProviderDropped
+    );
+    assert_eq!(
+        configurable
+            .apply_configuration("mod-1", enabled("true"))
+            .await,
+        Err(ConfigurationError::ProviderDropped)
+    );
+}
+
+#[tokio::test]
+async fn configurable_mods_cap_validates_and_resets() {
+    let game = ConfigurableGame::new();
+    let configurable = game.caps[0].as_configurable_mods().unwrap();
+
+    assert_eq!(
+        configurable.validate_configuration("mod-1", &enabled("true")),
+        Ok(())
+    );
+    let err = configurable
+        .validate_configuration("mod-1", &enabled("maybe"))
+        .unwrap_err();
+    assert!(err.for_field("enabled").is_some());
+    assert_eq!(configurable.validate_configuration("other", &[]), Ok(()));
+    assert!(
+        configurable
+            .validate_configuration("other", &enabled("true"))
+            .is_err()
+    );
+
+    configurable.reset_to_defaults("mod-1").await.unwrap();
+    configurable.reset_to_defaults("other").await.unwrap();
+    let applied = game.applied.lock().unwrap().clone();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied[0].0, "mod-1");
+    assert_eq!(applied[0].1[0].value, FormValue::Bool(true));
+
+    let cap = game.caps[0].clone();
+    drop(game);
+    let configurable = cap.as_configurable_mods().unwrap();
+    assert!(matches!(
+        configurable.validate_configuration("mod-1", &enabled("true")),
+        Err(FormValidationError::SchemaUnavailable(_))
+    ));
+    assert_eq!(
+        configurable.reset_to_defaults("mod-1").await,
+        Err(ConfigurationError::ProviderDropped)
+    );
+}
+
+#[tokio::test]
+async fn configurable_mods_cap_export_import_round_trip() {
+    let game = ConfigurableGame::new();
+    let configurable = game.caps[0].as_configurable_mods().unwrap();
+    assert_eq!(configurable.export_configuration("mod-1"), None);
+
+    configurable
+        .apply_configuration(
+            "mod-1",
+            vec![FormResponse {
+                id: "enabled".into(),
+                value: false.into(),
+            }],
+        )
+        .await
+        .unwrap();
+    let backup = configurable.export_configuration("mod-1").unwrap();
+    assert_eq!(backup, serde_json::json!({ "enabled": false }));
+
+    configurable.reset_to_defaults("mod-1").await.unwrap();
+    configurable
+        .import_configuration("mod-1", backup.clone())
+        .await
+        .unwrap();
+    assert_eq!(configurable.export_configuration("mod-1"), Some(backup));
+
+    assert!(matches!(
+        configurable
+            .import_configuration("mod-1", serde_json::json!(["enabled"]))
+            .await,
+        Err(ConfigurationError::Invalid(
+            FormValidationError::InvalidImport(_)
+        ))
+    ));
+    assert!(matches!(
+        configurable
+            .import_configuration("mod-1", serde_json::json!({ "enabled": null }))
+            .await,
+        Err(ConfigurationError::Invalid(
+            FormValidationError::InvalidImport(_)
+        ))
+    ));
+    assert!(matches!(
+        configurable
+            .import_configuration("mod-1", serde_json::json!({ "enabled": "maybe" }))
+            .await,
+        Err(ConfigurationError::Invalid(FormValidationError::Fields(_)))
+    ));
+    assert_eq!(game.applied.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn accessors_only_expose_matching_behavior() {
+    let api_key = DummyModProvider::new("dummy");
+    let oauth = Arc::new(DeviceCodeProvider {
+        polls: AtomicU32::new(0),
+    });
+    let game = ConfigurableGame::new();
+
+    let mut caps: Vec<CapabilityRef> = api_key.capabilities().to_vec();
+    caps.extend(CapabilityBuilder::new_from_arc(&oauth).oauth().finish());
+    caps.extend(game.caps.iter().cloned());
+
+    for cap in &caps {
+        let id = cap.id();
+        assert_eq!(
+            cap.as_requires_api_key().is_some(),
+            id == ids::REQUIRES_API_KEY
+        );
+        assert_eq!(cap.as_requires_oauth().is_some(), id == ids::REQUIRES_OAUTH);
+        assert_eq!(
+            cap.as_configurable_mods().is_some(),
+            id == ids::CONFIGURABLE_MODS
+        );
+        assert!(cap.as_has_multiple_files().is_none());
+        assert!(cap.as_resolves_dependencies().is_none());
+    }
+}
+
+/// A capability which is its own behavior, declared through the macro
+struct StaticConfig;
+
+#[async_trait::async_trait]
+impl ConfigurableModsBehavior for StaticConfig {
+    fn get_configurable(&self, _mod_id: &str) -> Result<Option<FormSchema>, CapabilityError> {
+        Ok(FormSchema::builder("Static").build().ok())
+    }
+    async fn apply_configuration(
+        &self,
+        _mod_id: &str,
+        _responses: Vec<FormResponse>,
+    ) -> Result<(), ConfigurationError> {
+        Ok(())
+    }
+}
+
+capability!(StaticConfig, "test.static_config", configurable_mods);
+
+#[test]
+fn capability_macro_overrides_accessor() {
+    let cap: CapabilityRef = Arc::new(StaticConfig);
+    assert_eq!(cap.id(), "test.static_config");
+    assert!(cap.as_requires_api_key().is_none());
+
+    let configurable = cap.as_configurable_mods().expect("should expose behavior");
+    assert_eq!(
+        configurable.get_configurable("any").unwrap().unwrap().title,
+        "Static"
+    );
+}
+
+#[tokio::test]
+async fn api_key_cap_async_validation() {
+    let provider = DummyModProvider::new("dummy");
+    let api_key = provider.capabilities()[0]
+        .as_requires_api_key()
+        .expect("should expose behavior");
+    let submit = |value: &str| {
+        vec![ApiSubmitResponse {
+            id: "api_key".into(),
+            value: value.into(),
+        }]
+    };
+
+    assert_eq!(
+        api_key.on_provided_async(&submit("ABCDEFGHIJKLMNOP")).await,
+        Ok(KeyAction::Store)
+    );
+    // Format checks still run first
+    assert_eq!(
+        api_key.on_provided_async(&submit("SHORT")).await,
+        Err(ApiKeyValidationError::TooShort { min_len: 16 })
+    );
+    // The sync path only checks the format
+    assert!(api_key.on_provided(&submit("REVOKEDABCDEFGHIJ")).is_ok());
+    assert_eq!(
+        api_key
+            .on_provided_async(&submit("REVOKEDABCDEFGHIJ"))
+            .await,
+        Err(ApiKeyValidationError::Invalid)
+    );
+    assert_eq!(
+        api_key
+            .on_provided_async(&submit("OFFLINEABCDEFGHIJ"))
+            .await,
+        Err(ApiKeyValidationError::ProviderError)
+    );
+}
+
+/// A provider relying on the default async validation
+struct SyncOnlyApiKey;
+
+impl RequiresApiKey for SyncOnlyApiKey {
+    fn on_provided(
+        &self,
+        values: &[ApiSubmitResponse],
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        match values.first() {
+            Some(v) if !v.value.is_empty() => Ok(KeyAction::DontStore),
+            _ => Err(ApiKeyValidationError::Empty),
+        }
+    }
+    fn needs_prompt(&self, _existing_key: Option<&str>) -> bool {
+        true
+    }
+    fn render(&self) -> Result<FormSchema, CapabilityError> {
+        Ok(FormSchema::builder("Key").build().unwrap())
+    }
+}
+
+/// A behavior-bearing capability declared purely through the macro
+struct StaticKey;
+
+impl RequiresApiKey for StaticKey {
+    fn on_provided(
+        &self,
+        _values: &[ApiSubmitResponse],
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        Ok(KeyAction::StoreSecure)
+    }
+    fn needs_prompt(&self, existing_key: Option<&str>) -> bool {
+        existing_key.is_none()
+    }
+    fn render(&self) -> Result<FormSchema, CapabilityError> {
+        Ok(FormSchema::builder("Static key").build().unwrap())
+    }
+}
+
+capability!(StaticKey, "test.static_key", version = 2, requires_api_key);
+
+#[test]
+fn capability_macro_with_version_and_accessor() {
+    let cap: CapabilityRef = Arc::new(StaticKey);
+    assert_eq!(cap.id(), "test.static_key");
+    assert_eq!(cap.version(), 2);
+    assert!(cap.as_configurable_mods().is_none());
+
+    let typed = cap.as_ref().get::<StaticKey>().expect("should downcast");
+    assert!(typed.needs_prompt(None));
+
+    let api_key = cap.as_requires_api_key().expect("should expose behavior");
+    assert_eq!(api_key.on_provided(&[]), Ok(KeyAction::StoreSecure));
+    assert_eq!(api_key.render().unwrap().title, "Static key");
+}
+
+#[tokio::test]
+async fn api_key_cap_async_defaults_to_sync() {
+    let provider = Arc::new(SyncOnlyApiKey);
+    let caps = CapabilityBuilder::new_from_arc(&provider)
+        .api_key()
+        .finish();
+    let api_key = caps[0].as_requires_api_key().unwrap();
+
+    assert_eq!(
+        api_key.on_provided_async(&[]).await,
+        Err(ApiKeyValidationError::Empty)
+    );
+    drop(provider);
+    assert_eq!(
+        api_key.on_provided_async(&[]).await,
+        Err(ApiKeyValidationError::ProviderError)
+    );
+}
+
+/// A provider mixing built-in and custom capabilities
+struct DescribedProvider {
+    caps: Vec<CapabilityRef>,
+}
+
+impl Provider for DescribedProvider {
+    fn id(&self) -> &'static str {
+        "described"
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &self.caps
+    }
+}
+
+#[test]
+fn provider_describes_capabilities_in_order() {
+    let api_key = DummyModProvider::new("dummy");
+    let game = ConfigurableGame::new();
+    let mut caps: Vec<CapabilityRef> = vec![Arc::new(SimpleCap)];
+    caps.extend(game.caps.iter().cloned());
+    caps.extend(api_key.capabilities().iter().cloned());
+    let provider = DescribedProvider { caps };
+
+    let descriptors = provider.describe_capabilities();
+    let ids: Vec<&str> = descriptors.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec!["test.simple", ids::CONFIGURABLE_MODS, ids::REQUIRES_API_KEY]
+    );
+    assert_eq!(
+        descriptors[0],
+        CapabilityDescriptor {
+            id: "test.simple".into(),
+            display_name: "Simple".into(),
+            description: None,
+            configurable: false,
+        }
+    );
+    assert_eq!(descriptors[2].display_name, "API key");
+    assert!(descriptors[2].configurable);
+
+    let json = serde_json::to_string(&descriptors).unwrap();
+    assert_eq!(
+        json,
+        serde_json::to_string(&provider.describe_capabilities()).unwrap()
+    );
+    let back: Vec<CapabilityDescriptor> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, descriptors);
+}
+
+#[test]
+fn capability_ids_map_to_descriptors() {
+    let id = ids::CapabilityId::from_id(ids::HAS_MULTIPLE_FILES).unwrap();
+    assert_eq!(id, ids::CapabilityId::HAS_MULTIPLE_FILES);
+    assert_eq!(id.descriptor().display_name, "Multiple files per mod");
+    assert!(!id.configurable());
+    assert!(ids::CapabilityId::from_id("unknown.id").is_none());
+}
+
+#[test]
+fn capability_ids_can_be_enumerated_and_parsed() {
+    use ids::CapabilityId;
+
+    assert_eq!(CapabilityId::count(), CapabilityId::all().len());
+    assert_eq!(CapabilityId::all(), CapabilityId::ALL);
+    for id in CapabilityId::all() {
+        assert_eq!(CapabilityId::from_str(id.as_str()), Some(*id));
+    }
+    assert_eq!(
+        CapabilityId::from_str(ids::REQUIRES_API_KEY),
+        Some(CapabilityId::REQUIRES_API_KEY)
+    );
+    assert_eq!(CapabilityId::from_str("vmm.mod.unknown"), None);
+}
+
+#[tokio::test]
+async fn api_key_cap_transformed_key() {
+    let provider = DummyModProvider::new("dummy");
+    let api_key = provider.capabilities()[0].as_requires_api_key().unwrap();
+    let raw = "EXCHANGEABCDEFGHIJ";
+
+    let action = api_key
+        .on_provided_async(&[ApiSubmitResponse {
+            id: "api_key".into(),
+            value: raw.into(),
+        }])
+        .await
+        .unwrap();
+    assert_eq!(
+        action,
+        KeyAction::StoreTransformed {
+            value: "session-ABCDEFGHIJ".into()
+        }
+    );
+    assert_eq!(action.stored_value(raw), Some("session-ABCDEFGHIJ"));
+}
+
+#[test]
+fn key_action_storage_and_serialization() {
+    assert!(!KeyAction::DontStore.should_store());
+    assert_eq!(KeyAction::DontStore.stored_value("raw"), None);
+    assert_eq!(KeyAction::StoreSecure.stored_value("raw"), Some("raw"));
+    assert!(KeyAction::StoreWithExpiry { expires_at: 0 }.should_store());
+
+    // Unit variants keep their original representation
+    assert_eq!(
+        serde_json::to_string(&KeyAction::Store).unwrap(),
+        r#""Store""#
+    );
+    let json = serde_json::to_string(&KeyAction::StoreWithExpiry {
+        expires_at: 1_700_000_000,
+    })
+    .unwrap();
+    assert_eq!(json, r#"{"StoreWithExpiry":{"expires_at":1700000000}}"#);
+    let back: KeyAction = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        back,
+        KeyAction::StoreWithExpiry {
+            expires_at: 1_700_000_000
+        }
+    );
+}
+
+/// A provider asking for a key and the matching account id
+struct AccountKeyProvider;
+
+impl RequiresApiKey for AccountKeyProvider {
+    fn on_provided(
+        &self,
+        values: &[ApiSubmitResponse],
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        let responses: Vec<FormResponse> = values.iter().cloned().map(Into::into).collect();
+        self.render()
+            .map_err(|_| ApiKeyValidationError::ProviderError)?
+            .validate(&responses)?;
+        Ok(KeyAction::Store)
+    }
+    fn on_rejected(&self) -> RejectAction {
+        RejectAction::ContinueAnonymous
+    }
+    fn needs_prompt(&self, existing_key: Option<&str>) -> bool {
+        existing_key.is_none()
+    }
+    fn render(&self) -> Result<FormSchema, CapabilityError> {
+        Ok(FormSchema::builder("Account")
+            .field(Field::password("api_key").required())
+            .field(Field::text("account_id").regex(r"^\d+$").required())
+            .build()
+            .unwrap())
+    }
+}
+
+#[test]
+fn api_key_field_errors_target_fields() {
+    let provider = Arc::new(AccountKeyProvider);
+    let caps = CapabilityBuilder::new_from_arc(&provider)
+        .api_key()
+        .finish();
+    let api_key = caps[0].as_requires_api_key().unwrap();
+    let submit = |key: &str, account: &str| {
+        vec![
+            ApiSubmitResponse {
+                id: "api_key".into(),
+                value: key.into(),
+            },
+            ApiSubmitResponse {
+                id: "account_id".into(),
+                value: account.into(),
+            },
+        ]
+    };
+
+    assert_eq!(
+        api_key.on_provided(&submit("key", "42")),
+        Ok(KeyAction::Store)
+    );
+
+    let Err(ApiKeyValidationError::FieldErrors(errors)) =
+        api_key.on_provided(&submit("key", "abc"))
+    else {
+        panic!("expected field errors");
+    };
+    let ids: Vec<&str> = errors.iter().map(|e| e.field_id.as_str()).collect();
+    assert_eq!(ids, vec!["account_id"]);
+
+    let Err(ApiKeyValidationError::FieldErrors(errors)) = api_key.on_provided(&submit("", "abc"))
+    else {
+        panic!("expected field errors");
+    };
+    let ids: Vec<&str> = errors.iter().map(|e| e.field_id.as_str()).collect();
+    assert_eq!(ids, vec!["api_key", "account_id"]);
+}
+
+#[test]
+fn api_key_field_error_constructor() {
+    let err = ApiKeyValidationError::field("account_id", "Unknown account");
+    assert_eq!(
+        err,
+        ApiKeyValidationError::FieldErrors(vec![FieldError {
+            field_id: "account_id".into(),
+            message: "Unknown account".into(),
+        }])
+    );
+    let json = serde_json::to_string(&err).unwrap();
+    let back: ApiKeyValidationError = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, err);
+}
+
+#[test]
+fn api_key_cap_reject_actions() {
+    let dummy = DummyModProvider::new("dummy");
+    let default = dummy.capabilities()[0].as_requires_api_key().unwrap();
+    assert_eq!(default.on_rejected(), RejectAction::RetryNextLaunch);
+
+    let account = Arc::new(AccountKeyProvider);
+    let caps = CapabilityBuilder::new_from_arc(&account).api_key().finish();
+    let overridden = caps[0].as_requires_api_key().unwrap();
+    assert_eq!(overridden.on_rejected(), RejectAction::ContinueAnonymous);
+
+    drop(account);
+    assert_eq!(overridden.on_rejected(), RejectAction::RetryNextLaunch);
+
+    let json = serde_json::to_string(&RejectAction::DisableForSession).unwrap();
+    assert_eq!(
+        serde_json::from_str::<RejectAction>(&json).unwrap(),
+        RejectAction::DisableForSession
+    );
+}
+
+#[test]
+fn profiles_cap_create_and_switch() {
+    let game = DummyGameProvider::new("game-a", "mod:p");
+    let cap = game.find_capability(ids::SUPPORTS_PROFILES).unwrap();
+    let profiles = cap.as_supports_profiles().unwrap();
+    assert_eq!(profiles.active_profile().unwrap().id, "default");
+
+    let overhaul = profiles.create_profile("Full Overhaul").unwrap();
+    assert_eq!(overhaul.id, "full-overhaul");
+    assert_eq!(profiles.list_profiles().len(), 2);
+    assert_eq!(
+        profiles.create_profile("full overhaul"),
+        Err(ProfileError::AlreadyExists("full overhaul".into()))
+    );
+
+    profiles.activate_profile("full-overhaul").unwrap();
+    assert_eq!(profiles.active_profile(), Some(overhaul));
+    profiles.delete_profile("default").unwrap();
+    assert_eq!(profiles.list_profiles().len(), 1);
+}
+
+#[test]
+fn profiles_cap_edge_cases() {
+    let game = DummyGameProvider::new("game-a", "mod:p");
+    let profiles = game.capabilities()[0].as_supports_profiles().unwrap();
+
+    assert_eq!(
+        profiles.activate_profile("missing"),
+        Err(ProfileError::NotFound("missing".into()))
+    );
+    assert_eq!(
+        profiles.delete_profile("default"),
+        Err(ProfileError::ProfileActive("default".into()))
+    );
+    assert_eq!(profiles.active_profile().unwrap().id, "default");
+
+    let cap = game.capabilities()[0].clone();
+    drop(game);
+    let profiles = cap.as_supports_profiles().unwrap();
+    assert!(profiles.list_profiles().is_empty());
+    assert_eq!(
+        profiles.create_profile("New"),
+        Err(ProfileError::ProviderDropped)
+    );
+}
+
+#[allow(dead_code)]
+mod versioned_ids {
+    crate::define_capabilities! {
+        PLAIN_CAP = "test.plain";
+        VERSIONED_CAP(2..=4) = "test.versioned", "Versioned", configurable;
+        #[deprecated(since = "0.4.0", note = "Use PLAIN_CAP instead")]
+        RETIRED_CAP = "test.retired";
+    }
+}
+
+#[test]
+fn define_capabilities_supported_versions() {
+    use versioned_ids::CapabilityId;
+
+    assert_eq!(CapabilityId::ALL.len(), 3);
+    assert_eq!(CapabilityId::count(), 3);
+    assert_eq!(CapabilityId::PLAIN_CAP.supported_versions(), 1..=1);
+    assert_eq!(CapabilityId::VERSIONED_CAP.supported_versions(), 2..=4);
+    assert!(CapabilityId::VERSIONED_CAP.configurable());
+    assert_eq!(
+        ids::CapabilityId::REQUIRES_API_KEY.supported_versions(),
+        1..=1
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_capabilities_still_resolve() {
+    use versioned_ids::{CapabilityId, RETIRED_CAP};
+
+    assert_eq!(RETIRED_CAP, "test.retired");
+    assert_eq!(
+        CapabilityId::from_id("test.retired"),
+        Some(CapabilityId::RETIRED_CAP)
+    );
+    assert_eq!(CapabilityId::RETIRED_CAP.display_name(), "Retired");
+}
+
+#[test]
+fn provider_capability_helpers() {
+    let provider = DescribedProvider {
+        caps: vec![Arc::new(SimpleCap), Arc::new(SimpleCap)],
+    };
+
+    assert!(provider.has_capability("test.simple"));
+    assert!(!provider.has_capability(ids::REQUIRES_API_KEY));
+    assert_eq!(provider.capabilities_of_type::<SimpleCap>().len(), 2);
+    assert!(
+        provider
+            .capabilities_of_type::<ApiKeyCapability<DummyModProvider>>()
+            .is_empty()
+    );
+    assert_eq!(provider.validate(), Ok(()));
+}
+
+struct UpdatingProvider;
+
+#[async_trait::async_trait]
+impl ChecksForUpdates for UpdatingProvider {
+    async fn check_updates(
+        &self,
+        installed: &[InstalledMod],
+    ) -> Result<Vec<UpdateInfo>, DiscoveryError> {
+        Ok(installed
+            .iter()
+            .filter(|m| m.installed_version != "2.0.0")
+            .map(|m| UpdateInfo {
+                mod_id: m.mod_id.clone(),
+                current_version: m.installed_version.clone(),
+                latest_version: "2.0.0".into(),
+                release_notes: None,
+            })
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn update_checker_cap_delegates() {
+    let provider = Arc::new(UpdatingProvider);
+    let caps = CapabilityBuilder::new_from_arc(&provider)
+        .update_checker()
+        .finish();
+    assert_eq!(caps[0].id(), ids::UPDATE_CHECKER);
+
+    let checker = caps[0].as_checks_for_updates().unwrap();
+    let installed = vec![
+        InstalledMod {
+            mod_id: "old".into(),
+            installed_version: "1.0.0".into(),
+        },
+        InstalledMod {
+            mod_id: "current".into(),
+            installed_version: "2.0.0".into(),
+        },
+    ];
+    let updates = checker.check_updates(&installed).await.unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].mod_id, "old");
+    assert_eq!(updates[0].current_version, "1.0.0");
+
+    drop(provider);
+    assert!(matches!(
+        checker.check_updates(&installed).await,
+        Err(DiscoveryError::ProviderUnavailable)
+    ));
+}
+
+struct SearchingProvider;
+
+impl SupportsFullTextSearch for SearchingProvider {}
+
+#[test]
+fn full_text_search_is_a_marker() {
+    let provider = Arc::new(SearchingProvider);
+    let caps = CapabilityBuilder::new_from_arc(&provider)
+        .full_text_search()
+        .finish();
+    assert_eq!(caps[0].id(), ids::FULL_TEXT_SEARCH);
+    assert!(caps[0].get::<FullTextSearchCapability>().is_some());
+}
+
+#[test]
+fn capability_error_maps_http_failures() {
+    use crate::net::https::HttpError;
+
+    assert_eq!(
+        CapabilityError::from(HttpError::Network("dns lookup failed".into())),
+        CapabilityError::Network("dns lookup failed".into())
+    );
+    let timeout = CapabilityError::from(HttpError::Timeout { elapsed_ms: 5000 });
+    assert_eq!(timeout, CapabilityError::Timeout(5000));
+    assert!(matches!(
+        CapabilityError::from(HttpError::RateLimit {
+            retry_after_secs: None
+        }),
+        CapabilityError::Network(_)
+    ));
+
+    let json = serde_json::to_string(&timeout).unwrap();
+    assert_eq!(
+        serde_json::from_str::<CapabilityError>(&json).unwrap(),
+        timeout
+    );
+
+    assert!(matches!(
+        DiscoveryError::from(timeout),
+        DiscoveryError::Timeout { elapsed_ms: 5000 }
+    ));
+    assert!(matches!(
+        DiscoveryError::from(CapabilityError::ProviderDropped),
+        DiscoveryError::ProviderUnavailable
+    ));
+}
@@ -1,18 +1,138 @@
-use std::sync::Arc;
+use std::{
+    path::Path,
+    sync::{
+        Arc, Barrier, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use crate::{
-    registry::{RegistryError, model::ProviderSource},
-    runtime::context::ContextBuilder,
+    capabilities::{
+        api_key_capability::ApiKeyCapability,
+        base::{Capability, CapabilityRef, SupportedCapabilities, VersionRange},
+        builder::CapabilityBuilder,
+        game_launcher_capability::{GameLaunchError, LaunchesGame},
+        ids,
+    },
+    capability,
+    registry::{
+        RegistryError,
+        audit::{MAX_REGISTRY_EVENTS, RegistryAction, RegistryEvent, RegistryOutcome},
+        model::{
+            ContextSnapshot, PluginOrigin, ProviderOptions, ProviderSource, ProviderSourceMatcher,
+        },
+        validation::{ValidationReport, ValidationWarning},
+    },
+    runtime::{
+        context::{Context, ContextBuilder},
+        events::ContextEvent,
+        state::{RestoreReport, RuntimeState},
+    },
     tests::dummy::{DummyGameProvider, DummyModProvider},
+    traits::{
+        discovery::{DiscoveryError, DiscoveryQuery, DiscoveryResult, ModExtendedMetadata},
+        game_provider::{
+            GameIcon, GameInstallError, GameLaunchHandle, GameMetadata, GameProvider,
+            GameVerifyReport,
+        },
+        mod_provider::{ModDownloadResult, ModProvider},
+        provider::{Provider, ProviderHealthStatus, ProviderValidationError},
+    },
 };
 
+/// Records every lifecycle hook it receives
+#[derive(Default)]
+struct CountingCap {
+    events: Mutex<Vec<String>>,
+}
+
+impl Capability for CountingCap {
+    fn id(&self) -> &'static str {
+        "test.counting"
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn on_registered(&self, provider_id: &str) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("registered:{}", provider_id));
+    }
+    fn on_context_ready(&self, ctx: &Context) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("ready:{}", ctx.list_mod_providers_detailed().len()));
+    }
+}
+
+/// An api key capability from a plugin built against a future lib-vmm
+struct FutureApiKeyCap;
+capability!(FutureApiKeyCap, ids::REQUIRES_API_KEY, version = 3);
+
+struct LifecycleModProvider {
+    caps: Vec<CapabilityRef>,
+}
+
+#[async_trait::async_trait]
+impl Provider for LifecycleModProvider {
+    fn id(&self) -> &'static str {
+        "mod:lifecycle"
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &self.caps
+    }
+    fn validate(&self) -> Result<(), Vec<ProviderValidationError>> {
+        let mut seen = Vec::new();
+        let errors: Vec<ProviderValidationError> = self
+            .caps
+            .iter()
+            .filter(|c| {
+                let duplicate = seen.contains(&c.id());
+                seen.push(c.id());
+                duplicate
+            })
+            .map(|c| ProviderValidationError {
+                message: "Duplicate capability".into(),
+                capability: Some(c.id().to_string()),
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    async fn health_check(&self) -> ProviderHealthStatus {
+        if self.caps.is_empty() {
+            ProviderHealthStatus::Degraded("No capabilities".into())
+        } else {
+            ProviderHealthStatus::Healthy
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModProvider for LifecycleModProvider {
+    async fn download_mod(&self, _mod_id: String) -> ModDownloadResult {
+        ModDownloadResult::Cancelled
+    }
+    async fn discover(&self, _query: &DiscoveryQuery) -> Result<DiscoveryResult, DiscoveryError> {
+        Err(DiscoveryError::ProviderUnavailable)
+    }
+    async fn get_extended_mod(&self, _mod_id: &str) -> ModExtendedMetadata {
+        unimplemented!()
+    }
+}
+
 #[test]
 fn register_and_freeze() {
     let mut b = ContextBuilder::new();
     b.register_mod_provider(
         "mod:provider",
         DummyModProvider::new("mod:provider"),
-        ProviderSource::Plugin("plug-a".into()),
+        ProviderSource::plugin("plug-a"),
     )
     .unwrap();
     b.register_mod_provider(
@@ -22,13 +142,18 @@ fn register_and_freeze() {
     )
     .unwrap();
 
-    let gp = Arc::new(DummyGameProvider::new("game-x", "mod:provider"));
-    b.register_game_provider(gp, ProviderSource::Plugin("plug-a".into()))
+    let gp = DummyGameProvider::new("game-x", "mod:provider");
+    b.register_game_provider(gp, ProviderSource::plugin("plug-a"))
         .unwrap();
 
     let ctx = b.freeze();
-    assert_eq!(ctx.list_mod_providers().len(), 2);
-    assert_eq!(ctx.list_games().len(), 1);
+    assert_eq!(ctx.list_mod_providers_detailed().len(), 2);
+    assert_eq!(ctx.list_games_detailed().len(), 1);
+    #[allow(deprecated)]
+    {
+        assert_eq!(ctx.list_mod_providers().len(), 2);
+        assert_eq!(ctx.list_games_detailed().len(), 1);
+    }
 }
 
 #[test]
@@ -38,7 +163,7 @@ fn reserved_core_id_error() {
         .register_mod_provider(
             "core:evil",
             DummyModProvider::new("core:evil"),
-            ProviderSource::Plugin("plug".into()),
+            ProviderSource::plugin("plug"),
         )
         .unwrap_err();
     assert!(matches!(err, RegistryError::ReservedCoreId(_)))
@@ -47,25 +172,90 @@ fn reserved_core_id_error() {
 #[test]
 fn missing_dependency_game_registration() {
     let mut b = ContextBuilder::new();
-    let gp = Arc::new(DummyGameProvider::new("game-y", "mod:missing"));
+    let gp = DummyGameProvider::new("game-y", "mod:missing");
     let err = b
-        .register_game_provider(gp, ProviderSource::Plugin("plug".into()))
+        .register_game_provider(gp, ProviderSource::plugin("plug"))
         .unwrap_err();
     assert!(matches!(err, RegistryError::NotFound(_)));
 }
 
-// #[test]
-// fn activation_and_active_provider() {
-//     let mut b = ContextBuilder::new();
-//     b.register_mod_provider("mod:p", DummyModProvider::new("mod:p"), ProviderSource::Plugin("p1".into())).unwrap();
-//     let gp = Arc::new(DummyGameProvider::new("game-z", "mod:p"));
-//     b.register_game_provider(gp, ProviderSource::Plugin("p1".into())).unwrap();
-//     let ctx = b.freeze();
+#[test]
+fn activation_and_active_provider() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("p1"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-z", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("p1"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    ctx.activate_game("game-z").unwrap();
+    assert_eq!(ctx.active_game().unwrap(), "game-z");
+    assert_eq!(ctx.active_game_primary_provider().unwrap(), "mod:p");
+    assert_eq!(ctx.active_game_required_providers(), ["mod:p"]);
+}
+
+#[test]
+fn games_are_looked_up_by_game_id() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-z", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("p1"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let game = ctx.get_game_provider("game-z").unwrap();
+    assert_eq!(game.id(), "dummy.game");
+    assert_eq!(game.game_id(), "game-z");
+    assert_eq!(ctx.get_metadata("Game-Z").unwrap().id, "game-z");
+
+    // The provider id is only the implementation id
+    assert!(matches!(
+        ctx.get_game_provider("dummy.game"),
+        Err(RegistryError::NotFound(_))
+    ));
+    assert!(ctx.activate_game("dummy.game").is_err());
+}
+
+#[test]
+fn game_instances_of_the_same_type_dont_collide() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("p1"),
+    )
+    .unwrap();
+    for id in ["game-a", "game-b"] {
+        let gp = DummyGameProvider::new(id, "mod:p");
+        b.register_game_provider(gp, ProviderSource::plugin("p1"))
+            .unwrap();
+    }
+    let err = b
+        .register_game_provider(
+            DummyGameProvider::new("Game-A", "mod:p"),
+            ProviderSource::plugin("p1"),
+        )
+        .unwrap_err();
+    assert!(matches!(err, RegistryError::GameAlreadyExists(id) if id == "game-a"));
 
-//     ctx.activate_game("game-z").unwrap();
-//     assert_eq!(ctx.active_game().unwrap(), "game-z");
-//     assert_eq!(ctx.active_game_required_provider().unwrap(), "mod:p");
-// }
+    let ctx = b.freeze();
+    let games: Vec<String> = ctx
+        .list_games_detailed()
+        .into_iter()
+        .map(|g| g.id)
+        .collect();
+    assert_eq!(games, ["game-a", "game-b"]);
+}
 
 // Generic tests
 
@@ -75,28 +265,2262 @@ async fn extended_info_error_without_active_game() {
     b.register_mod_provider(
         "mod:p",
         DummyModProvider::new("mod:p"),
-        ProviderSource::Plugin("plug".into()),
+        ProviderSource::plugin("plug"),
     )
     .unwrap();
-    let gp = Arc::new(DummyGameProvider::new("game-a", "mod:p"));
-    b.register_game_provider(gp, ProviderSource::Plugin("plug".into()))
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
         .unwrap();
     let ctx = b.freeze();
 
     let err = ctx.get_extended_info("mod-xyz").await.unwrap_err();
+    assert_eq!(err, RegistryError::NoActiveGame);
+
+    // An explicit provider doesn't need an active game
+    let meta = ctx
+        .get_extended_info_from("mod:p", "installed-mod")
+        .await
+        .unwrap();
+    assert!(meta.installed);
+    assert!(matches!(
+        ctx.get_extended_info_from("mod:missing", "installed-mod")
+            .await,
+        Err(RegistryError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn extended_info_success() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+    ctx.activate_game("game-a").unwrap();
+
+    let meta = ctx.get_extended_info("installed-mod").await.unwrap();
+    assert!(meta.installed);
+}
+
+#[tokio::test]
+async fn discover_merged_uses_active_game_providers() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+    let query = DiscoveryQuery::builder().game_id("game-a").build().unwrap();
+
+    let err = ctx.discover_merged(&query).await.unwrap_err();
+    assert!(matches!(err, DiscoveryError::InvalidQuery(_)));
+
+    ctx.activate_game("game-a").unwrap();
+    let result = ctx.discover_merged(&query).await.unwrap();
+    assert_eq!(result.meta.provider_id, "mod:p");
+    assert_eq!(result.mods.len(), 2);
+}
+
+#[tokio::test]
+async fn launch_active_game_delegates_to_provider() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let err = ctx.launch_active_game(vec![]).await.unwrap_err();
+    assert_eq!(err, RegistryError::NoActiveGame);
+
+    ctx.activate_game("game-a").unwrap();
+    let err = ctx
+        .launch_active_game(vec!["--windowed".into()])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RegistryError::GameProviderError("The game is not installed".into())
+    );
+}
+
+/// Pretends to start the game, remembering the arguments
+#[derive(Default)]
+struct RecordingLauncher {
+    args: Mutex<Option<Vec<String>>>,
+}
+
+#[async_trait::async_trait]
+impl LaunchesGame for RecordingLauncher {
+    async fn launch(&self, args: &[String]) -> Result<u32, GameLaunchError> {
+        let mut launched = self.args.lock().unwrap();
+        if launched.is_some() {
+            return Err(GameLaunchError::AlreadyRunning);
+        }
+        *launched = Some(args.to_vec());
+        Ok(4242)
+    }
+    fn is_running(&self) -> bool {
+        self.args.lock().unwrap().is_some()
+    }
+}
+
+#[tokio::test]
+async fn launch_active_game_prefers_launcher_capability() {
+    let launcher = Arc::new(RecordingLauncher::default());
+    let provider = Arc::new(LifecycleModProvider {
+        caps: CapabilityBuilder::new_from_arc(&launcher)
+            .game_launcher()
+            .finish(),
+    });
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider("mod:lifecycle", provider.clone(), ProviderSource::Core)
+        .unwrap();
+    b.register_game_provider_lenient(
+        DummyGameProvider::new("game-a", "mod:lifecycle"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let ctx = b.freeze();
+    ctx.activate_game("game-a").unwrap();
+
+    let handle = ctx
+        .launch_active_game(vec!["--windowed".into()])
+        .await
+        .unwrap();
+    assert_eq!(handle.process_id, 4242);
+    let cap = provider.find_capability(ids::GAME_LAUNCHER).unwrap();
+    assert!(cap.as_launches_game().unwrap().is_running());
+    assert_eq!(
+        *launcher.args.lock().unwrap(),
+        Some(vec!["--windowed".to_string()])
+    );
+
+    let err = ctx.launch_active_game(vec![]).await.unwrap_err();
+    assert_eq!(
+        err,
+        RegistryError::GameProviderError("The game is already running".into())
+    );
+
+    drop(launcher);
+    let launcher = cap.as_launches_game().unwrap();
+    assert!(!launcher.is_running());
+    assert!(matches!(
+        launcher.launch(&[]).await,
+        Err(GameLaunchError::ProviderDropped)
+    ));
+}
+
+#[test]
+fn launch_handle_without_child_cannot_kill() {
+    let handle = GameLaunchHandle::from_pid(1234);
+    assert_eq!(handle.process_id, 1234);
+    assert_eq!(
+        handle.kill().unwrap_err().kind(),
+        std::io::ErrorKind::Unsupported
+    );
+}
+
+#[test]
+fn metadata_includes_game_version() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let metadata = ctx.get_metadata("game-a").unwrap();
+    assert_eq!(metadata.version.as_deref(), Some("1.0.0"));
+    assert!(metadata.executable_path.is_none());
+    assert!(metadata.install_path.is_none());
+
+    // Metadata from before these fields existed still deserializes
+    let legacy: GameMetadata = serde_json::from_str(
+        r#"{"id":"g","display_name":"G","short_name":"G","icon":{"Path":"/i.png"},"provider_source":"Core"}"#,
+    )
+    .unwrap();
+    assert!(legacy.version.is_none() && legacy.install_path.is_none());
+}
+
+#[test]
+fn capability_lifecycle_hooks_fire_once() {
+    let cap = Arc::new(CountingCap::default());
+    let provider = Arc::new(LifecycleModProvider {
+        caps: vec![cap.clone() as CapabilityRef],
+    });
+
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider("Mod:Lifecycle", provider.clone(), ProviderSource::Core)
+        .unwrap();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    // A failed registration doesn't notify the capabilities
+    b.register_mod_provider("mod:lifecycle", provider, ProviderSource::Core)
+        .unwrap_err();
+    assert_eq!(*cap.events.lock().unwrap(), ["registered:mod:lifecycle"]);
+
+    let _ctx = b.freeze();
+    assert_eq!(
+        *cap.events.lock().unwrap(),
+        ["registered:mod:lifecycle", "ready:2"]
+    );
+}
+
+#[test]
+fn list_installed_mods_uses_active_game() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let err = ctx.list_installed_mods().unwrap_err();
+    assert_eq!(err, RegistryError::NoActiveGame);
+
+    ctx.activate_game("game-a").unwrap();
+    let mods = ctx.list_installed_mods().unwrap();
+    assert_eq!(mods.len(), 1);
+    assert_eq!(mods[0].mod_id, "installed-mod");
+    assert!(mods[0].enabled);
+}
+
+#[test]
+fn toggling_mods_updates_installed_list() {
+    let game = DummyGameProvider::new("game-a", "mod:p");
+    let enabled = |game: &DummyGameProvider| game.list_installed_mods().unwrap()[0].enabled;
+    assert!(enabled(&game));
+
+    game.disable_mod("installed-mod").unwrap();
+    assert!(!enabled(&game));
+    game.enable_mod("installed-mod").unwrap();
+    assert!(enabled(&game));
+
+    let err = game.disable_mod("missing").unwrap_err();
+    assert!(matches!(err, GameInstallError::ModNotFound(id) if id == "missing"));
+}
+
+#[test]
+fn install_mod_skips_already_installed_unless_forced() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+    ctx.activate_game("game-a").unwrap();
+
+    assert!(
+        ctx.install_mod_for_active_game(Path::new("/tmp/mod.zip"), false)
+            .unwrap()
+    );
+    assert!(
+        !ctx.install_mod_for_active_game(Path::new("/tmp/mod.already"), false)
+            .unwrap()
+    );
+
+    assert!(
+        ctx.install_mod_for_active_game(Path::new("/tmp/mod.already"), true)
+            .unwrap()
+    );
+    // Other errors aren't affected by `force`
+    assert!(
+        ctx.install_mod_for_active_game(Path::new("/tmp/mod.incompatible"), true)
+            .is_err()
+    );
+
+    let err = ctx
+        .install_mod_for_active_game(Path::new("/tmp/mod.incompatible"), false)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        RegistryError::GameProviderError(
+            "Mod version 2.0.0 is not compatible with game version 1.0.0".into()
+        )
+        .to_string()
+    );
+}
+
+#[tokio::test]
+async fn verify_active_game_reports_healthy() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let err = ctx.verify_active_game().await.unwrap_err();
+    assert_eq!(err, RegistryError::NoActiveGame);
+
+    ctx.activate_game("game-a").unwrap();
+    let report = ctx.verify_active_game().await.unwrap();
+    assert!(report.is_healthy());
+}
+
+#[test]
+fn verify_report_with_issues_is_unhealthy() {
+    let report = GameVerifyReport {
+        total_files: 10,
+        missing: vec!["bin/game.exe".into()],
+        corrupted: vec![],
+    };
+    assert!(!report.is_healthy());
+
+    let err = GameInstallError::MissingGameFiles(Some(report.clone()));
+    assert!(matches!(err, GameInstallError::MissingGameFiles(Some(r)) if r == report));
+}
+
+#[test]
+fn capability_index_separates_mods_and_games() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let lifecycle = Arc::new(LifecycleModProvider {
+        caps: vec![Arc::new(CountingCap::default()) as CapabilityRef],
+    });
+    b.register_mod_provider("mod:lifecycle", lifecycle, ProviderSource::Core)
+        .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let with_key = ctx.providers_with_capability(ids::REQUIRES_API_KEY);
+    assert_eq!(with_key.len(), 1);
+    assert_eq!(with_key[0].0, "mod:p");
+    assert_eq!(
+        ctx.providers_with_capability("test.counting")[0].0,
+        "mod:lifecycle"
+    );
+    assert!(ctx.providers_with_capability("unknown").is_empty());
+
+    // Game capabilities are indexed separately
+    assert!(
+        ctx.providers_with_capability(ids::SUPPORTS_PROFILES)
+            .is_empty()
+    );
+    let games = ctx.games_with_capability(ids::SUPPORTS_PROFILES);
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].0, "game-a");
+    assert!(ctx.games_with_capability(ids::REQUIRES_API_KEY).is_empty());
+
+    assert!(
+        ctx.provider_has_capability("Mod:P", ids::REQUIRES_API_KEY)
+            .unwrap()
+    );
+    assert!(
+        !ctx.provider_has_capability("mod:lifecycle", ids::REQUIRES_API_KEY)
+            .unwrap()
+    );
+    assert!(matches!(
+        ctx.provider_has_capability("mod:missing", ids::REQUIRES_API_KEY),
+        Err(RegistryError::NotFound(_))
+    ));
+
+    let cap = ctx
+        .capability_of::<ApiKeyCapability<DummyModProvider>>("mod:p")
+        .unwrap();
+    assert!(cap.is_some());
+    assert!(ctx.capability_of::<CountingCap>("mod:p").unwrap().is_none());
+    assert!(
+        ctx.capability_of::<CountingCap>("mod:lifecycle")
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn game_icon_to_url() {
+    let base = "https://cdn.example.com/";
+    assert_eq!(
+        GameIcon::Path("/icon.png".into()).to_url(base).as_deref(),
+        Some("https://cdn.example.com/icon.png")
+    );
+    assert_eq!(
+        GameIcon::Url("https://example.com/a.png".into())
+            .to_url(base)
+            .as_deref(),
+        Some("https://example.com/a.png")
+    );
+    assert_eq!(GameIcon::Bytes(vec![0x89, 0x50]).to_url(base), None);
+
+    let json = serde_json::to_string(&GameIcon::Bytes(vec![1, 2])).unwrap();
+    assert!(matches!(
+        serde_json::from_str::<GameIcon>(&json).unwrap(),
+        GameIcon::Bytes(b) if b == [1, 2]
+    ));
+}
+
+fn future_plugin_builder() -> ContextBuilder {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let future = Arc::new(LifecycleModProvider {
+        caps: vec![
+            Arc::new(FutureApiKeyCap) as CapabilityRef,
+            Arc::new(CountingCap::default()) as CapabilityRef,
+        ],
+    });
+    b.register_mod_provider("mod:lifecycle", future, ProviderSource::plugin("new"))
+        .unwrap();
+    b
+}
+
+#[test]
+fn freeze_reports_unsupported_capability_versions() {
+    let ctx = future_plugin_builder().freeze();
+
+    let report = ctx.compatibility_report();
+    assert!(!report.is_compatible());
+    // Only the future capability mismatches, custom capabilities aren't checked
+    assert_eq!(report.mismatches.len(), 1);
+    let mismatch = &report.mismatches[0];
+    assert_eq!(mismatch.provider_id, "mod:lifecycle");
+    assert_eq!(mismatch.capability_id, ids::REQUIRES_API_KEY);
+    assert_eq!(mismatch.provided_version, 3);
+    assert_eq!(mismatch.supported_range, VersionRange { min: 1, max: 1 });
+    assert_eq!(ctx.check_capability_compatibility(), report);
 
-    assert!(matches!(err, RegistryError::NotFound(_))); // No active game
+    let json = serde_json::to_string(&report).unwrap();
+    assert!(json.contains("\"provided_version\":3"));
 }
 
-// #[tokio::test]
-// async fn extended_info_success() {
-//     let mut b = ContextBuilder::new();
-//     b.register_mod_provider("mod:p", DummyModProvider::new("mod:p"), ProviderSource::Plugin("plug".into())).unwrap();
-//     let gp = Arc::new(DummyGameProvider::new("game-a", "mod:p"));
-//     b.register_game_provider(gp, ProviderSource::Plugin("plug".into())).unwrap();
-//     let ctx = b.freeze();
-//     ctx.activate_game("game-a").unwrap();
+#[test]
+fn supported_capabilities_can_be_widened() {
+    let mut b = future_plugin_builder();
+    b.set_supported_capabilities(
+        SupportedCapabilities::default().with(ids::REQUIRES_API_KEY, 1..=3),
+    );
+    let ctx = b.freeze();
+    assert!(ctx.compatibility_report().is_compatible());
+}
+
+#[test]
+fn load_order_delegates_to_active_game() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    let load_order = gp.load_order();
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    assert!(matches!(
+        ctx.get_load_order(),
+        Err(RegistryError::NoActiveGame)
+    ));
+    ctx.activate_game("game-a").unwrap();
+    assert_eq!(
+        ctx.get_load_order().unwrap(),
+        ["base-patch", "installed-mod"]
+    );
+
+    let order = vec![
+        "base-patch".to_string(),
+        "new-mod".into(),
+        "installed-mod".into(),
+    ];
+    ctx.set_load_order(order.clone()).unwrap();
+    assert_eq!(*load_order.lock().unwrap(), order);
+
+    let err = ctx
+        .set_load_order(vec!["installed-mod".into(), "base-patch".into()])
+        .unwrap_err();
+    assert!(matches!(err, RegistryError::GameProviderError(m) if m.contains("loaded first")));
+    assert_eq!(*load_order.lock().unwrap(), order);
+}
+
+#[test]
+fn list_games_for_provider() {
+    let mut b = ContextBuilder::new();
+    for id in ["mod:p", "mod:unused"] {
+        b.register_mod_provider(id, DummyModProvider::new(id), ProviderSource::Core)
+            .unwrap();
+    }
+    for id in ["game-b", "game-a"] {
+        let gp = DummyGameProvider::new(id, "mod:p");
+        b.register_game_provider(gp, ProviderSource::plugin("plug"))
+            .unwrap();
+    }
+    let ctx = b.freeze();
+
+    assert_eq!(
+        ctx.list_games_for_provider("Mod:P").unwrap(),
+        ["game-a", "game-b"]
+    );
+    assert!(
+        ctx.list_games_for_provider("mod:unused")
+            .unwrap()
+            .is_empty()
+    );
+    assert!(matches!(
+        ctx.list_games_for_provider("mod:missing"),
+        Err(RegistryError::NotFound(_))
+    ));
+}
 
-//     let meta = ctx.get_extended_info("installed-mod").await.unwrap();
-//     assert!(meta.installed);
-// }
+#[tokio::test]
+async fn watch_active_game_observes_changes() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let mut rx = ctx.watch_active_game();
+    assert_eq!(*rx.borrow(), None);
+    let watcher = tokio::spawn(async move {
+        rx.changed().await.unwrap();
+        let activated = rx.borrow_and_update().clone();
+        rx.changed().await.unwrap();
+        (activated, rx.borrow().clone())
+    });
+
+    ctx.activate_game("game-a").unwrap();
+    tokio::task::yield_now().await;
+    ctx.deactivate_game();
+
+    let (activated, deactivated) = watcher.await.unwrap();
+    assert_eq!(activated.as_deref(), Some("game-a"));
+    assert_eq!(deactivated, None);
+    assert!(ctx.active_game().is_none());
+}
+
+#[test]
+fn detailed_listings_include_capabilities_and_metadata() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("game-a", "mod:p");
+    b.register_game_provider(gp, ProviderSource::plugin("plug"))
+        .unwrap();
+    let ctx = b.freeze();
+
+    let providers = ctx.list_mod_providers_detailed();
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0].id, "mod:p");
+    assert_eq!(providers[0].capability_ids, [ids::REQUIRES_API_KEY]);
+
+    let games = ctx.list_games_detailed();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].required_provider_ids, ["mod:p"]);
+    assert_eq!(games[0].metadata.display_name, "Dummy Game");
+    assert_eq!(games[0].metadata.version.as_deref(), Some("1.0.0"));
+
+    let json = serde_json::to_value(games).unwrap();
+    assert_eq!(json[0]["metadata"]["short_name"], "DG");
+}
+
+#[test]
+fn reload_provider_swaps_implementation() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let old = Arc::new(LifecycleModProvider {
+        caps: vec![Arc::new(CountingCap::default()) as CapabilityRef],
+    });
+    b.register_mod_provider("mod:lifecycle", old, ProviderSource::Core)
+        .unwrap();
+    let ctx = b.freeze();
+    let mut rx = ctx.subscribe_events();
+    assert_eq!(ctx.providers_with_capability("test.counting").len(), 1);
+
+    let cap = Arc::new(CountingCap::default());
+    let new = Arc::new(LifecycleModProvider {
+        caps: vec![cap.clone() as CapabilityRef],
+    });
+    ctx.reload_provider("Mod:Lifecycle", new.clone()).unwrap();
+
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ContextEvent::ProviderReloaded("mod:lifecycle".into())
+    );
+    assert_eq!(
+        *cap.events.lock().unwrap(),
+        ["registered:mod:lifecycle", "ready:2"]
+    );
+    let found = ctx
+        .capability_of::<CountingCap>("mod:lifecycle")
+        .unwrap()
+        .unwrap();
+    assert!(Arc::ptr_eq(&found, &cap));
+
+    // The index is rebuilt from the new capabilities
+    ctx.reload_provider(
+        "mod:lifecycle",
+        Arc::new(LifecycleModProvider { caps: vec![] }),
+    )
+    .unwrap();
+    assert!(ctx.providers_with_capability("test.counting").is_empty());
+    assert_eq!(ctx.list_mod_providers_detailed()[0].display_name, None);
+    assert!(
+        ctx.list_mod_providers_detailed()
+            .iter()
+            .find(|p| p.id == "mod:lifecycle")
+            .unwrap()
+            .capability_ids
+            .is_empty()
+    );
+
+    assert!(matches!(
+        ctx.reload_provider("mod:missing", new.clone()),
+        Err(RegistryError::NotFound(_))
+    ));
+    assert!(matches!(
+        ctx.reload_provider("mod:p", new),
+        Err(RegistryError::ProviderMismatch { .. })
+    ));
+}
+
+#[test]
+fn lookups_suggest_similar_ids() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "core:thunderstore",
+        DummyModProvider::new("core:thunderstore"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let gp = DummyGameProvider::new("lethal-company", "core:thunderstore");
+    b.register_game_provider(gp, ProviderSource::Core).unwrap();
+    let ctx = b.freeze();
+
+    let suggested = |err: RegistryError| match err {
+        RegistryError::NotFoundWithSuggestions { suggestions, .. } => suggestions,
+        other => panic!("Expected suggestions, got {:?}", other),
+    };
+    assert_eq!(
+        suggested(ctx.get_mod_provider("core:thunderstor").err().unwrap()),
+        ["core:thunderstore"]
+    );
+    assert_eq!(
+        suggested(ctx.activate_game("lethal-compnay").unwrap_err()),
+        ["lethal-company"]
+    );
+    assert_eq!(
+        suggested(ctx.get_metadata("lethal_company").unwrap_err()),
+        ["lethal-company"]
+    );
+    assert!(matches!(
+        ctx.get_game_provider("minecraft"),
+        Err(RegistryError::NotFound(_))
+    ));
+}
+
+#[test]
+fn merge_plugin_builder_into_base() {
+    let mut base = ContextBuilder::new();
+    base.register_mod_provider(
+        "core:base",
+        DummyModProvider::new("core:base"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    base.register_game_provider(
+        DummyGameProvider::new("game-a", "core:base"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+
+    let plugin = || {
+        let mut b = ContextBuilder::new();
+        b.register_mod_provider(
+            "plugin:mods",
+            DummyModProvider::new("plugin:mods"),
+            ProviderSource::plugin("plug"),
+        )
+        .unwrap();
+        b.register_game_provider(
+            DummyGameProvider::new("game-b", "plugin:mods"),
+            ProviderSource::plugin("plug"),
+        )
+        .unwrap();
+        b
+    };
+
+    assert_eq!(plugin().mod_provider_count(), 1);
+    assert_eq!(plugin().game_count(), 1);
+    assert!(ContextBuilder::new().is_empty());
+
+    base.merge(plugin()).unwrap();
+    assert_eq!(base.len(), 4);
+    let ctx = base.freeze();
+    assert_eq!(ctx.list_mod_providers_detailed().len(), 2);
+    assert_eq!(ctx.list_games_detailed().len(), 2);
+    assert_eq!(
+        ctx.list_games_for_provider("plugin:mods").unwrap(),
+        ["game-b"]
+    );
+
+    let mut first = plugin();
+    let errors = first.merge(plugin()).unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            RegistryError::ProviderAlreadyExists("plugin:mods".into()),
+            RegistryError::GameAlreadyExists("game-b".into()),
+        ]
+    );
+}
+
+#[test]
+fn merge_rolls_back_on_partial_conflict() {
+    let mut base = ContextBuilder::new();
+    base.register_mod_provider(
+        "mod:a",
+        DummyModProvider::new("mod:a"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    base.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:a"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+
+    // "mod:b" and "game-ok" would be fine on their own
+    let mut plugin = ContextBuilder::new();
+    for id in ["mod:a", "mod:b"] {
+        plugin
+            .register_mod_provider(id, DummyModProvider::new(id), ProviderSource::Core)
+            .unwrap();
+    }
+    for id in ["game-a", "game-ok"] {
+        plugin
+            .register_game_provider(DummyGameProvider::new(id, "mod:b"), ProviderSource::Core)
+            .unwrap();
+    }
+
+    let errors = base.merge(plugin).unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            RegistryError::ProviderAlreadyExists("mod:a".into()),
+            RegistryError::GameAlreadyExists("game-a".into()),
+        ]
+    );
+    assert_eq!(base.mod_provider_count(), 1);
+    assert_eq!(base.game_count(), 1);
+
+    let ctx = base.freeze();
+    assert!(ctx.get_mod_provider("mod:b").is_err());
+    assert_eq!(ctx.list_games_for_provider("mod:a").unwrap(), ["game-a"]);
+}
+
+#[tokio::test]
+async fn games_can_use_multiple_providers() {
+    let mut b = ContextBuilder::new();
+    for id in ["mod:store", "mod:workshop"] {
+        b.register_mod_provider(id, DummyModProvider::new(id), ProviderSource::Core)
+            .unwrap();
+    }
+    let err = b
+        .register_game_provider(
+            DummyGameProvider::with_providers("game-x", &["mod:store", "mod:missing"]),
+            ProviderSource::Core,
+        )
+        .unwrap_err();
+    assert_eq!(err, RegistryError::NotFound("mod:missing".into()));
+    let gp = DummyGameProvider::with_providers("game-a", &["Mod:Store", "mod:workshop"]);
+    b.register_game_provider(gp, ProviderSource::Core).unwrap();
+    let ctx = b.freeze();
+
+    assert!(ctx.active_game_required_providers().is_empty());
+    ctx.activate_game("game-a").unwrap();
+    assert_eq!(
+        ctx.active_game_required_providers(),
+        ["mod:store", "mod:workshop"]
+    );
+    assert_eq!(ctx.active_game_primary_provider().unwrap(), "mod:store");
+    assert_eq!(
+        ctx.list_games_for_provider("mod:workshop").unwrap(),
+        ["game-a"]
+    );
+
+    let meta = ctx
+        .get_extended_info_from("mod:workshop", "installed-mod")
+        .await
+        .unwrap();
+    assert!(meta.installed);
+
+    // Discovery asks every provider and merges the results
+    let query = DiscoveryQuery::builder().game_id("game-a").build().unwrap();
+    let result = ctx.discover_merged(&query).await.unwrap();
+    assert_eq!(result.meta.provider_id, "mod:store,mod:workshop");
+}
+
+#[tokio::test]
+async fn health_check_all_providers() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "Mod:P",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let lifecycle = Arc::new(LifecycleModProvider { caps: vec![] });
+    b.register_mod_provider("mod:lifecycle", lifecycle, ProviderSource::Core)
+        .unwrap();
+    let ctx = b.freeze();
+
+    let statuses = ctx.health_check_all_providers().await;
+    assert_eq!(statuses.len(), 2);
+    assert_eq!(statuses["mod:p"], ProviderHealthStatus::Healthy);
+    assert_eq!(
+        statuses["mod:lifecycle"],
+        ProviderHealthStatus::Degraded("No capabilities".into())
+    );
+}
+
+#[test]
+fn activate_game_returns_previous_game() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    for id in ["game-a", "game-b"] {
+        b.register_game_provider(DummyGameProvider::new(id, "mod:p"), ProviderSource::Core)
+            .unwrap();
+    }
+    let ctx = b.freeze();
+
+    assert_eq!(ctx.activate_game("game-a").unwrap(), None);
+    assert_eq!(
+        ctx.activate_game("game-b").unwrap().as_deref(),
+        Some("game-a")
+    );
+    assert_eq!(ctx.deactivate_game().as_deref(), Some("game-b"));
+    assert_eq!(ctx.deactivate_game(), None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn activate_game_concurrently() {
+    let games: Vec<String> = (0..8).map(|i| format!("game-{i}")).collect();
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    for id in &games {
+        b.register_game_provider(DummyGameProvider::new(id, "mod:p"), ProviderSource::Core)
+            .unwrap();
+    }
+    let ctx = Arc::new(b.freeze());
+
+    let tasks = games.iter().cloned().map(|id| {
+        let ctx = Arc::clone(&ctx);
+        tokio::spawn(async move {
+            let mut previous = Vec::new();
+            for _ in 0..50 {
+                previous.push(ctx.activate_game(&id).unwrap());
+                tokio::task::yield_now().await;
+            }
+            previous
+        })
+    });
+    let previous: Vec<Option<String>> = futures::future::join_all(tasks)
+        .await
+        .into_iter()
+        .flat_map(Result::unwrap)
+        .collect();
+
+    // Exactly one activation found no game active, every other one replaced a known game
+    assert_eq!(previous.iter().filter(|p| p.is_none()).count(), 1);
+    assert!(previous.iter().flatten().all(|p| games.contains(p)));
+    assert!(games.contains(&ctx.active_game().unwrap()));
+}
+
+#[test]
+fn snapshot_lists_providers_games_and_active_game() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    let ctx = b.freeze();
+    ctx.activate_game("game-a").unwrap();
+
+    let snapshot = ctx.snapshot();
+    assert_eq!(snapshot.mod_providers.len(), 1);
+    assert_eq!(snapshot.mod_providers[0].id, "mod:p");
+    assert_eq!(
+        snapshot.mod_providers[0].capability_ids,
+        vec![ids::REQUIRES_API_KEY.to_string()]
+    );
+    assert_eq!(
+        snapshot.mod_providers[0].display_name.as_deref(),
+        Some("Dummy Mod Provider")
+    );
+    assert_eq!(snapshot.mod_providers[0].version.as_deref(), Some("1.0.0"));
+    assert!(snapshot.mod_providers[0].registered_at > 0);
+    assert_eq!(snapshot.games[0].id, "game-a");
+    assert_eq!(snapshot.games[0].required_provider_id, "mod:p");
+    assert_eq!(snapshot.active_game.as_deref(), Some("game-a"));
+
+    let json = ctx.export_to_json().unwrap();
+    let parsed: ContextSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.games[0].id, "game-a");
+
+    // Nothing but ids ends up in the export
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let mut keys: Vec<&String> = value["mod_providers"][0]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        [
+            "capability_ids",
+            "display_name",
+            "id",
+            "registered_at",
+            "source",
+            "version"
+        ]
+    );
+}
+
+#[test]
+fn entry_views_include_source_and_dependencies() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_mod_provider_lazy(
+        "mod:lazy",
+        ProviderSource::plugin("plug"),
+        Box::new(|| Ok(DummyModProvider::new("mod:lazy"))),
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    b.alias_game("old-game", "game-a").unwrap();
+    let ctx = b.freeze();
+
+    let game = ctx.get_game_entry("Old-Game").unwrap();
+    assert_eq!(game.id, "game-a");
+    assert_eq!(game.source, ProviderSource::plugin("plug"));
+    assert_eq!(game.required_provider_id, "mod:p");
+    assert_eq!(game.required_provider_ids, ["mod:p"]);
+    assert_eq!(game.metadata.id, "game-a");
+    assert!(Arc::ptr_eq(
+        game.game(),
+        &ctx.get_game_provider("game-a").unwrap()
+    ));
+    let json = serde_json::to_value(&game).unwrap();
+    assert_eq!(json["required_provider_id"], "mod:p");
+    assert!(matches!(
+        ctx.get_game_entry("unknown"),
+        Err(RegistryError::NotFound(_))
+    ));
+
+    let provider = ctx.get_provider_entry("mod:p").unwrap();
+    assert_eq!(provider.source, ProviderSource::Core);
+    assert_eq!(provider.capability_ids, [ids::REQUIRES_API_KEY]);
+    assert_eq!(provider.provider().id(), "mod:p");
+
+    // Lazy providers are constructed, like with `get_mod_provider`
+    let lazy = ctx.get_provider_entry("mod:lazy").unwrap();
+    assert_eq!(lazy.source, ProviderSource::plugin("plug"));
+    assert!(ctx.dump_string().contains("mod:lazy"));
+}
+
+#[test]
+fn dump_string_lists_entries() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let ctx = b.freeze();
+
+    let dump = ctx.dump_string();
+    assert!(
+        dump.contains("\tmod:p \"Dummy Mod Provider\" (Core)"),
+        "{dump}"
+    );
+    assert!(
+        dump.contains("\tgame-a (Core) -> Depends on mod:p"),
+        "{dump}"
+    );
+    assert!(!dump.contains("Active game"), "{dump}");
+
+    ctx.activate_game("game-a").unwrap();
+    assert!(ctx.dump_string().contains("Active game: game-a"));
+}
+
+#[test]
+fn registry_events_record_failures() {
+    let mut b = ContextBuilder::new();
+    b.reserve_namespace("vmm", ProviderSourceMatcher::Core);
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap_err();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:missing"),
+        ProviderSource::Core,
+    )
+    .unwrap_err();
+    b.alias_game("old-game", "game-a").unwrap_err();
+
+    let events = b.events();
+    let actions: Vec<RegistryAction> = events.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        [
+            RegistryAction::ReserveNamespace,
+            RegistryAction::RegisterModProvider,
+            RegistryAction::RegisterModProvider,
+            RegistryAction::RegisterGame,
+            RegistryAction::AliasGame,
+        ]
+    );
+    assert_eq!(events[1].outcome, RegistryOutcome::Ok);
+    assert_eq!(
+        events[2].outcome,
+        RegistryOutcome::Failed("Duplicate provider id: mod:p".to_string())
+    );
+    assert_eq!(events[2].source, Some(ProviderSource::plugin("plug")));
+    assert_eq!(events[3].id, "game-a");
+    assert_eq!(
+        events[3].outcome,
+        RegistryOutcome::Failed("Cannot find id mod:missing".to_string())
+    );
+    assert_eq!(events[4].id, "old-game -> game-a");
+    assert!(matches!(events[4].outcome, RegistryOutcome::Failed(_)));
+
+    // Carried into the context and serializable for bug reports
+    let ctx = b.freeze();
+    let events = ctx.registration_events();
+    assert_eq!(events.len(), 5);
+    let json = serde_json::to_string(events).unwrap();
+    let parsed: Vec<RegistryEvent> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, events);
+}
+
+#[test]
+fn registry_events_are_capped() {
+    let mut b = ContextBuilder::new();
+    for i in 0..MAX_REGISTRY_EVENTS + 10 {
+        b.reserve_namespace(&format!("ns{i}"), ProviderSourceMatcher::Core);
+    }
+    assert_eq!(b.events().len(), MAX_REGISTRY_EVENTS);
+    assert_eq!(b.events()[0].id, "ns10");
+}
+
+#[test]
+fn context_publishes_events() {
+    let (events, mut builder_rx) = tokio::sync::broadcast::channel(8);
+    let mut b = ContextBuilder::new();
+    b.set_event_sender(events);
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    assert_eq!(
+        builder_rx.try_recv().unwrap(),
+        ContextEvent::ProviderRegistered("mod:p".into())
+    );
+    let ctx = b.freeze();
+
+    let mut rx = ctx.subscribe_events();
+    ctx.activate_game("game-a").unwrap();
+    ctx.deactivate_game();
+    ctx.deactivate_game();
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ContextEvent::GameActivated("game-a".into())
+    );
+    assert_eq!(rx.try_recv().unwrap(), ContextEvent::GameDeactivated);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn lagging_subscriber_can_resync_from_snapshot() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let ctx = b.freeze();
+    let mut rx = ctx.subscribe_events();
+
+    for _ in 0..100 {
+        ctx.activate_game("game-a").unwrap();
+    }
+    assert!(matches!(
+        rx.try_recv(),
+        Err(tokio::sync::broadcast::error::TryRecvError::Lagged(_))
+    ));
+    assert_eq!(ctx.snapshot().active_game.as_deref(), Some("game-a"));
+}
+
+#[test]
+fn freeze_validated_reports_every_warning_kind() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "core:base",
+        DummyModProvider::new("core:base"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_mod_provider(
+        "mod:unused",
+        DummyModProvider::new("mod:unused"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let bare = Arc::new(LifecycleModProvider { caps: vec![] });
+    b.register_mod_provider("mod:lifecycle", bare, ProviderSource::plugin("plug"))
+        .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "core:base"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    // The dummy game requires an API key, which the bare provider lacks
+    assert_eq!(
+        b.register_game_provider(
+            DummyGameProvider::new("game-b", "mod:lifecycle"),
+            ProviderSource::plugin("plug"),
+        ),
+        Err(RegistryError::MissingRequiredCapability {
+            game: "game-b".into(),
+            provider: "mod:lifecycle".into(),
+            capability: ids::REQUIRES_API_KEY.into(),
+        })
+    );
+    b.register_game_provider_lenient(
+        DummyGameProvider::new("game-b", "mod:lifecycle"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+
+    let (ctx, report) = b.freeze_validated();
+    assert_eq!(ctx.list_games_detailed().len(), 2);
+    assert!(!report.is_clean());
+    assert_eq!(
+        report.warnings,
+        [
+            ValidationWarning::NamespaceMismatch {
+                id: "mod:unused".into(),
+                source: ProviderSource::Core,
+            },
+            ValidationWarning::OrphanProvider("mod:unused".into()),
+            ValidationWarning::MissingCapability {
+                game: "game-b".into(),
+                provider: "mod:lifecycle".into(),
+                capability: ids::REQUIRES_API_KEY.into(),
+            },
+        ]
+    );
+
+    let json = serde_json::to_string(&report).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ValidationReport>(&json).unwrap(),
+        report
+    );
+}
+
+#[test]
+fn validate_collects_configuration_errors() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    assert_eq!(b.validate(), Ok(()));
+
+    b.register_mod_provider(
+        "mod:renamed",
+        DummyModProvider::new("mod:original"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    assert_eq!(
+        b.validate().unwrap_err(),
+        [RegistryError::IdMismatch {
+            registered: "mod:renamed".into(),
+            provider_id: "mod:original".into(),
+        }]
+    );
+    assert_eq!(
+        b.validate().unwrap_err()[0].to_string(),
+        "Provider registered as mod:renamed reports id mod:original"
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Invalid context configuration")]
+fn freeze_panics_on_invalid_configuration_in_debug_builds() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:a",
+        DummyModProvider::new("mod:b"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.freeze();
+}
+
+#[test]
+fn find_game_by_external_id() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    for id in ["Game-B", "game-a"] {
+        b.register_game_provider(DummyGameProvider::new(id, "mod:p"), ProviderSource::Core)
+            .unwrap();
+    }
+    let ctx = b.freeze();
+
+    assert_eq!(
+        ctx.find_game_by_external_id("external-123").as_deref(),
+        Some("game-a")
+    );
+    assert_eq!(ctx.find_game_by_external_id("external-999"), None);
+}
+
+fn prioritized_builder() -> ContextBuilder {
+    let mut b = ContextBuilder::new();
+    for (id, priority) in [
+        ("mod:c", 0),
+        ("mod:low", -5),
+        ("mod:a", 0),
+        ("mod:high", 10),
+        ("mod:b", 0),
+    ] {
+        b.register_mod_provider_with(
+            id,
+            DummyModProvider::new(id),
+            ProviderSource::Core,
+            ProviderOptions { priority },
+        )
+        .unwrap();
+    }
+    b.register_game_provider(
+        DummyGameProvider::with_providers("game-a", &["mod:b", "mod:a", "mod:high"]),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::with_providers("game-b", &["mod:c", "mod:a"]),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b
+}
+
+#[test]
+fn providers_are_ordered_by_priority_then_id() {
+    let expected = ["mod:high", "mod:a", "mod:b", "mod:c", "mod:low"];
+    // Registration happens into a HashMap, so rebuild a few times to catch unstable ordering
+    for _ in 0..10 {
+        let ctx = prioritized_builder().freeze();
+        let listed: Vec<String> = ctx
+            .list_mod_providers_detailed()
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(listed, expected);
+
+        let with_key: Vec<String> = ctx
+            .providers_with_capability(ids::REQUIRES_API_KEY)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(with_key, expected);
+    }
+}
+
+#[test]
+fn primary_provider_for_game_honors_priority() {
+    let mut b = prioritized_builder();
+    b.alias_game("old-a", "game-a").unwrap();
+    let ctx = b.freeze();
+
+    assert_eq!(ctx.primary_provider_for_game("game-a").unwrap(), "mod:high");
+    assert_eq!(ctx.primary_provider_for_game("Old-A").unwrap(), "mod:high");
+    // Ties keep the order the game declared
+    assert_eq!(ctx.primary_provider_for_game("Game-B").unwrap(), "mod:c");
+    assert!(ctx.primary_provider_for_game("game-z").is_err());
+
+    ctx.activate_game("game-a").unwrap();
+    assert_eq!(
+        ctx.active_game_required_providers(),
+        ["mod:high", "mod:b", "mod:a"]
+    );
+    assert_eq!(
+        ctx.active_game_primary_provider().as_deref(),
+        Some("mod:high")
+    );
+}
+
+#[tokio::test]
+async fn discover_mods_uses_primary_provider_of_active_game() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let ctx = b.freeze();
+    let query = DiscoveryQuery::builder().game_id("game-a").build().unwrap();
+
+    assert!(matches!(
+        ctx.get_required_provider_for_active_game(),
+        Err(RegistryError::NoActiveGame)
+    ));
+    assert!(matches!(
+        ctx.discover_mods(query.clone()).await,
+        Err(DiscoveryError::InvalidQuery(_))
+    ));
+
+    ctx.activate_game("game-a").unwrap();
+    let provider = ctx.get_required_provider_for_active_game().unwrap();
+    assert_eq!(provider.id(), "mod:p");
+    let result = ctx.discover_mods(query).await.unwrap();
+    assert_eq!(result.meta.provider_id, "mod:p");
+
+    // The dummy provider can't search, so the context filters for it
+    let search = DiscoveryQuery::builder()
+        .game_id("game-a")
+        .search(" EARLY ")
+        .build()
+        .unwrap();
+    let result = ctx.discover_mods(search).await.unwrap();
+    let ids: Vec<&str> = result.mods.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["mod-2"]);
+}
+
+#[test]
+fn lazy_provider_is_built_on_first_access() {
+    let built = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&built);
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider_lazy(
+        "mod:lazy",
+        ProviderSource::Core,
+        Box::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(DummyModProvider::new("mod:lazy"))
+        }),
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:lazy"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    let ctx = b.freeze();
+
+    assert_eq!(built.load(Ordering::SeqCst), 0);
+    // Capabilities are indexed on realization
+    assert!(
+        ctx.providers_with_capability(ids::REQUIRES_API_KEY)
+            .is_empty()
+    );
+    assert_eq!(ctx.list_games_for_provider("mod:lazy").unwrap(), ["game-a"]);
+
+    let provider = ctx.get_mod_provider("Mod:Lazy").unwrap();
+    assert_eq!(provider.id(), "mod:lazy");
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        ctx.providers_with_capability(ids::REQUIRES_API_KEY).len(),
+        1
+    );
+    assert!(Arc::ptr_eq(
+        &provider,
+        &ctx.get_mod_provider("mod:lazy").unwrap()
+    ));
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn lazy_provider_init_errors_are_reported() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider_lazy(
+        "mod:broken",
+        ProviderSource::Core,
+        Box::new(|| Err("keychain locked".into())),
+    )
+    .unwrap();
+    assert_eq!(
+        b.register_mod_provider_lazy(
+            "mod:broken",
+            ProviderSource::Core,
+            Box::new(|| unreachable!())
+        ),
+        Err(RegistryError::ProviderAlreadyExists("mod:broken".into()))
+    );
+    let ctx = b.freeze();
+
+    for _ in 0..2 {
+        assert_eq!(
+            ctx.get_mod_provider("mod:broken").err(),
+            Some(RegistryError::ProviderInitFailed {
+                id: "mod:broken".into(),
+                message: "keychain locked".into(),
+            })
+        );
+    }
+}
+
+#[test]
+fn racing_lazy_realizations_share_one_provider() {
+    let built = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&built);
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider_lazy(
+        "mod:slow",
+        ProviderSource::Core,
+        Box::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            Ok(DummyModProvider::new("mod:slow"))
+        }),
+    )
+    .unwrap();
+    let ctx = b.freeze();
+
+    let barrier = Barrier::new(2);
+    let (a, b) = std::thread::scope(|s| {
+        let race = || {
+            barrier.wait();
+            ctx.get_mod_provider("mod:slow").unwrap()
+        };
+        let a = s.spawn(race);
+        let b = s.spawn(race);
+        (a.join().unwrap(), b.join().unwrap())
+    });
+
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn register_mod_provider_validates_in_debug_builds() {
+    let provider = Arc::new(LifecycleModProvider {
+        caps: vec![
+            Arc::new(CountingCap::default()) as CapabilityRef,
+            Arc::new(CountingCap::default()) as CapabilityRef,
+        ],
+    });
+    let mut b = ContextBuilder::new();
+    assert_eq!(
+        b.register_mod_provider("mod:lifecycle", provider, ProviderSource::Core),
+        Err(RegistryError::ValidationFailed(
+            "mod:lifecycle: Duplicate capability (test.counting)".into()
+        ))
+    );
+    assert!(b.is_empty());
+}
+
+#[test]
+fn reserved_namespaces_reject_other_sources() {
+    let official = ProviderSource::plugin("official");
+    let bundled = ProviderSource::Plugin {
+        id: "bundled".into(),
+        version: None,
+        origin: PluginOrigin::Bundled,
+    };
+    let mut b = ContextBuilder::new();
+    b.reserve_namespace("vmm:", ProviderSourceMatcher::Core);
+    // Overlapping reservations allow every matching source
+    b.reserve_namespace("VMM", ProviderSourceMatcher::Plugin("official".into()));
+    b.reserve_namespace("vmm", ProviderSourceMatcher::BundledPlugins);
+
+    for (id, source) in [
+        ("vmm:core-made", ProviderSource::Core),
+        ("vmm:official", official.clone()),
+        ("vmm:bundled", bundled),
+        ("vmm.extra:anyone", ProviderSource::plugin("anyone")),
+    ] {
+        b.register_mod_provider(id, DummyModProvider::new(id), source)
+            .unwrap();
+    }
+    assert_eq!(
+        b.register_mod_provider(
+            "vmm:impostor",
+            DummyModProvider::new("vmm:impostor"),
+            ProviderSource::plugin("impostor")
+        ),
+        Err(RegistryError::ReservedNamespace {
+            namespace: "vmm".into(),
+            id: "vmm:impostor".into(),
+        })
+    );
+    assert_eq!(
+        b.register_game_provider(
+            DummyGameProvider::new("vmm:game", "vmm:official"),
+            ProviderSource::plugin("impostor"),
+        ),
+        Err(RegistryError::ReservedNamespace {
+            namespace: "vmm".into(),
+            id: "vmm:game".into(),
+        })
+    );
+    b.register_game_provider(DummyGameProvider::new("vmm:game", "vmm:official"), official)
+        .unwrap();
+}
+
+#[test]
+fn core_namespace_stays_reserved() {
+    let mut b = ContextBuilder::new();
+    b.reserve_namespace("core", ProviderSourceMatcher::Plugin("sneaky".into()));
+    assert_eq!(
+        b.register_mod_provider(
+            "core:sneaky",
+            DummyModProvider::new("core:sneaky"),
+            ProviderSource::plugin("sneaky")
+        ),
+        Err(RegistryError::ReservedCoreId("core:sneaky".into()))
+    );
+    b.register_mod_provider(
+        "core:base",
+        DummyModProvider::new("core:base"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+}
+
+#[test]
+fn merge_checks_reserved_namespaces() {
+    let mut base = ContextBuilder::new();
+    base.reserve_namespace("vmm", ProviderSourceMatcher::Core);
+
+    let mut plugin = ContextBuilder::new();
+    plugin
+        .register_mod_provider(
+            "vmm:fake",
+            DummyModProvider::new("vmm:fake"),
+            ProviderSource::plugin("plug"),
+        )
+        .unwrap();
+    assert_eq!(
+        base.merge(plugin).unwrap_err(),
+        [RegistryError::ReservedNamespace {
+            namespace: "vmm".into(),
+            id: "vmm:fake".into(),
+        }]
+    );
+}
+
+fn context_with_game(game_id: &str) -> Context {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new(game_id, "mod:p"),
+        ProviderSource::plugin("plug"),
+    )
+    .unwrap();
+    b.freeze()
+}
+
+#[test]
+fn runtime_state_round_trip() {
+    let ctx = context_with_game("game-a");
+    ctx.activate_game("game-a").unwrap();
+
+    let json = serde_json::to_string(&ctx.export_state()).unwrap();
+    let state: RuntimeState = serde_json::from_str(&json).unwrap();
+
+    let next = context_with_game("game-a");
+    let report = next.restore_state(state).unwrap();
+    assert!(report.is_complete());
+    assert_eq!(report.active_game.as_deref(), Some("game-a"));
+    assert_eq!(next.active_game().as_deref(), Some("game-a"));
+
+    // Fields added later must not break older saves
+    let empty: RuntimeState = serde_json::from_str("{}").unwrap();
+    assert_eq!(empty, RuntimeState::default());
+    assert_eq!(next.restore_state(empty).unwrap(), RestoreReport::default());
+    assert_eq!(next.active_game(), None);
+}
+
+#[test]
+fn restore_state_reports_missing_game() {
+    let ctx = context_with_game("game-b");
+    ctx.activate_game("game-b").unwrap();
+    let state = RuntimeState {
+        active_game: Some("game-a".into()),
+    };
+
+    let report = ctx.restore_state(state).unwrap();
+    assert!(!report.is_complete());
+    assert_eq!(report.missing_game.as_deref(), Some("game-a"));
+    assert_eq!(report.active_game, None);
+    assert_eq!(ctx.active_game(), None);
+
+    let malformed = RuntimeState {
+        active_game: Some("".into()),
+    };
+    assert!(ctx.restore_state(malformed).is_err());
+}
+
+/// Counts how often its metadata is built and it's shut down
+struct CountingGameProvider {
+    id: String,
+    display_name: String,
+    metadata_calls: AtomicUsize,
+    shutdown_calls: AtomicUsize,
+}
+
+impl CountingGameProvider {
+    fn new(id: &str, display_name: &str) -> Arc<Self> {
+        Arc::new(Self {
+            id: id.into(),
+            display_name: display_name.into(),
+            metadata_calls: AtomicUsize::new(0),
+            shutdown_calls: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl Provider for CountingGameProvider {
+    fn id(&self) -> &'static str {
+        "counting.game"
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &[]
+    }
+    fn on_shutdown(&self) {
+        self.shutdown_calls.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl GameProvider for CountingGameProvider {
+    fn game_id(&self) -> &str {
+        &self.id
+    }
+    fn mod_provider_id(&self) -> &str {
+        "mod:p"
+    }
+    fn metadata(&self) -> GameMetadata {
+        self.metadata_calls.fetch_add(1, Ordering::SeqCst);
+        GameMetadata {
+            id: self.id.clone(),
+            display_name: self.display_name.clone(),
+            short_name: self.display_name.clone(),
+            icon: GameIcon::Path("/icon.png".into()),
+            provider_source: ProviderSource::Core,
+            version: None,
+            executable_path: None,
+            install_path: None,
+        }
+    }
+    fn get_external_id(&self) -> &str {
+        &self.id
+    }
+    fn install_mod(&self, _path: &Path) -> Result<(), GameInstallError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn game_metadata_is_built_once() {
+    let games = [
+        CountingGameProvider::new("game-c", "Alpha"),
+        CountingGameProvider::new("game-b", "Beta"),
+        CountingGameProvider::new("game-a", "Alpha"),
+    ];
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    for game in &games {
+        b.register_game_provider(game.clone(), ProviderSource::Core)
+            .unwrap();
+    }
+    let ctx = b.freeze();
+
+    let all: Vec<String> = ctx.all_game_metadata().into_iter().map(|m| m.id).collect();
+    assert_eq!(all, vec!["game-a", "game-c", "game-b"]);
+
+    let batch = ctx.metadata_for(&["game-b", "missing", "game-a"]);
+    assert_eq!(batch[0].as_ref().unwrap().display_name, "Beta");
+    assert!(matches!(batch[1], Err(RegistryError::NotFound(_))));
+    assert_eq!(batch[2].as_ref().unwrap().id, "game-a");
+
+    for _ in 0..3 {
+        ctx.get_metadata("game-a").unwrap();
+    }
+    ctx.list_games_detailed();
+    for game in &games {
+        assert_eq!(game.metadata_calls.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[test]
+fn aliases_resolve_through_normalization() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "ts:thunderstore",
+        DummyModProvider::new("ts:thunderstore"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(
+        DummyGameProvider::new("ts:game", "ts:thunderstore"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.alias_provider(" Thunderstore ", "TS:Thunderstore")
+        .unwrap();
+    b.alias_game("Old-Game", "ts:game").unwrap();
+    let ctx = b.freeze();
+
+    let provider = ctx.get_mod_provider("THUNDERSTORE").unwrap();
+    assert_eq!(provider.id(), "ts:thunderstore");
+    assert_eq!(
+        ctx.get_game_provider("old-game").unwrap().game_id(),
+        "ts:game"
+    );
+    assert_eq!(ctx.get_metadata(" OLD-GAME ").unwrap().id, "ts:game");
+    ctx.activate_game("old-game").unwrap();
+    assert_eq!(ctx.active_game().as_deref(), Some("ts:game"));
+
+    let report = ctx
+        .restore_state(RuntimeState {
+            active_game: Some("old-game".into()),
+        })
+        .unwrap();
+    assert_eq!(report.active_game.as_deref(), Some("ts:game"));
+
+    assert_eq!(ctx.list_mod_providers_detailed().len(), 1);
+    assert_eq!(ctx.list_games_detailed().len(), 1);
+    assert_eq!(
+        ctx.list_aliases(),
+        vec![("thunderstore".to_string(), "ts:thunderstore".to_string())]
+    );
+    assert_eq!(
+        ctx.list_game_aliases(),
+        vec![("old-game".to_string(), "ts:game".to_string())]
+    );
+}
+
+#[test]
+fn aliases_cannot_collide_or_chain() {
+    let mut b = ContextBuilder::new();
+    for id in ["mod:a", "mod:b"] {
+        b.register_mod_provider(id, DummyModProvider::new(id), ProviderSource::Core)
+            .unwrap();
+    }
+    b.alias_provider("mod:old", "mod:a").unwrap();
+
+    assert_eq!(
+        b.alias_provider("mod:older", "mod:old"),
+        Err(RegistryError::AliasChain {
+            alias: "mod:older".into(),
+            target: "mod:old".into(),
+        })
+    );
+    assert_eq!(
+        b.alias_provider("mod:b", "mod:a"),
+        Err(RegistryError::ProviderAlreadyExists("mod:b".into()))
+    );
+    assert_eq!(
+        b.alias_provider("MOD:OLD", "mod:b"),
+        Err(RegistryError::ProviderAlreadyExists("mod:old".into()))
+    );
+    assert_eq!(
+        b.alias_provider("mod:x", "mod:missing"),
+        Err(RegistryError::NotFound("mod:missing".into()))
+    );
+    assert_eq!(
+        b.register_mod_provider(
+            "mod:old",
+            DummyModProvider::new("mod:old"),
+            ProviderSource::Core
+        ),
+        Err(RegistryError::ProviderAlreadyExists("mod:old".into()))
+    );
+    assert_eq!(
+        b.alias_game("game-old", "game-a"),
+        Err(RegistryError::NotFound("game-a".into()))
+    );
+}
+
+#[test]
+fn update_adds_and_removes_providers_and_games() {
+    let ctx = context_with_game("game-a");
+    let mut rx = ctx.subscribe_events();
+
+    let mut update = ctx.begin_update();
+    update
+        .add_mod_provider(
+            "mod:q",
+            DummyModProvider::new("mod:q"),
+            ProviderSource::plugin("plug-q"),
+        )
+        .add_game_provider(
+            DummyGameProvider::new("game-b", "mod:q"),
+            ProviderSource::plugin("plug-q"),
+        );
+    update.commit().unwrap();
+
+    assert!(ctx.get_mod_provider("mod:q").is_ok());
+    assert_eq!(ctx.list_games_for_provider("mod:q").unwrap(), ["game-b"]);
+    let with_key: Vec<String> = ctx
+        .providers_with_capability(ids::REQUIRES_API_KEY)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(with_key, ["mod:p", "mod:q"]);
+    assert_eq!(ctx.games_with_capability(ids::SUPPORTS_PROFILES).len(), 2);
+    assert_eq!(ctx.get_metadata("game-b").unwrap().id, "game-b");
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ContextEvent::ProviderRegistered("mod:q".into())
+    );
+
+    let mut update = ctx.begin_update();
+    update.remove_game("game-b").remove_mod_provider("Mod:Q");
+    update.commit().unwrap();
+
+    assert!(ctx.get_mod_provider("mod:q").is_err());
+    assert!(ctx.get_game_provider("game-b").is_err());
+    assert_eq!(
+        ctx.providers_with_capability(ids::REQUIRES_API_KEY).len(),
+        1
+    );
+    assert_eq!(ctx.list_games_detailed().len(), 1);
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ContextEvent::ProviderRemoved("mod:q".into())
+    );
+}
+
+#[test]
+fn failed_update_leaves_context_unchanged() {
+    let ctx = context_with_game("game-a");
+    let mut rx = ctx.subscribe_events();
+
+    let mut update = ctx.begin_update();
+    update
+        .add_mod_provider(
+            "mod:q",
+            DummyModProvider::new("mod:q"),
+            ProviderSource::plugin("plug"),
+        )
+        .add_mod_provider(
+            "mod:p",
+            DummyModProvider::new("mod:p"),
+            ProviderSource::plugin("plug"),
+        )
+        .remove_game("game-missing");
+    let errors = update.commit().unwrap_err();
+    assert!(matches!(&errors[0], RegistryError::ProviderAlreadyExists(id) if id == "mod:p"));
+    assert!(matches!(&errors[1], RegistryError::NotFound(id) if id == "game-missing"));
+
+    // Removing a provider a game still uses only fails once every change is applied
+    let mut update = ctx.begin_update();
+    update.remove_mod_provider("mod:p");
+    let errors = update.commit().unwrap_err();
+    assert!(matches!(
+        &errors[..],
+        [RegistryError::ProviderInUse(message)] if message == "mod:p (used by game-a)"
+    ));
+
+    assert!(ctx.get_mod_provider("mod:q").is_err());
+    assert!(ctx.get_mod_provider("mod:p").is_ok());
+    assert_eq!(ctx.list_games_for_provider("mod:p").unwrap(), ["game-a"]);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn update_ignores_invalid_entries_it_does_not_touch() {
+    let mut b = ContextBuilder::new();
+    // Realized providers aren't checked against their registry id, like any provider in a
+    // release build
+    b.register_mod_provider_lazy(
+        "mod:lazy",
+        ProviderSource::Core,
+        Box::new(|| Ok(DummyModProvider::new("mod:other"))),
+    )
+    .unwrap();
+    let ctx = b.freeze();
+    ctx.get_mod_provider("mod:lazy").unwrap();
+
+    let mut update = ctx.begin_update();
+    update.add_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    );
+    update.commit().unwrap();
+    assert!(ctx.get_mod_provider("mod:p").is_ok());
+}
+
+#[test]
+fn remove_mod_provider_checks_dependent_games() {
+    let (events, mut rx) = tokio::sync::broadcast::channel(8);
+    let mut b = ContextBuilder::new();
+    b.set_event_sender(events);
+    for id in ["mod:p", "mod:q"] {
+        b.register_mod_provider(id, DummyModProvider::new(id), ProviderSource::Core)
+            .unwrap();
+    }
+    b.register_game_provider(
+        DummyGameProvider::new("game-a", "mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.alias_provider("mod:old-q", "mod:q").unwrap();
+    let ctx = b.freeze();
+    while rx.try_recv().is_ok() {}
+
+    let err = ctx.remove_mod_provider("mod:p").unwrap_err();
+    assert_eq!(
+        err,
+        RegistryError::ProviderInUse("mod:p (used by game-a)".to_string())
+    );
+    assert_eq!(
+        err.to_string(),
+        "Mod provider is still in use: mod:p (used by game-a)"
+    );
+    assert!(ctx.get_mod_provider("mod:p").is_ok());
+
+    ctx.remove_mod_provider("MOD:Q").unwrap();
+    assert!(ctx.get_mod_provider("mod:q").is_err());
+    assert!(ctx.list_aliases().is_empty());
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ContextEvent::ProviderRemoved("mod:q".to_string())
+    );
+    assert!(matches!(
+        ctx.remove_mod_provider("mod:q"),
+        Err(RegistryError::NotFound(_))
+    ));
+}
+
+#[test]
+fn update_deactivates_the_active_game_only_if_removed() {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    for id in ["game-a", "game-b"] {
+        b.register_game_provider(DummyGameProvider::new(id, "mod:p"), ProviderSource::Core)
+            .unwrap();
+    }
+    b.alias_game("old-a", "game-a").unwrap();
+    let ctx = b.freeze();
+    ctx.activate_game("game-a").unwrap();
+    let mut rx = ctx.subscribe_events();
+
+    let mut update = ctx.begin_update();
+    update
+        .replace_game_provider(DummyGameProvider::new("game-a", "mod:p"))
+        .remove_game("game-b");
+    update.commit().unwrap();
+    assert_eq!(ctx.active_game().as_deref(), Some("game-a"));
+    assert_eq!(ctx.list_game_aliases().len(), 1);
+
+    let mut update = ctx.begin_update();
+    update.remove_game("game-a");
+    update.commit().unwrap();
+    assert_eq!(ctx.active_game(), None);
+    assert!(ctx.list_game_aliases().is_empty());
+    assert_eq!(rx.try_recv().unwrap(), ContextEvent::GameDeactivated);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn update_replaces_providers_and_keeps_cached_metadata() {
+    let game = CountingGameProvider::new("game-a", "Alpha");
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider_with(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+        ProviderOptions { priority: 5 },
+    )
+    .unwrap();
+    b.register_game_provider(game.clone(), ProviderSource::Core)
+        .unwrap();
+    let ctx = b.freeze();
+    let mut rx = ctx.subscribe_events();
+
+    let cap = Arc::new(CountingCap::default());
+    let replacement = Arc::new(LifecycleModProvider {
+        caps: vec![cap.clone() as CapabilityRef],
+    });
+    let mut update = ctx.begin_update();
+    update.replace_mod_provider("mod:p", replacement);
+    // `LifecycleModProvider` reports a different `Provider::id()`
+    assert!(matches!(
+        &update.commit().unwrap_err()[..],
+        [RegistryError::IdMismatch { .. }]
+    ));
+
+    let mut update = ctx.begin_update();
+    update.replace_mod_provider("mod:p", DummyModProvider::new("mod:p"));
+    update.commit().unwrap();
+    let providers = ctx.list_mod_providers_detailed();
+    assert_eq!(providers[0].priority, 5);
+    assert_eq!(providers[0].source, ProviderSource::Core);
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        ContextEvent::ProviderReloaded("mod:p".into())
+    );
+
+    assert_eq!(ctx.get_metadata("game-a").unwrap().display_name, "Alpha");
+    assert_eq!(game.metadata_calls.load(Ordering::SeqCst), 1);
+    assert!(cap.events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn readers_never_see_a_partial_update() {
+    let ctx = context_with_game("game-a");
+    let done = std::sync::atomic::AtomicBool::new(false);
+
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..200 {
+                let mut update = ctx.begin_update();
+                update
+                    .add_mod_provider(
+                        "mod:q",
+                        DummyModProvider::new("mod:q"),
+                        ProviderSource::plugin("plug"),
+                    )
+                    .add_game_provider(
+                        DummyGameProvider::new("game-b", "mod:q"),
+                        ProviderSource::plugin("plug"),
+                    );
+                update.commit().unwrap();
+
+                let mut update = ctx.begin_update();
+                update.remove_game("game-b").remove_mod_provider("mod:q");
+                update.commit().unwrap();
+            }
+            done.store(true, Ordering::SeqCst);
+        });
+
+        while !done.load(Ordering::SeqCst) {
+            let snapshot = ctx.snapshot();
+            let has_game = snapshot.games.iter().any(|g| g.id == "game-b");
+            let has_provider = snapshot.mod_providers.iter().any(|p| p.id == "mod:q");
+            assert_eq!(has_game, has_provider);
+        }
+    });
+}
+
+fn counting_game_context(game: Arc<CountingGameProvider>) -> Context {
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:p",
+        DummyModProvider::new("mod:p"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.register_game_provider(game, ProviderSource::Core)
+        .unwrap();
+    b.freeze()
+}
+
+#[tokio::test]
+async fn shutdown_cancels_tracked_tasks_and_notifies_providers() {
+    let game = CountingGameProvider::new("game-a", "Alpha");
+    let ctx = counting_game_context(game.clone());
+
+    let token = ctx.cancellation_token();
+    let observed = Arc::new(AtomicUsize::new(0));
+    let task_observed = observed.clone();
+    ctx.spawn_tracked(async move {
+        token.cancelled().await;
+        task_observed.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let report = ctx.shutdown().await;
+    assert!(report.is_clean());
+    assert_eq!(observed.load(Ordering::SeqCst), 1);
+    assert_eq!(report.notified, ["mod:p", "game-a"]);
+    assert_eq!(game.shutdown_calls.load(Ordering::SeqCst), 1);
+    assert!(ctx.cancellation_token().is_cancelled());
+
+    // Providers are only notified once
+    let report = ctx.shutdown().await;
+    assert!(report.notified.is_empty());
+    assert_eq!(game.shutdown_calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn shutdown_reports_tasks_ignoring_cancellation() {
+    let ctx = counting_game_context(CountingGameProvider::new("game-a", "Alpha"));
+    let (release, released) = tokio::sync::oneshot::channel::<()>();
+    ctx.spawn_tracked(async move {
+        let _ = released.await;
+    });
+
+    let report = ctx
+        .shutdown_with_timeout(std::time::Duration::from_millis(20))
+        .await;
+    assert_eq!(report.pending_tasks, 1);
+    assert!(!report.is_clean());
+    // The providers are notified regardless
+    assert_eq!(report.notified.len(), 2);
+
+    release.send(()).unwrap();
+    let report = ctx.shutdown().await;
+    assert!(report.is_clean());
+}
+
+#[tokio::test]
+async fn dropping_the_context_cancels_its_token() {
+    let ctx = counting_game_context(CountingGameProvider::new("game-a", "Alpha"));
+    let token = ctx.cancellation_token();
+    assert!(!token.is_cancelled());
+    drop(ctx);
+    assert!(token.is_cancelled());
+}
@@ -0,0 +1,185 @@
+use std::{path::PathBuf, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::watch,
+};
+
+use crate::{
+    services::{DefaultDownloadService, DownloadEvent, DownloadService, DownloadServiceConfig},
+    traits::mod_provider::{DownloadProgress, ModDownloadResult},
+};
+
+/// Size of the payload the test server returns for `/mods/<name>`
+const PAYLOAD_LEN: usize = 300 * 1024;
+
+fn payload() -> Vec<u8> {
+    (0..PAYLOAD_LEN).map(|i| (i % 251) as u8).collect()
+}
+
+/// Serves the payload for every path under `/mods/` and a 404 for anything else,
+/// returning the base url
+async fn serve() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = if path.starts_with("/mods/") {
+                    ("200 OK", payload())
+                } else {
+                    ("404 Not Found", b"not found".to_vec())
+                };
+                let head = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&body).await;
+            });
+        }
+    });
+    format!("http://{addr}")
+}
+
+fn service(download_dir: PathBuf) -> DefaultDownloadService {
+    DefaultDownloadService::new(DownloadServiceConfig {
+        download_dir,
+        ..DownloadServiceConfig::default()
+    })
+    .unwrap()
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("vmm-download-test-{}", uuid::Uuid::new_v4()))
+}
+
+async fn finished(mut rx: watch::Receiver<ModDownloadResult>) -> ModDownloadResult {
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        rx.wait_for(|r| {
+            matches!(
+                r,
+                ModDownloadResult::Completed(_) | ModDownloadResult::Failed(_)
+            )
+        }),
+    )
+    .await
+    .expect("download should finish")
+    .unwrap();
+    result.clone()
+}
+
+#[tokio::test]
+async fn downloads_to_the_configured_folder() {
+    let base = serve().await;
+    let dir = temp_dir();
+    let service = service(dir.clone());
+    let mut events = service.subscribe_events();
+
+    let rx = service
+        .queue_download(format!("{base}/mods/cool-mod.zip"))
+        .await;
+    assert!(matches!(
+        *rx.borrow(),
+        ModDownloadResult::Progress(DownloadProgress {
+            bytes_downloaded: 0,
+            ..
+        })
+    ));
+    let ModDownloadResult::Completed(path) = finished(rx).await else {
+        panic!("download should complete");
+    };
+    assert_eq!(std::fs::read(&path).unwrap(), payload());
+
+    let mut received = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        received.push(event);
+    }
+    assert_eq!(path, dir.join(format!("{}-cool-mod.zip", received[0].id())));
+    assert!(matches!(received[0], DownloadEvent::Queued { .. }));
+    assert!(matches!(received[1], DownloadEvent::Started { .. }));
+    assert!(matches!(
+        received.last(),
+        Some(DownloadEvent::Completed { .. })
+    ));
+    let progress: Vec<u64> = received
+        .iter()
+        .filter_map(|e| match e {
+            DownloadEvent::Progress { bytes, total, .. } => {
+                assert_eq!(*total, Some(PAYLOAD_LEN as u64));
+                Some(*bytes)
+            }
+            _ => None,
+        })
+        .collect();
+    // Throttled to whole percents
+    assert!(!progress.is_empty() && progress.len() <= 100);
+    assert!(progress.is_sorted());
+    assert_eq!(progress.last(), Some(&(PAYLOAD_LEN as u64)));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn downloads_with_the_same_name_keep_separate_files() {
+    let base = serve().await;
+    let dir = temp_dir();
+    let service = service(dir.clone());
+
+    let url = format!("{base}/mods/cool-mod.zip");
+    let (first, second) = tokio::join!(
+        service.queue_download(url.clone()),
+        service.queue_download(url)
+    );
+    let (first, second) = tokio::join!(finished(first), finished(second));
+    let (ModDownloadResult::Completed(first), ModDownloadResult::Completed(second)) =
+        (first, second)
+    else {
+        panic!("downloads should complete");
+    };
+    assert_ne!(first, second);
+    assert_eq!(std::fs::read(&first).unwrap(), payload());
+    assert_eq!(std::fs::read(&second).unwrap(), payload());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn failed_downloads_leave_no_file() {
+    let base = serve().await;
+    let dir = temp_dir();
+    let service = service(dir.clone());
+    let mut events = service.subscribe_events();
+
+    let rx = service.queue_download(format!("{base}/missing.zip")).await;
+    let ModDownloadResult::Failed(reason) = finished(rx).await else {
+        panic!("download should fail");
+    };
+    assert!(reason.contains("404"), "{reason}");
+    assert!(!dir.exists() || std::fs::read_dir(&dir).unwrap().next().is_none());
+
+    let queued = events.recv().await.unwrap();
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        DownloadEvent::Started { .. }
+    ));
+    assert!(matches!(
+        events.recv().await.unwrap(),
+        DownloadEvent::Failed { id, .. } if id == queued.id()
+    ));
+}
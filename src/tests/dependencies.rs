@@ -0,0 +1,107 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    capabilities::{
+        base::CapabilityRef,
+        builder::CapabilityBuilder,
+        dependencies_capability::{DependencyError, ResolvesDependencies, resolve_install_order},
+        ids,
+    },
+    traits::discovery::{DiscoveryError, ModDependency},
+};
+
+fn dep(mod_id: &str, optional: bool) -> ModDependency {
+    ModDependency {
+        mod_id: mod_id.into(),
+        version_req: None,
+        optional,
+    }
+}
+
+fn graph(edges: &[(&str, &[&str])]) -> HashMap<String, Vec<ModDependency>> {
+    edges
+        .iter()
+        .map(|(id, deps)| (id.to_string(), deps.iter().map(|d| dep(d, false)).collect()))
+        .collect()
+}
+
+#[test]
+fn resolve_diamond_installs_shared_dependency_once() {
+    let deps = graph(&[
+        ("app", &["ui", "net"]),
+        ("ui", &["core"]),
+        ("net", &["core"]),
+        ("core", &[]),
+    ]);
+    let order = resolve_install_order("app", &deps).unwrap();
+
+    assert_eq!(order.len(), 4);
+    assert_eq!(order.first().map(String::as_str), Some("core"));
+    assert_eq!(order.last().map(String::as_str), Some("app"));
+}
+
+#[test]
+fn resolve_detects_cycles() {
+    let deps = graph(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]);
+    assert_eq!(
+        resolve_install_order("a", &deps),
+        Err(DependencyError::Cycle(vec![
+            "a".into(),
+            "b".into(),
+            "c".into(),
+            "a".into()
+        ]))
+    );
+}
+
+#[test]
+fn resolve_reports_missing_required_but_skips_optional() {
+    let deps = graph(&[("a", &["b"])]);
+    assert_eq!(
+        resolve_install_order("a", &deps),
+        Err(DependencyError::Missing("b".into()))
+    );
+
+    let mut deps = graph(&[]);
+    deps.insert("a".into(), vec![dep("extras", true)]);
+    assert_eq!(resolve_install_order("a", &deps).unwrap(), vec!["a"]);
+}
+
+struct DependencyProvider {
+    caps: Vec<CapabilityRef>,
+}
+
+#[async_trait]
+impl ResolvesDependencies for DependencyProvider {
+    async fn dependencies_of(&self, mod_id: &str) -> Result<Vec<ModDependency>, DiscoveryError> {
+        match mod_id {
+            "app" => Ok(vec![dep("core", false)]),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn dependencies_capability_delegates() {
+    let provider = Arc::new_cyclic(|weak| DependencyProvider {
+        caps: CapabilityBuilder::new_from_weak(weak.clone())
+            .dependencies()
+            .finish(),
+    });
+    let cap = &provider.caps[0];
+    assert_eq!(cap.id(), ids::RESOLVES_DEPENDENCIES);
+
+    let resolver = cap
+        .as_resolves_dependencies()
+        .expect("should expose behavior");
+    let mut deps = HashMap::new();
+    for id in ["app", "core"] {
+        deps.insert(id.to_string(), resolver.dependencies_of(id).await.unwrap());
+    }
+    assert_eq!(
+        resolve_install_order("app", &deps).unwrap(),
+        vec!["core", "app"]
+    );
+}
@@ -0,0 +1,589 @@
+use crate::{
+    capabilities::base::CapabilityRef,
+    tests::dummy::DummyModProvider,
+    traits::{
+        discovery::{
+            DiscoveryError, DiscoveryQuery, DiscoveryResult, InstalledMod, ModExtendedMetadata,
+            ModSummary, ModVersion, PaginationMeta, SortDirection, SortOrder, Tag, build_tag_tree,
+            compare_versions,
+        },
+        mod_provider::{ModDownloadResult, ModProvider},
+        provider::Provider,
+    },
+};
+
+/// A provider implementing only the required methods
+struct MinimalModProvider;
+
+impl Provider for MinimalModProvider {
+    fn id(&self) -> &'static str {
+        "minimal"
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &[]
+    }
+}
+
+#[async_trait::async_trait]
+impl ModProvider for MinimalModProvider {
+    async fn download_mod(&self, _mod_id: String) -> ModDownloadResult {
+        ModDownloadResult::Cancelled
+    }
+    async fn discover(&self, _query: &DiscoveryQuery) -> Result<DiscoveryResult, DiscoveryError> {
+        Err(DiscoveryError::ProviderUnavailable)
+    }
+    async fn get_extended_mod(&self, _mod_id: &str) -> ModExtendedMetadata {
+        unimplemented!()
+    }
+}
+
+/// A provider relying on the default `check_for_updates`
+struct VersionedModProvider;
+
+impl Provider for VersionedModProvider {
+    fn id(&self) -> &'static str {
+        "versioned"
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &[]
+    }
+}
+
+#[async_trait::async_trait]
+impl ModProvider for VersionedModProvider {
+    async fn download_mod(&self, _mod_id: String) -> ModDownloadResult {
+        ModDownloadResult::Cancelled
+    }
+    async fn discover(&self, _query: &DiscoveryQuery) -> Result<DiscoveryResult, DiscoveryError> {
+        Err(DiscoveryError::ProviderUnavailable)
+    }
+    async fn get_extended_mod(&self, _mod_id: &str) -> ModExtendedMetadata {
+        unimplemented!()
+    }
+    async fn get_mod_versions(&self, mod_id: &str) -> Result<Vec<ModVersion>, DiscoveryError> {
+        if mod_id == "missing" {
+            return Err(DiscoveryError::InvalidQuery("unknown mod".into()));
+        }
+        Ok(["1.9.0", "1.10.0", "1.10.0-rc.1", "1.2.0"]
+            .into_iter()
+            .map(|v| ModVersion {
+                version: v.into(),
+                release_date: None,
+                changelog: Some(format!("Notes for {}", v)),
+                download_url: None,
+                file_size: None,
+            })
+            .collect())
+    }
+}
+
+fn installed(mod_id: &str, version: &str) -> InstalledMod {
+    InstalledMod {
+        mod_id: mod_id.into(),
+        installed_version: version.into(),
+    }
+}
+
+#[tokio::test]
+async fn mod_versions_from_provider() {
+    let provider = DummyModProvider::new("dummy");
+    let versions = provider.get_mod_versions("mod-1").await.unwrap();
+
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].version, "1.1.0");
+    assert_eq!(versions[1].version, "1.0.0");
+}
+
+#[tokio::test]
+async fn mod_versions_default_is_not_implemented() {
+    let err = MinimalModProvider
+        .get_mod_versions("mod-1")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DiscoveryError::Internal(_)));
+}
+
+#[test]
+fn mod_version_serialization_roundtrip() {
+    let version = ModVersion {
+        version: "2.0.0".into(),
+        release_date: Some("2025-03-01".into()),
+        changelog: Some("Rewrite".into()),
+        download_url: None,
+        file_size: Some(1024),
+    };
+    let json = serde_json::to_string(&version).expect("Should serialize");
+    let deserialized: ModVersion = serde_json::from_str(&json).expect("Should deserialize");
+
+    assert_eq!(deserialized.version, "2.0.0");
+    assert_eq!(deserialized.file_size, Some(1024));
+}
+
+#[tokio::test]
+async fn dummy_reports_hardcoded_update() {
+    let provider = DummyModProvider::new("dummy");
+    let updates = provider
+        .check_for_updates(&[installed("mod-1", "1.0.0"), installed("other", "1.0.0")])
+        .await
+        .unwrap();
+
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].mod_id, "mod-1");
+    assert_eq!(updates[0].latest_version, "1.1.0");
+}
+
+#[tokio::test]
+async fn default_update_check_uses_version_history() {
+    let updates = VersionedModProvider
+        .check_for_updates(&[
+            installed("outdated", "1.9.0"),
+            installed("current", "v1.10"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].mod_id, "outdated");
+    assert_eq!(updates[0].current_version, "1.9.0");
+    assert_eq!(updates[0].latest_version, "1.10.0");
+    assert_eq!(
+        updates[0].release_notes.as_deref(),
+        Some("Notes for 1.10.0")
+    );
+}
+
+#[tokio::test]
+async fn default_update_check_skips_failed_lookups() {
+    let updates = VersionedModProvider
+        .check_for_updates(&[
+            installed("missing", "1.0.0"),
+            installed("outdated", "1.9.0"),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].mod_id, "outdated");
+}
+
+#[test]
+fn version_comparison() {
+    use std::cmp::Ordering;
+
+    assert_eq!(compare_versions("1.10.0", "1.9.2"), Ordering::Greater);
+    assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+    assert_eq!(compare_versions("v2.0.0", "2.0.0"), Ordering::Equal);
+    assert_eq!(compare_versions("0.9", "1.0"), Ordering::Less);
+}
+
+#[test]
+fn pre_releases_rank_below_their_release() {
+    use std::cmp::Ordering;
+
+    assert_eq!(compare_versions("1.0.0-beta", "1.0.0"), Ordering::Less);
+    assert_eq!(compare_versions("1.0-rc.1", "1.0.0"), Ordering::Less);
+    assert_eq!(compare_versions("1.0.0-rc.1", "0.9.9"), Ordering::Greater);
+    assert_eq!(
+        compare_versions("1.0.0-alpha", "1.0.0-beta"),
+        Ordering::Less
+    );
+    assert_eq!(
+        compare_versions("1.0.0-beta.2", "1.0.0-beta.11"),
+        Ordering::Less
+    );
+    assert_eq!(
+        compare_versions("1.0.0-beta", "1.0.0-beta.1"),
+        Ordering::Less
+    );
+    assert_eq!(compare_versions("1.0.0-1", "1.0.0-alpha"), Ordering::Less);
+    assert_eq!(compare_versions("1.0.0+build.5", "1.0.0"), Ordering::Equal);
+}
+
+#[tokio::test]
+async fn download_url_from_provider() {
+    let provider = DummyModProvider::new("dummy");
+    let url = provider.get_download_url("mod-1", None).await.unwrap();
+    assert_eq!(url, "https://example.com/mods/mod-1");
+
+    let err = MinimalModProvider
+        .get_download_url("mod-1", Some("1.0.0"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DiscoveryError::Internal(_)));
+}
+
+#[tokio::test]
+async fn dummy_discover_populates_author_and_timestamps() {
+    let provider = DummyModProvider::new("dummy");
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .sort(SortOrder::Updated)
+        .author_id("user-1")
+        .build()
+        .unwrap();
+    let result = provider.discover(&query).await.unwrap();
+    let summary = &result.mods[0];
+    assert_eq!(summary.author_id.as_deref(), Some("user-1"));
+    assert!(summary.author_url.is_some());
+    assert!(summary.last_updated.is_some());
+    assert!(summary.created_at.is_some());
+}
+
+#[tokio::test]
+async fn dummy_discover_populates_size_and_rating() {
+    let provider = DummyModProvider::new("dummy");
+    let query: DiscoveryQuery =
+        serde_json::from_str(r#"{"game_id":"game","page":null,"page_size":null,"search":null,"tags":null,"sort":"Rating","min_rating":4.0}"#)
+            .unwrap();
+    assert!(!query.exclude_nsfw);
+
+    let result = provider.discover(&query).await.unwrap();
+    let summary = &result.mods[0];
+    assert_eq!(summary.file_size, Some(1_048_576));
+    assert_eq!(summary.rating_score, Some(4.5));
+    assert_eq!(summary.rating_count, Some(100));
+    assert!(!summary.is_nsfw);
+}
+
+#[test]
+fn mod_summary_without_author_fields_deserializes() {
+    let json = r#"{"id":"m","name":"M","description":"","short_description":"","downloads":0,
+        "views":0,"likes":0,"thumbnail_image":"","tags":[],"user_name":"u","user_avatar":""}"#;
+    let summary: ModSummary = serde_json::from_str(json).unwrap();
+    assert!(summary.author_id.is_none());
+    assert!(summary.last_updated.is_none());
+    assert!(summary.file_size.is_none());
+    assert!(!summary.is_nsfw);
+}
+
+#[tokio::test]
+async fn extended_metadata_history_roundtrip() {
+    let provider = DummyModProvider::new("dummy");
+    let meta = provider.get_extended_mod("installed-mod").await;
+    assert!(meta.version_history.len() >= 2);
+
+    let json = serde_json::to_string(&meta).unwrap();
+    let back: ModExtendedMetadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.changelog.as_deref(), Some("Fixed crashes"));
+    assert_eq!(back.version_history.len(), meta.version_history.len());
+    assert_eq!(back.version_history[0].version, "1.1.0");
+
+    // Metadata from before the history existed still deserializes
+    let legacy = r#"{"header_image":"","carousel_images":[],"version":"1.0","installed":false,"description":""}"#;
+    let meta: ModExtendedMetadata = serde_json::from_str(legacy).unwrap();
+    assert!(meta.changelog.is_none());
+    assert!(meta.version_history.is_empty());
+}
+
+#[test]
+fn discovery_query_builder_sets_fields() {
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .page(2)
+        .page_size(20)
+        .search("armor")
+        .tag("a")
+        .tag("b")
+        .sort(SortOrder::Downloads)
+        .min_rating(3.5)
+        .exclude_nsfw()
+        .build()
+        .unwrap();
+
+    assert_eq!(query.game_id, "game");
+    assert_eq!(query.page, Some(2));
+    assert_eq!(query.page_size, Some(20));
+    assert_eq!(query.search.as_deref(), Some("armor"));
+    assert_eq!(query.tags, Some(vec!["a".to_string(), "b".to_string()]));
+    assert!(matches!(query.sort, Some(SortOrder::Downloads)));
+    assert_eq!(query.min_rating, Some(3.5));
+    assert!(query.exclude_nsfw);
+}
+
+#[test]
+fn discovery_query_builder_requires_game_id() {
+    assert!(DiscoveryQuery::builder().page(1).build().is_err());
+    assert!(DiscoveryQuery::builder().game_id("  ").build().is_err());
+}
+
+#[tokio::test]
+async fn next_query_is_none_on_last_page() {
+    let provider = DummyModProvider::new("dummy");
+    let query = DiscoveryQuery::builder().game_id("game").build().unwrap();
+    let result = provider.discover(&query).await.unwrap();
+
+    assert!(!result.has_next_page());
+    assert!(result.next_query(&query).is_none());
+}
+
+#[tokio::test]
+async fn next_query_advances_page_or_cursor() {
+    let provider = DummyModProvider::new("dummy");
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .search("armor")
+        .build()
+        .unwrap();
+    let mut result = provider.discover(&query).await.unwrap();
+
+    result.meta.pagination = PaginationMeta {
+        current: 1,
+        page_size: 10,
+        total_pages: Some(3),
+        total_items: None,
+        next_cursor: None,
+    };
+    let next = result.next_query(&query).expect("should have a next page");
+    assert_eq!(next.page, Some(2));
+    assert_eq!(next.search.as_deref(), Some("armor"));
+
+    result.meta.pagination.total_pages = None;
+    result.meta.pagination.next_cursor = Some("abc".into());
+    let next = result.next_query(&query).expect("should have a next page");
+    assert_eq!(next.cursor.as_deref(), Some("abc"));
+    assert_eq!(next.page, None);
+}
+
+#[tokio::test]
+async fn merge_deduplicates_mods_and_tags() {
+    let query = DiscoveryQuery::builder().game_id("game").build().unwrap();
+    let first = DummyModProvider::new("first")
+        .discover(&query)
+        .await
+        .unwrap();
+    let mut second = DummyModProvider::new("second")
+        .discover(&query)
+        .await
+        .unwrap();
+
+    let mut extra = second.mods[0].clone();
+    extra.id = "mod-3".into();
+    second.mods.push(extra);
+    second.meta.pagination.total_items = Some(3);
+    second
+        .meta
+        .available_tags
+        .as_mut()
+        .unwrap()
+        .push(Tag::new("tag2", "Tag Two"));
+
+    let merged = first.merge(second);
+    assert_eq!(merged.meta.provider_id, "first,second");
+    let ids: Vec<&str> = merged.mods.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["mod-1", "mod-2", "mod-3"]);
+    assert_eq!(merged.meta.pagination.total_items, Some(5));
+    assert_eq!(merged.meta.available_tags.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn merge_drops_unknown_totals() {
+    let query = DiscoveryQuery::builder().game_id("game").build().unwrap();
+    let first = DummyModProvider::new("first")
+        .discover(&query)
+        .await
+        .unwrap();
+    let mut second = first.clone();
+    second.meta.pagination.total_items = None;
+
+    assert_eq!(first.merge(second).meta.pagination.total_items, None);
+}
+
+#[test]
+fn http_errors_map_to_discovery_errors() {
+    use crate::net::HttpError;
+
+    assert!(matches!(
+        DiscoveryError::from(HttpError::RateLimit {
+            retry_after_secs: Some(30)
+        }),
+        DiscoveryError::RateLimit {
+            retry_after_secs: Some(30)
+        }
+    ));
+    assert!(matches!(
+        DiscoveryError::from(HttpError::Timeout { elapsed_ms: 500 }),
+        DiscoveryError::Timeout { elapsed_ms: 500 }
+    ));
+    assert!(matches!(
+        DiscoveryError::from(HttpError::Network("offline".into())),
+        DiscoveryError::Network(_)
+    ));
+    assert!(matches!(
+        DiscoveryError::from(HttpError::Parse("bad json".into())),
+        DiscoveryError::Internal(_)
+    ));
+}
+
+#[test]
+fn discovery_error_new_variants_roundtrip() {
+    let err = DiscoveryError::RateLimit {
+        retry_after_secs: None,
+    };
+    let json = serde_json::to_string(&err).unwrap();
+    assert!(matches!(
+        serde_json::from_str(&json).unwrap(),
+        DiscoveryError::RateLimit {
+            retry_after_secs: None
+        }
+    ));
+}
+
+#[tokio::test]
+async fn dummy_discover_filters_client_side() {
+    let provider = DummyModProvider::new("dummy");
+    let ids = |result: DiscoveryResult| -> Vec<String> {
+        result.mods.into_iter().map(|m| m.id).collect()
+    };
+
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .exclude_tag("early-access")
+        .build()
+        .unwrap();
+    assert_eq!(ids(provider.discover(&query).await.unwrap()), vec!["mod-1"]);
+
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .min_downloads(10)
+        .build()
+        .unwrap();
+    assert_eq!(ids(provider.discover(&query).await.unwrap()), vec!["mod-1"]);
+
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .updated_after("2024-01-01")
+        .build()
+        .unwrap();
+    assert_eq!(query.date_after.as_deref(), Some("2024-01-01"));
+    assert_eq!(ids(provider.discover(&query).await.unwrap()), vec!["mod-1"]);
+
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .updated_before("2024-02-01")
+        .build()
+        .unwrap();
+    assert_eq!(
+        ids(provider.discover(&query).await.unwrap()),
+        vec!["mod-1", "mod-2"]
+    );
+}
+
+/// A summary last updated at `last_updated`
+fn updated_summary(last_updated: &str) -> ModSummary {
+    let json = r#"{"id":"m","name":"M","description":"","short_description":"","downloads":0,
+        "views":0,"likes":0,"thumbnail_image":"","tags":[],"user_name":"u","user_avatar":""}"#;
+    ModSummary {
+        last_updated: Some(last_updated.to_string()),
+        ..serde_json::from_str(json).unwrap()
+    }
+}
+
+#[test]
+fn date_filters_compare_full_timestamps_by_day() {
+    let summary = updated_summary("2024-05-01T08:00:00Z");
+    let query = |after: &str, before: &str| {
+        DiscoveryQuery::builder()
+            .game_id("game")
+            .updated_after(after)
+            .updated_before(before)
+            .build()
+            .unwrap()
+    };
+    assert!(query("2024-05-01T12:00:00Z", "2024-05-01T06:00:00Z").matches(&summary));
+    assert!(!query("2024-05-02T00:00:00Z", "2024-06-01").matches(&summary));
+    assert!(!query("2024-01-01", "2024-04-30T23:59:59Z").matches(&summary));
+}
+
+#[test]
+fn date_filters_tolerate_non_ascii_dates() {
+    // Byte 10 is inside the 'é'
+    let summary = updated_summary("2024-05-0é-garbage");
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .updated_after("2024-01-01")
+        .build()
+        .unwrap();
+    assert!(query.matches(&summary));
+    assert!(query.matches(&updated_summary("é")));
+}
+
+#[test]
+fn custom_sort_order_and_direction() {
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .sort(SortOrder::Custom("featured".into()))
+        .ascending()
+        .build()
+        .unwrap();
+    assert_eq!(query.sort_direction, Some(SortDirection::Ascending));
+
+    let json = serde_json::to_string(&query).unwrap();
+    let back: DiscoveryQuery = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.sort, Some(SortOrder::Custom("featured".into())));
+
+    let sort = back.sort.unwrap();
+    assert_eq!(sort.or_relevance(&["featured"]), sort);
+    assert_eq!(sort.or_relevance(&["staff_picks"]), SortOrder::Relevance);
+    assert_eq!(SortOrder::Likes.or_relevance(&[]), SortOrder::Likes);
+
+    let query = DiscoveryQuery::builder()
+        .game_id("game")
+        .descending()
+        .build()
+        .unwrap();
+    assert_eq!(query.sort_direction, Some(SortDirection::Descending));
+}
+
+#[test]
+fn pagination_page_helpers() {
+    let page = |current, total_pages, total_items| PaginationMeta {
+        current,
+        page_size: 10,
+        total_pages,
+        total_items,
+        next_cursor: None,
+    };
+
+    assert!(page(1, None, None).is_first_page());
+    assert!(!page(2, None, None).is_first_page());
+
+    assert!(!page(1, None, None).is_last_page());
+    assert!(!page(1, Some(2), None).is_last_page());
+    assert!(page(2, Some(2), None).is_last_page());
+    assert!(!page(2, None, Some(21)).is_last_page());
+    assert!(page(3, None, Some(21)).is_last_page());
+    assert!(page(2, None, Some(20)).is_last_page());
+
+    assert_eq!(page(1, None, Some(21)).total_count(), Some(21));
+    assert_eq!(page(1, None, None).total_count(), None);
+}
+
+#[test]
+fn tag_tree_groups_by_parent() {
+    let mut combat = Tag::new("combat", "Combat");
+    combat.parent_id = Some("gameplay".into());
+    let mut weapons = Tag::new("weapons", "Weapons");
+    weapons.parent_id = Some("combat".into());
+    weapons.color = Some("#FF5733".into());
+    let tags = vec![
+        Tag::new("gameplay", "Gameplay"),
+        combat,
+        weapons,
+        Tag::new("visuals", "Visuals"),
+    ];
+
+    let tree = build_tag_tree(&tags);
+    let ids = |parent: Option<&str>| -> Vec<&str> {
+        tree[&parent.map(str::to_string)]
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect()
+    };
+    assert_eq!(ids(None), vec!["gameplay", "visuals"]);
+    assert_eq!(ids(Some("gameplay")), vec!["combat"]);
+    assert_eq!(ids(Some("combat")), vec!["weapons"]);
+
+    // Tags from before the hierarchy existed are top-level
+    let tag: Tag = serde_json::from_str(r#"{"id":"a","name":"A"}"#).unwrap();
+    assert!(tag.parent_id.is_none() && tag.color.is_none());
+}
@@ -0,0 +1,259 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    capabilities::{
+        base::CapabilityRef, builder::CapabilityBuilder, ids,
+        multiple_files_capability::HasMultipleFiles,
+    },
+    services::{DownloadEvent, DownloadService},
+    tests::dummy::{DummyDownloadService, DummyModProvider},
+    traits::{
+        discovery::{
+            DiscoveryError, DiscoveryQuery, DiscoveryResult, ModExtendedMetadata, ModFileCategory,
+            ModFileInfo,
+        },
+        mod_provider::{DownloadProgress, ModDownloadResult, ModProvider},
+        provider::Provider,
+    },
+};
+
+#[tokio::test]
+async fn download_events_reach_every_subscriber() {
+    let service = DummyDownloadService::new();
+    let mut first = service.subscribe_events();
+    let mut second = service.subscribe_events();
+
+    let _ = service
+        .queue_download("https://example.com/mod.zip".into())
+        .await;
+
+    for rx in [&mut first, &mut second] {
+        let queued = rx.recv().await.unwrap();
+        let id = queued.id();
+        assert!(
+            matches!(queued, DownloadEvent::Queued { ref url, .. } if url == "https://example.com/mod.zip")
+        );
+        assert!(matches!(rx.recv().await.unwrap(), DownloadEvent::Started { id: i } if i == id));
+        assert!(
+            matches!(rx.recv().await.unwrap(), DownloadEvent::Completed { id: i, .. } if i == id)
+        );
+    }
+}
+
+#[test]
+fn download_event_serialization_roundtrip() {
+    let event = DownloadEvent::Progress {
+        id: uuid::Uuid::new_v4(),
+        bytes: 512,
+        total: Some(1024),
+    };
+    let json = serde_json::to_string(&event).expect("Should serialize");
+    let deserialized: DownloadEvent = serde_json::from_str(&json).expect("Should deserialize");
+
+    assert_eq!(deserialized.id(), event.id());
+    assert!(matches!(
+        deserialized,
+        DownloadEvent::Progress {
+            bytes: 512,
+            total: Some(1024),
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn dummy_download_reports_progress_before_completion() {
+    let provider = DummyModProvider::new("dummy");
+    let updates = provider.download_updates("some-mod");
+
+    let (last, progress) = updates.split_last().unwrap();
+    assert!(progress.len() >= 2);
+    assert!(
+        progress
+            .iter()
+            .all(|u| matches!(u, ModDownloadResult::Progress(_)))
+    );
+    assert!(matches!(last, ModDownloadResult::Completed(_)));
+    assert!(matches!(
+        provider.download_mod("some-mod".into()).await,
+        ModDownloadResult::Completed(_)
+    ));
+    assert!(matches!(
+        provider.download_mod("fail".into()).await,
+        ModDownloadResult::Failed(_)
+    ));
+}
+
+#[test]
+fn download_speed_and_eta_from_byte_counts() {
+    let previous = ModDownloadResult::from(DownloadProgress::new(1_000, Some(11_000)));
+    let current = ModDownloadResult::from(DownloadProgress::new(3_000, Some(11_000)));
+    let elapsed = Duration::from_secs(2);
+
+    assert_eq!(
+        ModDownloadResult::speed_bps(&current, &previous, elapsed),
+        Some(1_000.0)
+    );
+    assert_eq!(
+        ModDownloadResult::estimated_remaining(&current, &previous, elapsed),
+        Some(Duration::from_secs(8))
+    );
+}
+
+#[test]
+fn download_speed_requires_progress_and_elapsed_time() {
+    let progress = ModDownloadResult::from(DownloadProgress::new(10, None));
+    let done = ModDownloadResult::Completed("/tmp/x".into());
+
+    assert!(ModDownloadResult::speed_bps(&done, &progress, Duration::from_secs(1)).is_none());
+    assert!(ModDownloadResult::speed_bps(&progress, &progress, Duration::ZERO).is_none());
+    // Unknown total size means no ETA, even with a known speed
+    assert!(
+        ModDownloadResult::estimated_remaining(&progress, &progress, Duration::from_secs(1))
+            .is_none()
+    );
+}
+
+#[test]
+fn download_progress_percent() {
+    assert_eq!(DownloadProgress::new(256, Some(1024)).percent(), Some(25));
+    assert_eq!(DownloadProgress::new(1023, Some(1024)).percent(), Some(99));
+    // Servers can send more than announced
+    assert_eq!(DownloadProgress::new(2048, Some(1024)).percent(), Some(100));
+    assert_eq!(DownloadProgress::new(0, Some(0)).percent(), Some(100));
+    assert_eq!(DownloadProgress::new(256, None).percent(), None);
+}
+
+#[test]
+fn download_progress_serialization_roundtrip() {
+    let progress = DownloadProgress {
+        bytes_downloaded: 512,
+        total_bytes: None,
+        bytes_per_second: Some(128),
+    };
+    let json = serde_json::to_string(&progress).expect("Should serialize");
+    assert_eq!(
+        json,
+        r#"{"bytes_downloaded":512,"total_bytes":null,"bytes_per_second":128}"#
+    );
+    let deserialized: DownloadProgress = serde_json::from_str(&json).expect("Should deserialize");
+    assert_eq!(deserialized, progress);
+}
+
+/// A provider hosting a main file and an optional patch per mod
+struct MultiFileModProvider {
+    caps: Vec<CapabilityRef>,
+}
+
+impl MultiFileModProvider {
+    fn new() -> Arc<Self> {
+        Arc::new_cyclic(|weak| MultiFileModProvider {
+            caps: CapabilityBuilder::new_from_weak(weak.clone())
+                .multiple_files()
+                .finish(),
+        })
+    }
+}
+
+impl Provider for MultiFileModProvider {
+    fn id(&self) -> &'static str {
+        "multi-file"
+    }
+    fn capabilities(&self) -> &[CapabilityRef] {
+        &self.caps
+    }
+}
+
+#[async_trait]
+impl ModProvider for MultiFileModProvider {
+    async fn download_mod(&self, mod_id: String) -> ModDownloadResult {
+        self.download_file(&mod_id, "main").await
+    }
+    async fn discover(&self, _query: &DiscoveryQuery) -> Result<DiscoveryResult, DiscoveryError> {
+        Err(DiscoveryError::ProviderUnavailable)
+    }
+    async fn get_extended_mod(&self, _mod_id: &str) -> ModExtendedMetadata {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl HasMultipleFiles for MultiFileModProvider {
+    async fn list_files(&self, _mod_id: &str) -> Result<Vec<ModFileInfo>, DiscoveryError> {
+        let file = |file_id: &str, category| ModFileInfo {
+            file_id: file_id.into(),
+            name: format!("{}.zip", file_id),
+            size: Some(1024),
+            category,
+            version: Some("1.0.0".into()),
+            uploaded_at: None,
+        };
+        Ok(vec![
+            file("main", ModFileCategory::Main),
+            file("patch", ModFileCategory::Patch),
+        ])
+    }
+
+    async fn download_file(&self, mod_id: &str, file_id: &str) -> ModDownloadResult {
+        ModDownloadResult::Completed(PathBuf::from(format!("/tmp/{}/{}", mod_id, file_id)))
+    }
+}
+
+/// Downloads the given file if the provider hosts several, falling back to `download_mod`
+async fn download(provider: &dyn ModProvider, mod_id: &str, file_id: &str) -> ModDownloadResult {
+    match provider
+        .find_capability(ids::HAS_MULTIPLE_FILES)
+        .and_then(|c| c.as_has_multiple_files())
+    {
+        Some(files) => files.download_file(mod_id, file_id).await,
+        None => provider.download_mod(mod_id.to_string()).await,
+    }
+}
+
+#[tokio::test]
+async fn multiple_files_capability_lists_and_downloads() {
+    let provider = MultiFileModProvider::new();
+    let files = provider.capabilities()[0]
+        .as_has_multiple_files()
+        .expect("should expose behavior");
+
+    let listed = files.list_files("mod-1").await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[1].category, ModFileCategory::Patch);
+
+    assert!(matches!(
+        download(provider.as_ref(), "mod-1", "patch").await,
+        ModDownloadResult::Completed(p) if p.to_str() == Some("/tmp/mod-1/patch")
+    ));
+    // The plain path still works for providers with the capability
+    assert!(matches!(
+        provider.download_mod("mod-1".into()).await,
+        ModDownloadResult::Completed(p) if p.to_str() == Some("/tmp/mod-1/main")
+    ));
+}
+
+#[tokio::test]
+async fn download_falls_back_without_multiple_files() {
+    let provider = DummyModProvider::new("dummy");
+    assert!(provider.find_capability(ids::HAS_MULTIPLE_FILES).is_none());
+    assert!(matches!(
+        download(provider.as_ref(), "mod-1", "ignored").await,
+        ModDownloadResult::Completed(p) if p.to_str() == Some("/tmp/mod-1")
+    ));
+}
+
+#[tokio::test]
+async fn multiple_files_capability_provider_dropped() {
+    let cap = MultiFileModProvider::new().capabilities()[0].clone();
+    let files = cap.as_has_multiple_files().unwrap();
+    assert!(matches!(
+        files.list_files("mod-1").await,
+        Err(DiscoveryError::ProviderUnavailable)
+    ));
+    assert!(matches!(
+        files.download_file("mod-1", "main").await,
+        ModDownloadResult::Failed(_)
+    ));
+}
@@ -1,32 +1,43 @@
 use std::{
+    collections::HashSet,
     fmt::Debug,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use async_trait::async_trait;
+use tokio::sync::{broadcast, watch};
+use uuid::Uuid;
 
 use crate::{
     capabilities::{
         api_key_capability::{ApiKeyValidationError, ApiSubmitResponse, KeyAction, RequiresApiKey},
         base::CapabilityRef,
         builder::{CapabilityBuilder, CapabilityError},
-        form::{Field, FieldType, FormSchema},
+        form::{Field, FormSchema},
+        ids,
+        profiles_capability::{ProfileError, ProfileInfo, SupportsProfiles},
     },
     registry::model::ProviderSource,
+    services::{DownloadEvent, DownloadService},
     traits::{
         discovery::{
-            DiscoveryError, DiscoveryMeta, DiscoveryQuery, DiscoveryResult, ModExtendedMetadata,
-            ModSummary, PaginationMeta, Tag,
+            DiscoveryError, DiscoveryMeta, DiscoveryQuery, DiscoveryResult, InstalledMod,
+            ModExtendedMetadata, ModSummary, ModVersion, PaginationMeta, Tag, UpdateInfo,
+            UserProfile,
         },
-        game_provider::{GameIcon, GameInstallError, GameMetadata, GameProvider},
-        mod_provider::{ModDownloadResult, ModProvider},
-        provider::Provider,
+        game_provider::{
+            GameIcon, GameInstallError, GameLaunchError, GameLaunchHandle, GameMetadata,
+            GameProvider, GameVerifyReport, InstalledModInfo,
+        },
+        mod_provider::{DownloadProgress, ModDownloadResult, ModProvider},
+        provider::{Provider, ProviderHealthStatus},
     },
 };
 
 pub struct DummyModProvider {
-    id: String,
+    /// Leaked so `Provider::id()` can report the registered id
+    id: &'static str,
     caps: Vec<CapabilityRef>,
 }
 
@@ -46,26 +57,55 @@ impl DummyModProvider {
                 .finish();
 
             DummyModProvider {
-                id: id.to_string(),
+                id: Box::leak(id.to_string().into_boxed_str()),
                 caps,
             }
         })
     }
 
     pub fn id_str(&self) -> &str {
-        &self.id
+        self.id
+    }
+
+    /// Every update a download of `mod_id` goes through, the last one is returned by `download_mod`
+    pub fn download_updates(&self, mod_id: &str) -> Vec<ModDownloadResult> {
+        if mod_id == "fail" {
+            return vec![ModDownloadResult::Failed("bad id".into())];
+        }
+
+        let total = 1024;
+        let mut updates: Vec<ModDownloadResult> = [0, 256, 768, 1024]
+            .into_iter()
+            .map(|bytes| DownloadProgress::new(bytes, Some(total)).into())
+            .collect();
+        updates.push(ModDownloadResult::Completed(PathBuf::from(format!(
+            "/tmp/{}",
+            mod_id
+        ))));
+        updates
     }
 }
 
+#[async_trait]
 impl Provider for DummyModProvider {
     fn id(&self) -> &'static str {
-        "dummyModProvider"
+        self.id
+    }
+    fn display_name(&self) -> Option<&str> {
+        Some("Dummy Mod Provider")
+    }
+    fn version(&self) -> Option<&str> {
+        Some("1.0.0")
     }
     fn capabilities(&self) -> &[CapabilityRef] {
         &self.caps
     }
+    async fn health_check(&self) -> ProviderHealthStatus {
+        ProviderHealthStatus::Healthy
+    }
 }
 
+#[async_trait]
 impl RequiresApiKey for DummyModProvider {
     fn on_provided(&self, value: &[ApiSubmitResponse]) -> Result<KeyAction, ApiKeyValidationError> {
         let first = value.first().ok_or(ApiKeyValidationError::Empty)?;
@@ -80,38 +120,55 @@ impl RequiresApiKey for DummyModProvider {
         Ok(KeyAction::Store)
     }
 
+    /// Simulates a `/validate` call, keys starting with `OFFLINE` fail to reach the provider,
+    /// keys starting with `REVOKED` are rejected by it and keys starting with `EXCHANGE`
+    /// are swapped for a session token
+    async fn on_provided_async(
+        &self,
+        value: &[ApiSubmitResponse],
+    ) -> Result<KeyAction, ApiKeyValidationError> {
+        self.on_provided(value)?;
+
+        let key = value[0].value.trim();
+        let response: Result<bool, DiscoveryError> = if key.starts_with("OFFLINE") {
+            Err(DiscoveryError::Network("connection refused".into()))
+        } else {
+            Ok(!key.starts_with("REVOKED"))
+        };
+
+        match response {
+            Ok(true) if key.starts_with("EXCHANGE") => Ok(KeyAction::StoreTransformed {
+                value: format!("session-{}", &key["EXCHANGE".len()..]),
+            }),
+            Ok(true) => Ok(KeyAction::Store),
+            Ok(false) => Err(ApiKeyValidationError::Invalid),
+            Err(_) => Err(ApiKeyValidationError::ProviderError),
+        }
+    }
+
     fn needs_prompt(&self, existing_key: Option<&str>) -> bool {
         match existing_key {
             None => true,
-            Some(k) if k.is_empty() => true,
+            Some("") => true,
             Some(_) => false,
         }
     }
 
     fn render(&self) -> Result<FormSchema, CapabilityError> {
-        Ok(FormSchema {
-            title: "Enter key".into(),
-            description: Some("Description".into()),
-            fields: vec![Field {
-                id: "api_key".into(),
-                label: "api_key".into(),
-                field_type: FieldType::Password,
-                regex: None,
-                help: None,
-                placeholder: Some("Paste key here".into()),
-            }],
-        })
+        Ok(FormSchema::builder("Enter key")
+            .description("Description")
+            .field(Field::password("api_key").placeholder("Paste key here"))
+            .build()
+            .expect("dummy schema is valid"))
     }
 }
 
 #[async_trait]
 impl ModProvider for DummyModProvider {
     async fn download_mod(&self, mod_id: String) -> ModDownloadResult {
-        if mod_id == "fail" {
-            ModDownloadResult::Failed("bad id".into())
-        } else {
-            ModDownloadResult::Completed(PathBuf::from(format!("/tmp/{}", mod_id)))
-        }
+        self.download_updates(&mod_id)
+            .pop()
+            .expect("at least one update")
     }
 
     async fn discover(&self, query: &DiscoveryQuery) -> Result<DiscoveryResult, DiscoveryError> {
@@ -127,7 +184,29 @@ impl ModProvider for DummyModProvider {
             tags: vec!["tag1".into()],
             user_name: "tester".into(),
             user_avatar: "/avatar.png".into(),
+            author_id: Some("user-1".into()),
+            author_url: Some("https://example.com/users/user-1".into()),
+            last_updated: Some("2024-02-01T12:00:00Z".into()),
+            created_at: Some("2024-01-01T12:00:00Z".into()),
+            file_size: Some(1_048_576),
+            rating_score: Some(4.5),
+            rating_count: Some(100),
+            is_nsfw: false,
+        };
+        let early_access = ModSummary {
+            id: "mod-2".into(),
+            name: "Early Access Mod".into(),
+            downloads: 3,
+            tags: vec!["early-access".into()],
+            last_updated: Some("2023-06-01T12:00:00Z".into()),
+            rating_score: None,
+            rating_count: None,
+            ..summary.clone()
         };
+        let mut mods = vec![summary, early_access];
+        // No server-side filtering here, so apply the filters client-side
+        mods.retain(|m| query.matches(m));
+
         Ok(DiscoveryResult {
             meta: DiscoveryMeta {
                 provider_id: self.id_str().to_string(),
@@ -136,40 +215,144 @@ impl ModProvider for DummyModProvider {
                     current: 1,
                     page_size: 10,
                     total_pages: Some(1),
-                    total_items: Some(1),
+                    total_items: Some(mods.len() as u32),
+                    next_cursor: None,
                 },
                 applied_tags: query.tags.clone().unwrap_or_default(),
-                available_tags: Some(vec![Tag {
-                    id: "tag1".into(),
-                    name: "Tag One".into(),
-                }]),
+                available_tags: Some(vec![Tag::new("tag1", "Tag One")]),
             },
-            mods: vec![summary],
+            mods,
         })
     }
 
     async fn get_extended_mod(&self, mod_id: &str) -> ModExtendedMetadata {
+        let version_history = if mod_id == "installed-mod" {
+            self.get_mod_versions(mod_id).await.unwrap_or_default()
+        } else {
+            Vec::new()
+        };
         ModExtendedMetadata {
             header_image: "/header.png".into(),
             carousel_images: vec!["/c1.png".into(), "/c2.png".into()],
             version: "1.0.0".into(),
             installed: mod_id == "installed-mod",
             description: format!("Extended meta for {}", mod_id),
+            changelog: Some("Fixed crashes".into()),
+            version_history,
+        }
+    }
+
+    async fn get_mod_versions(&self, mod_id: &str) -> Result<Vec<ModVersion>, DiscoveryError> {
+        Ok(vec![
+            ModVersion {
+                version: "1.1.0".into(),
+                release_date: Some("2025-02-01".into()),
+                changelog: Some("Fixed crashes".into()),
+                download_url: Some(format!("https://example.com/mods/{}/1.1.0", mod_id)),
+                file_size: Some(2048),
+            },
+            ModVersion {
+                version: "1.0.0".into(),
+                release_date: Some("2025-01-01".into()),
+                changelog: None,
+                download_url: None,
+                file_size: None,
+            },
+        ])
+    }
+
+    async fn get_download_url(
+        &self,
+        mod_id: &str,
+        _version: Option<&str>,
+    ) -> Result<String, DiscoveryError> {
+        Ok(format!("https://example.com/mods/{}", mod_id))
+    }
+
+    async fn get_user_profile(&self) -> Result<UserProfile, DiscoveryError> {
+        if self.needs_prompt(None) {
+            return Err(DiscoveryError::InvalidQuery("No API key stored".into()));
         }
+        Ok(UserProfile {
+            username: "tester".into(),
+            avatar_url: Some("/avatar.png".into()),
+            profile_url: None,
+        })
+    }
+
+    async fn check_for_updates(
+        &self,
+        installed: &[InstalledMod],
+    ) -> Result<Vec<UpdateInfo>, DiscoveryError> {
+        Ok(installed
+            .iter()
+            .filter(|m| m.mod_id == "mod-1" && m.installed_version != "1.1.0")
+            .map(|m| UpdateInfo {
+                mod_id: m.mod_id.clone(),
+                current_version: m.installed_version.clone(),
+                latest_version: "1.1.0".into(),
+                release_notes: Some("Fixed crashes".into()),
+            })
+            .collect())
     }
 }
 
 pub struct DummyGameProvider {
     id: String,
-    mod_provider: String,
+    /// The primary provider first
+    mod_providers: Vec<String>,
+    disabled_mods: Mutex<HashSet<String>>,
+    profiles: Mutex<Vec<ProfileInfo>>,
+    active_profile: Mutex<String>,
+    load_order: Arc<Mutex<Vec<String>>>,
+    caps: Vec<CapabilityRef>,
 }
 
 impl DummyGameProvider {
-    pub fn new(id: &str, mod_provider: &str) -> Self {
-        Self {
-            id: id.to_string(),
-            mod_provider: mod_provider.to_string(),
+    /// The only mod installed into the dummy game
+    pub const INSTALLED_MOD: &str = "installed-mod";
+    /// Has to be loaded before every other mod
+    pub const BASE_PATCH: &str = "base-patch";
+
+    pub fn new(id: &str, mod_provider: &str) -> Arc<Self> {
+        Self::with_providers(id, &[mod_provider])
+    }
+
+    /// A game pulling mods from several providers, the first one is the primary provider
+    pub fn with_providers(id: &str, mod_providers: &[&str]) -> Arc<Self> {
+        Arc::new_cyclic(|weak_self| {
+            let caps = CapabilityBuilder::new_from_weak(weak_self.clone())
+                .profiles()
+                .finish();
+
+            Self {
+                id: id.to_string(),
+                mod_providers: mod_providers.iter().map(|p| p.to_string()).collect(),
+                disabled_mods: Mutex::new(HashSet::new()),
+                profiles: Mutex::new(vec![ProfileInfo {
+                    id: "default".into(),
+                    name: "Default".into(),
+                }]),
+                active_profile: Mutex::new("default".into()),
+                load_order: Arc::new(Mutex::new(vec![
+                    Self::BASE_PATCH.into(),
+                    Self::INSTALLED_MOD.into(),
+                ])),
+                caps,
+            }
+        })
+    }
+
+    /// The current load order, shared so tests can inspect it
+    pub fn load_order(&self) -> Arc<Mutex<Vec<String>>> {
+        Arc::clone(&self.load_order)
+    }
+
+    fn check_installed(mod_id: &str) -> Result<(), GameInstallError> {
+        if mod_id != Self::INSTALLED_MOD {
+            return Err(GameInstallError::ModNotFound(mod_id.to_string()));
         }
+        Ok(())
     }
 }
 
@@ -178,14 +361,74 @@ impl Provider for DummyGameProvider {
         "dummy.game"
     }
     fn capabilities(&self) -> &[CapabilityRef] {
-        &[]
+        &self.caps
+    }
+}
+
+impl SupportsProfiles for DummyGameProvider {
+    fn list_profiles(&self) -> Vec<ProfileInfo> {
+        self.profiles.lock().unwrap().clone()
+    }
+
+    fn create_profile(&self, name: &str) -> Result<ProfileInfo, ProfileError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ProfileError::InvalidName("Name cannot be empty".into()));
+        }
+        let mut profiles = self.profiles.lock().unwrap();
+        if profiles.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+            return Err(ProfileError::AlreadyExists(name.to_string()));
+        }
+        let profile = ProfileInfo {
+            id: name.to_lowercase().replace(' ', "-"),
+            name: name.to_string(),
+        };
+        profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    fn activate_profile(&self, id: &str) -> Result<(), ProfileError> {
+        if !self.profiles.lock().unwrap().iter().any(|p| p.id == id) {
+            return Err(ProfileError::NotFound(id.to_string()));
+        }
+        *self.active_profile.lock().unwrap() = id.to_string();
+        Ok(())
+    }
+
+    fn delete_profile(&self, id: &str) -> Result<(), ProfileError> {
+        if *self.active_profile.lock().unwrap() == id {
+            return Err(ProfileError::ProfileActive(id.to_string()));
+        }
+        let mut profiles = self.profiles.lock().unwrap();
+        let index = profiles
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| ProfileError::NotFound(id.to_string()))?;
+        profiles.remove(index);
+        Ok(())
+    }
+
+    fn active_profile(&self) -> Option<ProfileInfo> {
+        let active = self.active_profile.lock().unwrap();
+        self.profiles
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|p| p.id == *active)
+            .cloned()
     }
 }
 
 #[async_trait]
 impl GameProvider for DummyGameProvider {
     fn mod_provider_id(&self) -> &str {
-        &self.mod_provider
+        &self.mod_providers[0]
+    }
+    fn mod_provider_ids(&self) -> Vec<&str> {
+        self.mod_providers.iter().map(String::as_str).collect()
+    }
+    fn required_mod_provider_capabilities(&self) -> &[&'static str] {
+        &[ids::REQUIRES_API_KEY]
     }
 
     fn game_id(&self) -> &str {
@@ -198,13 +441,118 @@ impl GameProvider for DummyGameProvider {
             display_name: "Dummy Game".into(),
             short_name: "DG".into(),
             icon: GameIcon::Path("/icon.png".into()),
-            provider_source: ProviderSource::Plugin("plugin-x".into()),
+            provider_source: ProviderSource::plugin("plugin-x"),
+            version: self.get_game_version(),
+            executable_path: None,
+            install_path: self.game_root_path(),
         }
     }
     fn get_external_id(&self) -> &str {
         "external-123"
     }
-    fn install_mod(&self, _path: &Path) -> Result<(), GameInstallError> {
+    /// Archives ending in `.already` are reported as installed and `.incompatible` ones
+    /// as built for another game version
+    fn install_mod(&self, path: &Path) -> Result<(), GameInstallError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("already") => Err(GameInstallError::AlreadyInstalled {
+                mod_id: Self::INSTALLED_MOD.into(),
+                installed_version: "1.0.0".into(),
+            }),
+            Some("incompatible") => Err(GameInstallError::IncompatibleVersion {
+                mod_version: "2.0.0".into(),
+                game_version: self.get_game_version().unwrap_or_default(),
+            }),
+            _ => Ok(()),
+        }
+    }
+    fn reinstall_mod(&self, path: &Path) -> Result<(), GameInstallError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("already") => Ok(()),
+            _ => self.install_mod(path),
+        }
+    }
+    fn list_installed_mods(&self) -> Result<Vec<InstalledModInfo>, GameInstallError> {
+        let enabled = !self
+            .disabled_mods
+            .lock()
+            .unwrap()
+            .contains(Self::INSTALLED_MOD);
+        Ok(vec![InstalledModInfo {
+            mod_id: Self::INSTALLED_MOD.into(),
+            version: Some("1.0.0".into()),
+            install_path: PathBuf::from("/games/dummy/mods/installed-mod"),
+            enabled,
+            install_time: None,
+        }])
+    }
+    fn enable_mod(&self, mod_id: &str) -> Result<(), GameInstallError> {
+        Self::check_installed(mod_id)?;
+        self.disabled_mods.lock().unwrap().remove(mod_id);
+        Ok(())
+    }
+    fn disable_mod(&self, mod_id: &str) -> Result<(), GameInstallError> {
+        Self::check_installed(mod_id)?;
+        self.disabled_mods
+            .lock()
+            .unwrap()
+            .insert(mod_id.to_string());
         Ok(())
     }
+    fn get_load_order(&self) -> Vec<String> {
+        self.load_order.lock().unwrap().clone()
+    }
+    fn set_load_order(&self, order: Vec<String>) -> Result<(), GameInstallError> {
+        if order.iter().position(|m| m == Self::BASE_PATCH) != Some(0) {
+            return Err(GameInstallError::LoadOrderConflict(format!(
+                "{} has to be loaded first",
+                Self::BASE_PATCH
+            )));
+        }
+        *self.load_order.lock().unwrap() = order;
+        Ok(())
+    }
+    fn get_game_version(&self) -> Option<String> {
+        Some("1.0.0".into())
+    }
+    async fn verify_game_files(&self) -> Result<GameVerifyReport, GameInstallError> {
+        Ok(GameVerifyReport::default())
+    }
+    async fn launch_game(&self, _args: Vec<String>) -> Result<GameLaunchHandle, GameLaunchError> {
+        Err(GameLaunchError::NotInstalled)
+    }
+}
+
+pub struct DummyDownloadService {
+    events: broadcast::Sender<DownloadEvent>,
+}
+
+impl DummyDownloadService {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(16);
+        Self { events }
+    }
+}
+
+#[async_trait]
+impl DownloadService for DummyDownloadService {
+    async fn queue_download(&self, url: String) -> watch::Receiver<ModDownloadResult> {
+        let id = Uuid::new_v4();
+        let path = PathBuf::from("/tmp/dummy-download");
+        let _ = self.events.send(DownloadEvent::Queued {
+            id,
+            url: url.clone(),
+        });
+        let _ = self.events.send(DownloadEvent::Started { id });
+        let _ = self.events.send(DownloadEvent::Completed {
+            id,
+            path: path.clone(),
+        });
+
+        let (_tx, rx) = watch::channel(ModDownloadResult::Completed(path));
+        rx
+    }
+
+    fn subscribe_events(&self) -> broadcast::Receiver<DownloadEvent> {
+        self.events.subscribe()
+    }
 }
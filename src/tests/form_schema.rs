@@ -1,49 +1,44 @@
-use crate::capabilities::form::{Field, FieldType, FormSchema};
+use crate::capabilities::{
+    api_key_capability::ApiSubmitResponse,
+    form::{
+        Field, FieldCondition, FieldType, FlowStep, FormFlow, FormFlowState, FormResponse,
+        FormSchema, FormSchemaError, FormStep, FormValidationError, FormValue, SelectOption,
+        StepCondition,
+    },
+};
 
 #[test]
 fn form_schema_with_all_field_types() {
-    let schema = FormSchema {
-        title: "Complete Form".to_string(),
-        description: Some("A form with all field types".to_string()),
-        fields: vec![
-            Field {
-                id: "text_field".to_string(),
-                label: "Text Input".to_string(),
-                field_type: FieldType::Text,
-                placeholder: Some("Enter text".to_string()),
-                regex: None,
-                help: Some("A text field".to_string()),
-            },
-            Field {
-                id: "password_field".to_string(),
-                label: "Password".to_string(),
-                field_type: FieldType::Password,
-                placeholder: Some("Enter password".to_string()),
-                regex: Some(r"^.{8,}$".to_string()),
-                help: Some("At least 8 characters".to_string()),
-            },
-            Field {
-                id: "select_field".to_string(),
-                label: "Select Option".to_string(),
-                field_type: FieldType::Select(vec![
+    let schema = FormSchema::builder("Complete Form")
+        .description("A form with all field types")
+        .field(
+            Field::text("text_field")
+                .label("Text Input")
+                .placeholder("Enter text")
+                .help("A text field"),
+        )
+        .field(
+            Field::password("password_field")
+                .label("Password")
+                .placeholder("Enter password")
+                .regex(r"^.{8,}$")
+                .help("At least 8 characters"),
+        )
+        .field(
+            Field::select(
+                "select_field",
+                vec![
                     "Option A".to_string(),
                     "Option B".to_string(),
                     "Option C".to_string(),
-                ]),
-                placeholder: None,
-                regex: None,
-                help: Some("Choose one".to_string()),
-            },
-            Field {
-                id: "info_field".to_string(),
-                label: "Information".to_string(),
-                field_type: FieldType::MarkdownInfo,
-                placeholder: None,
-                regex: None,
-                help: None,
-            },
-        ],
-    };
+                ],
+            )
+            .label("Select Option")
+            .help("Choose one"),
+        )
+        .field(Field::markdown("info_field").label("Information"))
+        .build()
+        .unwrap();
 
     assert_eq!(schema.fields.len(), 4);
     assert_eq!(schema.title, "Complete Form");
@@ -113,14 +108,11 @@ fn field_type_markdown_info() {
 
 #[test]
 fn field_with_regex_validation() {
-    let field = Field {
-        id: "email".to_string(),
-        label: "Email Address".to_string(),
-        field_type: FieldType::Text,
-        placeholder: Some("user@example.com".to_string()),
-        regex: Some(r"^[^\s@]+@[^\s@]+\.[^\s@]+$".to_string()),
-        help: Some("Enter a valid email".to_string()),
-    };
+    let field = Field::text("email")
+        .label("Email Address")
+        .placeholder("user@example.com")
+        .regex(r"^[^\s@]+@[^\s@]+\.[^\s@]+$")
+        .help("Enter a valid email");
 
     assert_eq!(field.id, "email");
     assert!(field.regex.is_some());
@@ -131,14 +123,11 @@ fn field_with_regex_validation() {
 
 #[test]
 fn field_serialization_roundtrip() {
-    let field = Field {
-        id: "username".to_string(),
-        label: "Username".to_string(),
-        field_type: FieldType::Text,
-        placeholder: Some("Enter username".to_string()),
-        regex: Some(r"^\w{3,20}$".to_string()),
-        help: Some("3-20 characters".to_string()),
-    };
+    let field = Field::text("username")
+        .label("Username")
+        .placeholder("Enter username")
+        .regex(r"^\w{3,20}$")
+        .help("3-20 characters");
 
     let json = serde_json::to_string(&field).expect("Should serialize");
     let deserialized: Field = serde_json::from_str(&json).expect("Should deserialize");
@@ -152,28 +141,21 @@ fn field_serialization_roundtrip() {
 
 #[test]
 fn form_schema_serialization_roundtrip() {
-    let schema = FormSchema {
-        title: "Registration Form".to_string(),
-        description: Some("Please fill out all fields".to_string()),
-        fields: vec![
-            Field {
-                id: "name".to_string(),
-                label: "Full Name".to_string(),
-                field_type: FieldType::Text,
-                placeholder: Some("John Doe".to_string()),
-                regex: None,
-                help: None,
-            },
-            Field {
-                id: "password".to_string(),
-                label: "Password".to_string(),
-                field_type: FieldType::Password,
-                placeholder: None,
-                regex: Some(r"^.{8,}$".to_string()),
-                help: Some("Minimum 8 characters".to_string()),
-            },
-        ],
-    };
+    let schema = FormSchema::builder("Registration Form")
+        .description("Please fill out all fields")
+        .field(
+            Field::text("name")
+                .label("Full Name")
+                .placeholder("John Doe"),
+        )
+        .field(
+            Field::password("password")
+                .label("Password")
+                .regex(r"^.{8,}$")
+                .help("Minimum 8 characters"),
+        )
+        .build()
+        .unwrap();
 
     let json = serde_json::to_string(&schema).expect("Should serialize");
     let deserialized: FormSchema = serde_json::from_str(&json).expect("Should deserialize");
@@ -192,7 +174,9 @@ fn form_schema_serialization_roundtrip() {
 fn form_schema_minimal() {
     let schema = FormSchema {
         title: "Minimal".to_string(),
+        title_key: None,
         description: None,
+        description_key: None,
         fields: vec![],
     };
 
@@ -203,14 +187,11 @@ fn form_schema_minimal() {
 
 #[test]
 fn field_clone() {
-    let field = Field {
-        id: "test".to_string(),
-        label: "Test".to_string(),
-        field_type: FieldType::Text,
-        placeholder: Some("placeholder".to_string()),
-        regex: Some("regex".to_string()),
-        help: Some("help".to_string()),
-    };
+    let field = Field::text("test")
+        .label("Test")
+        .placeholder("placeholder")
+        .regex("regex")
+        .help("help");
 
     let cloned = field.clone();
     assert_eq!(field.id, cloned.id);
@@ -222,7 +203,9 @@ fn field_clone() {
 fn form_schema_clone() {
     let schema = FormSchema {
         title: "Test".to_string(),
+        title_key: None,
         description: Some("Description".to_string()),
+        description_key: None,
         fields: vec![],
     };
 
@@ -239,6 +222,17 @@ fn field_type_clone() {
         FieldType::Password,
         FieldType::Select(vec!["a".to_string()]),
         FieldType::MarkdownInfo,
+        FieldType::Checkbox { default: true },
+        FieldType::Number {
+            min: Some(0.0),
+            max: None,
+            step: None,
+        },
+        FieldType::MultiSelect(vec!["a".to_string()]),
+        FieldType::FilePicker {
+            directories_only: false,
+            extensions: vec![],
+        },
     ];
 
     for ft in types {
@@ -250,14 +244,7 @@ fn field_type_clone() {
 
 #[test]
 fn field_debug_output() {
-    let field = Field {
-        id: "debug_test".to_string(),
-        label: "Debug Test".to_string(),
-        field_type: FieldType::Text,
-        placeholder: None,
-        regex: None,
-        help: None,
-    };
+    let field = Field::text("debug_test").label("Debug Test");
 
     let debug_str = format!("{:?}", field);
     assert!(debug_str.contains("debug_test"));
@@ -268,7 +255,9 @@ fn field_debug_output() {
 fn form_schema_debug_output() {
     let schema = FormSchema {
         title: "Debug Form".to_string(),
+        title_key: None,
         description: Some("For debugging".to_string()),
+        description_key: None,
         fields: vec![],
     };
 
@@ -294,20 +283,693 @@ fn field_type_select_large_option_list() {
 
 #[test]
 fn field_with_complex_regex() {
-    let field = Field {
-        id: "complex".to_string(),
-        label: "Complex Validation".to_string(),
-        field_type: FieldType::Text,
-        placeholder: None,
-        regex: Some(
-            r"^(?=.*[a-z])(?=.*[A-Z])(?=.*\d)(?=.*[@$!%*?&])[A-Za-z\d@$!%*?&]{8,}$".to_string(),
-        ),
-        help: Some(
-            "Password must contain uppercase, lowercase, number, and special character".to_string(),
-        ),
-    };
+    let field = Field::text("complex")
+        .label("Complex Validation")
+        .regex(r"^(?=.*[a-z])(?=.*[A-Z])(?=.*\d)(?=.*[@$!%*?&])[A-Za-z\d@$!%*?&]{8,}$")
+        .help("Password must contain uppercase, lowercase, number, and special character");
 
     assert!(field.regex.is_some());
     let regex = field.regex.unwrap();
     assert!(regex.len() > 20);
 }
+
+#[test]
+fn field_type_checkbox_serialization() {
+    let field_type = FieldType::Checkbox { default: true };
+    let json = serde_json::to_string(&field_type).expect("Should serialize");
+    let deserialized: FieldType = serde_json::from_str(&json).expect("Should deserialize");
+
+    match deserialized {
+        FieldType::Checkbox { default } => assert!(default),
+        _ => panic!("Incorrect deserialization"),
+    }
+}
+
+#[test]
+fn field_type_number_serialization() {
+    let field_type = FieldType::Number {
+        min: Some(0.0),
+        max: Some(100.0),
+        step: None,
+    };
+    let json = serde_json::to_string(&field_type).expect("Should serialize");
+    let deserialized: FieldType = serde_json::from_str(&json).expect("Should deserialize");
+
+    match deserialized {
+        FieldType::Number { min, max, step } => {
+            assert_eq!(min, Some(0.0));
+            assert_eq!(max, Some(100.0));
+            assert_eq!(step, None);
+        }
+        _ => panic!("Incorrect deserialization"),
+    }
+}
+
+#[test]
+fn field_type_multi_select_serialization() {
+    let field_type = FieldType::MultiSelect(vec!["Red".to_string(), "Blue".to_string()]);
+    let json = serde_json::to_string(&field_type).expect("Should serialize");
+    let deserialized: FieldType = serde_json::from_str(&json).expect("Should deserialize");
+
+    match deserialized {
+        FieldType::MultiSelect(opts) => assert_eq!(opts, vec!["Red", "Blue"]),
+        _ => panic!("Incorrect deserialization"),
+    }
+}
+
+#[test]
+fn field_type_file_picker_serialization() {
+    let field_type = FieldType::FilePicker {
+        directories_only: false,
+        extensions: vec!["zip".to_string(), "7z".to_string()],
+    };
+    let json = serde_json::to_string(&field_type).expect("Should serialize");
+    let deserialized: FieldType = serde_json::from_str(&json).expect("Should deserialize");
+
+    match deserialized {
+        FieldType::FilePicker {
+            directories_only,
+            extensions,
+        } => {
+            assert!(!directories_only);
+            assert_eq!(extensions, vec!["zip", "7z"]);
+        }
+        _ => panic!("Incorrect deserialization"),
+    }
+}
+
+#[test]
+fn field_type_legacy_payloads_still_deserialize() {
+    let text: FieldType = serde_json::from_str(r#""Text""#).expect("Should deserialize");
+    assert!(matches!(text, FieldType::Text));
+
+    let select: FieldType =
+        serde_json::from_str(r#"{"Select":["a","b"]}"#).expect("Should deserialize");
+    assert!(matches!(select, FieldType::Select(opts) if opts.len() == 2));
+}
+
+fn response(id: &str, value: &str) -> FormResponse {
+    FormResponse {
+        id: id.to_string(),
+        value: value.into(),
+    }
+}
+
+fn validation_schema() -> FormSchema {
+    FormSchema::builder("Mod settings")
+        .field(
+            Field::text("name")
+                .label("Name")
+                .regex(r"^\w{3,20}$")
+                .required(),
+        )
+        .field(
+            Field::select("quality", vec!["Low".to_string(), "High".to_string()]).label("Quality"),
+        )
+        .field(
+            Field::multi_select("features", vec!["A".to_string(), "B".to_string()])
+                .label("Features"),
+        )
+        .field(Field::number("volume", Some(0.0), Some(100.0)).label("Volume"))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn validate_accepts_valid_responses() {
+    let schema = validation_schema();
+    let validated = schema
+        .validate(&[
+            response("name", "player_one"),
+            response("quality", "High"),
+            response("features", "A, B"),
+            response("volume", "42.5"),
+        ])
+        .expect("Should validate");
+
+    assert_eq!(
+        validated.get("name").and_then(FormValue::as_str),
+        Some("player_one")
+    );
+    assert_eq!(
+        validated.get("volume").and_then(FormValue::as_number),
+        Some(42.5)
+    );
+}
+
+#[test]
+fn validate_reports_errors_per_field() {
+    let schema = validation_schema();
+    let err = schema
+        .validate(&[
+            response("quality", "Ultra"),
+            response("features", "A,C"),
+            response("volume", "101"),
+            response("unknown", "x"),
+        ])
+        .unwrap_err();
+
+    let FormValidationError::Fields(errors) = &err else {
+        panic!("expected field errors");
+    };
+    assert_eq!(errors.len(), 5);
+    assert!(err.for_field("name").is_some()); // required but missing
+    assert!(err.for_field("quality").is_some());
+    assert!(err.for_field("features").is_some());
+    assert!(err.for_field("volume").is_some());
+    assert!(err.for_field("unknown").is_some());
+}
+
+#[test]
+fn validate_rejects_blank_required_and_bad_patterns() {
+    let schema = validation_schema();
+    let blank = schema.validate(&[response("name", "   ")]).unwrap_err();
+    assert!(blank.for_field("name").is_some());
+
+    let mismatch = schema.validate(&[response("name", "a b")]).unwrap_err();
+    assert!(mismatch.for_field("name").is_some());
+
+    let nan = schema
+        .validate(&[response("name", "abc"), response("volume", "loud")])
+        .unwrap_err();
+    assert!(nan.for_field("volume").is_some());
+    assert!(nan.for_field("name").is_none());
+}
+
+#[test]
+fn validate_rejects_non_finite_numbers() {
+    let schema = validation_schema();
+    for value in ["NaN", "inf", "-infinity"] {
+        let err = schema
+            .validate(&[response("name", "abc"), response("volume", value)])
+            .unwrap_err();
+        assert_eq!(
+            err.for_field("volume"),
+            Some(format!("'{value}' is not a number").as_str())
+        );
+    }
+
+    let unbounded = FormSchema::builder("Ratio")
+        .field(Field::number("ratio", None, None))
+        .build()
+        .unwrap();
+    assert!(unbounded.validate(&[response("ratio", "NaN")]).is_err());
+}
+
+#[test]
+fn validate_reports_invalid_regex() {
+    let schema = FormSchema::builder("Broken")
+        .field(Field::text("broken").label("Broken").regex("(unclosed"))
+        .build()
+        .unwrap();
+
+    let err = schema.validate(&[response("broken", "value")]).unwrap_err();
+    assert!(
+        err.for_field("broken")
+            .unwrap()
+            .starts_with("Invalid pattern")
+    );
+}
+
+#[test]
+fn validate_accepts_api_submit_responses() {
+    let schema = validation_schema();
+    let submitted = vec![ApiSubmitResponse {
+        id: "name".to_string(),
+        value: "player_one".to_string(),
+    }];
+    let responses: Vec<FormResponse> = submitted.into_iter().map(Into::into).collect();
+
+    assert!(schema.validate(&responses).is_ok());
+}
+
+#[test]
+fn form_validation_error_serialization_roundtrip() {
+    let err = validation_schema().validate(&[]).unwrap_err();
+    let json = serde_json::to_string(&err).expect("Should serialize");
+    let deserialized: FormValidationError =
+        serde_json::from_str(&json).expect("Should deserialize");
+
+    assert_eq!(deserialized, err);
+}
+
+fn step(id: &str, condition: Option<(&str, &str)>) -> FormStep {
+    FormStep {
+        id: id.to_string(),
+        schema: FormSchema {
+            title: id.to_string(),
+            title_key: None,
+            description: None,
+            description_key: None,
+            fields: vec![],
+        },
+        condition: condition.map(|(field_id, equals)| StepCondition {
+            field_id: field_id.to_string(),
+            equals: equals.into(),
+        }),
+    }
+}
+
+fn auth_flow() -> FormFlow {
+    FormFlow {
+        steps: vec![
+            step("method", None),
+            step("api_key", Some(("method", "key"))),
+            step("oauth", Some(("method", "oauth"))),
+            step("confirm", None),
+        ],
+    }
+}
+
+fn current_step<'a>(state: &FormFlowState, flow: &'a FormFlow) -> Option<&'a str> {
+    match state.next(flow) {
+        FlowStep::Step(step) => Some(step.id.as_str()),
+        FlowStep::Complete => None,
+    }
+}
+
+#[test]
+fn form_flow_follows_conditions() {
+    let flow = auth_flow();
+    let mut state = FormFlowState::new();
+
+    assert_eq!(current_step(&state, &flow), Some("method"));
+    state.submit("method", vec![response("method", "oauth")]);
+    assert_eq!(current_step(&state, &flow), Some("oauth"));
+    state.submit("oauth", vec![]);
+    assert_eq!(current_step(&state, &flow), Some("confirm"));
+    state.submit("confirm", vec![]);
+    assert_eq!(current_step(&state, &flow), None);
+}
+
+#[test]
+fn form_flow_resubmission_replaces_answers() {
+    let flow = auth_flow();
+    let mut state = FormFlowState::new();
+
+    state.submit("method", vec![response("method", "oauth")]);
+    state.submit("method", vec![response("method", "key")]);
+
+    assert_eq!(state.responses.len(), 1);
+    assert_eq!(current_step(&state, &flow), Some("api_key"));
+}
+
+#[test]
+fn form_flow_single_wraps_schema() {
+    let flow = FormFlow::single(validation_schema());
+    let mut state = FormFlowState::new();
+
+    let FlowStep::Step(first) = state.next(&flow) else {
+        panic!("Expected a step");
+    };
+    assert_eq!(first.schema.title, "Mod settings");
+    state.submit(&first.id.clone(), vec![response("name", "abc")]);
+    assert!(matches!(state.next(&flow), FlowStep::Complete));
+}
+
+#[test]
+fn form_flow_serialization_roundtrip() {
+    let flow = auth_flow();
+    let json = serde_json::to_string(&flow).expect("Should serialize");
+    let deserialized: FormFlow = serde_json::from_str(&json).expect("Should deserialize");
+
+    assert_eq!(deserialized.steps.len(), 4);
+    let cond = deserialized.steps[1].condition.as_ref().unwrap();
+    assert_eq!(cond.field_id, "method");
+    assert_eq!(cond.equals, FormValue::Text("key".to_string()));
+}
+
+#[test]
+fn validate_accepts_typed_values() {
+    let schema = validation_schema();
+    let validated = schema
+        .validate(&[
+            response("name", "player_one"),
+            FormResponse {
+                id: "features".to_string(),
+                value: FormValue::List(vec!["A".to_string(), "B".to_string()]),
+            },
+            FormResponse {
+                id: "volume".to_string(),
+                value: FormValue::Number(10.0),
+            },
+        ])
+        .expect("Should validate");
+
+    assert_eq!(
+        validated.get("features").and_then(FormValue::as_list),
+        Some(vec!["A".to_string(), "B".to_string()])
+    );
+
+    let err = schema
+        .validate(&[
+            response("name", "player_one"),
+            FormResponse {
+                id: "volume".to_string(),
+                value: FormValue::Bool(true),
+            },
+        ])
+        .unwrap_err();
+    assert!(err.for_field("volume").is_some());
+}
+
+#[test]
+fn form_value_untagged_serialization() {
+    let values = vec![
+        (FormValue::Text("hello".to_string()), r#""hello""#),
+        (FormValue::Bool(true), "true"),
+        (FormValue::Number(2.5), "2.5"),
+        (
+            FormValue::List(vec!["a".to_string(), "b".to_string()]),
+            r#"["a","b"]"#,
+        ),
+    ];
+
+    for (value, expected) in values {
+        let json = serde_json::to_string(&value).expect("Should serialize");
+        assert_eq!(json, expected);
+        let deserialized: FormValue = serde_json::from_str(&json).expect("Should deserialize");
+        assert_eq!(deserialized, value);
+    }
+}
+
+#[test]
+fn form_response_legacy_string_payload() {
+    let legacy: FormResponse =
+        serde_json::from_str(r#"{"id":"enabled","value":"true"}"#).expect("Should deserialize");
+
+    assert_eq!(legacy.value, FormValue::Text("true".to_string()));
+    assert_eq!(legacy.value.as_bool(), Some(true));
+    assert_eq!(legacy.value.as_str(), Some("true"));
+}
+
+#[test]
+fn form_value_lossy_conversions() {
+    assert_eq!(FormValue::from("3").as_number(), Some(3.0));
+    assert_eq!(
+        FormValue::from("a, b").as_list(),
+        Some(vec!["a".to_string(), "b".to_string()])
+    );
+    assert_eq!(FormValue::Bool(false).to_text(), "false");
+    assert_eq!(
+        FormValue::from(String::from("x")),
+        FormValue::Text("x".to_string())
+    );
+    assert!(FormValue::Number(1.0).as_str().is_none());
+}
+
+#[test]
+fn builder_constructors_set_field_types() {
+    let schema = FormSchema::builder("All types")
+        .field(Field::text("text"))
+        .field(Field::password("password"))
+        .field(Field::select("select", vec!["a".to_string()]))
+        .field(Field::markdown("info"))
+        .field(Field::checkbox("checkbox", true))
+        .field(Field::number("number", Some(1.0), None).step(0.5))
+        .field(Field::multi_select("multi", vec!["a".to_string()]))
+        .field(Field::file_picker("folder", true, vec![]))
+        .build()
+        .unwrap();
+
+    assert!(matches!(schema.fields[0].field_type, FieldType::Text));
+    assert!(matches!(schema.fields[1].field_type, FieldType::Password));
+    assert!(matches!(schema.fields[2].field_type, FieldType::Select(_)));
+    assert!(matches!(
+        schema.fields[3].field_type,
+        FieldType::MarkdownInfo
+    ));
+    assert!(matches!(
+        schema.fields[4].field_type,
+        FieldType::Checkbox { default: true }
+    ));
+    assert!(matches!(
+        schema.fields[5].field_type,
+        FieldType::Number {
+            min: Some(1.0),
+            max: None,
+            step: Some(0.5)
+        }
+    ));
+    assert!(matches!(
+        schema.fields[6].field_type,
+        FieldType::MultiSelect(_)
+    ));
+    assert!(matches!(
+        schema.fields[7].field_type,
+        FieldType::FilePicker {
+            directories_only: true,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn builder_sets_optional_properties() {
+    let field = Field::text("name")
+        .label("Name")
+        .placeholder("Your name")
+        .regex("^.+$")
+        .help("Shown publicly")
+        .required()
+        .value("anon");
+
+    assert_eq!(field.label, "Name");
+    assert_eq!(field.placeholder.as_deref(), Some("Your name"));
+    assert_eq!(field.regex.as_deref(), Some("^.+$"));
+    assert_eq!(field.help.as_deref(), Some("Shown publicly"));
+    assert!(field.required);
+    assert_eq!(field.value, Some(FormValue::Text("anon".to_string())));
+
+    // The label defaults to the id
+    assert_eq!(Field::text("plain").label, "plain");
+}
+
+#[test]
+fn builder_rejects_duplicate_field_ids() {
+    let err = FormSchema::builder("Duplicates")
+        .field(Field::text("name"))
+        .field(Field::password("name"))
+        .build()
+        .unwrap_err();
+
+    assert_eq!(err, FormSchemaError::DuplicateFieldId("name".to_string()));
+}
+
+#[test]
+fn builder_rejects_empty_field_ids() {
+    let err = FormSchema::builder("Empty")
+        .field(Field::text("  "))
+        .build()
+        .unwrap_err();
+
+    assert_eq!(err, FormSchemaError::EmptyFieldId);
+}
+
+fn server_form() -> FormSchema {
+    FormSchema::builder("Server")
+        .field(Field::checkbox("custom_server", false))
+        .field(
+            Field::text("endpoint")
+                .required()
+                .visible_when(FieldCondition::equals("custom_server", true)),
+        )
+        .field(Field::text("region").visible_when(FieldCondition::one_of(
+            "endpoint",
+            vec!["eu".into(), "us".into()],
+        )))
+        .build()
+        .unwrap()
+}
+
+fn visible_ids(schema: &FormSchema, current: &[FormResponse]) -> Vec<String> {
+    schema
+        .visible_fields(current)
+        .iter()
+        .map(|f| f.id.clone())
+        .collect()
+}
+
+#[test]
+fn visible_fields_follow_conditions() {
+    let schema = server_form();
+    assert_eq!(visible_ids(&schema, &[]), vec!["custom_server"]);
+
+    let current = vec![
+        FormResponse {
+            id: "custom_server".into(),
+            value: FormValue::Bool(true),
+        },
+        FormResponse {
+            id: "endpoint".into(),
+            value: "eu".into(),
+        },
+    ];
+    assert_eq!(
+        visible_ids(&schema, &current),
+        vec!["custom_server", "endpoint", "region"]
+    );
+
+    // Hiding a field also hides the fields depending on it
+    let current = vec![
+        FormResponse {
+            id: "custom_server".into(),
+            value: "false".into(),
+        },
+        FormResponse {
+            id: "endpoint".into(),
+            value: "eu".into(),
+        },
+    ];
+    assert_eq!(visible_ids(&schema, &current), vec!["custom_server"]);
+}
+
+#[test]
+fn validate_skips_hidden_fields() {
+    let schema = server_form();
+    let validated = schema
+        .validate(&[FormResponse {
+            id: "endpoint".into(),
+            value: "ignored".into(),
+        }])
+        .expect("hidden required field should be skipped");
+    assert!(validated.get("endpoint").is_none());
+
+    let err = schema
+        .validate(&[FormResponse {
+            id: "custom_server".into(),
+            value: true.into(),
+        }])
+        .unwrap_err();
+    assert!(err.for_field("endpoint").is_some());
+}
+
+#[test]
+fn not_equals_condition() {
+    let cond = FieldCondition::not_equals("mode", "simple");
+    assert!(cond.matches(Some(&"advanced".into())));
+    assert!(cond.matches(None));
+    assert!(!cond.matches(Some(&"simple".into())));
+}
+
+#[test]
+fn builder_rejects_condition_cycles() {
+    let err = FormSchema::builder("Cycle")
+        .field(Field::text("a").visible_when(FieldCondition::equals("b", "x")))
+        .field(Field::text("b").visible_when(FieldCondition::equals("a", "x")))
+        .build()
+        .unwrap_err();
+    assert_eq!(err, FormSchemaError::ConditionCycle("a".into()));
+
+    let err = FormSchema::builder("Unknown")
+        .field(Field::text("a").visible_when(FieldCondition::equals("missing", "x")))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        FormSchemaError::UnknownConditionField {
+            field_id: "a".into(),
+            depends_on: "missing".into(),
+        }
+    );
+}
+
+#[test]
+fn field_condition_serialization_roundtrip() {
+    let field = Field::text("endpoint").visible_when(FieldCondition::equals("custom_server", true));
+    let json = serde_json::to_string(&field).unwrap();
+    let back: Field = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        back.visible_when,
+        Some(FieldCondition::Equals { ref field_id, value: FormValue::Bool(true) }) if field_id == "custom_server"
+    ));
+
+    // Fields serialized before conditions existed are always visible
+    let legacy =
+        r#"{"id":"a","label":"A","field_type":"Text","placeholder":null,"regex":null,"help":null}"#;
+    let field: Field = serde_json::from_str(legacy).unwrap();
+    assert!(field.visible_when.is_none());
+}
+
+fn localized_form() -> FormSchema {
+    FormSchema::builder("Settings")
+        .title_key("settings.title")
+        .description("Provider settings")
+        .field(
+            Field::text("name")
+                .label("Name")
+                .label_key("settings.name")
+                .help_key("settings.name.help"),
+        )
+        .field(
+            Field::select_options(
+                "quality",
+                vec![
+                    SelectOption::new("hq", "High").label_key("settings.quality.high"),
+                    SelectOption::new("lq", "Low").label_key("settings.quality.unknown"),
+                ],
+            )
+            .label("Quality"),
+        )
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn localize_resolves_keys_and_falls_back_to_literals() {
+    let resolver = |key: &str| match key {
+        "settings.title" => Some("Einstellungen".to_string()),
+        "settings.name" => Some("Name (de)".to_string()),
+        "settings.name.help" => Some("Hilfe".to_string()),
+        "settings.quality.high" => Some("Hoch".to_string()),
+        _ => None,
+    };
+    let localized = localized_form().localize(&resolver);
+
+    assert_eq!(localized.title, "Einstellungen");
+    assert_eq!(localized.description.as_deref(), Some("Provider settings"));
+    assert_eq!(localized.fields[0].label, "Name (de)");
+    assert_eq!(localized.fields[0].help.as_deref(), Some("Hilfe"));
+    assert_eq!(localized.fields[1].label, "Quality");
+    match &localized.fields[1].field_type {
+        FieldType::SelectV2(options) => {
+            assert_eq!(options[0].label, "Hoch");
+            assert_eq!(options[1].label, "Low");
+        }
+        other => panic!("Expected SelectV2, got {:?}", other),
+    }
+}
+
+#[test]
+fn localized_schema_round_trips() {
+    let schema = localized_form();
+    let json = serde_json::to_string(&schema).unwrap();
+    let back: FormSchema = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(back.title_key.as_deref(), Some("settings.title"));
+    assert_eq!(back.fields[0].label_key.as_deref(), Some("settings.name"));
+    assert!(matches!(&back.fields[1].field_type, FieldType::SelectV2(o) if o.len() == 2));
+
+    // Payloads without the key fields still deserialize
+    let legacy: FormSchema = serde_json::from_str(
+        r#"{"title":"Old","description":null,"fields":[{"id":"a","label":"A","field_type":"Text","placeholder":null,"regex":null,"help":null}]}"#,
+    )
+    .unwrap();
+    assert!(legacy.title_key.is_none());
+    assert!(legacy.fields[0].label_key.is_none());
+}
+
+#[test]
+fn select_v2_validates_option_values() {
+    let schema = localized_form();
+    let response = |value: &str| {
+        vec![FormResponse {
+            id: "quality".into(),
+            value: value.into(),
+        }]
+    };
+
+    assert!(schema.validate(&response("hq")).is_ok());
+    let err = schema.validate(&response("High")).unwrap_err();
+    assert!(err.for_field("quality").is_some());
+}
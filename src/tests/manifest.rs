@@ -0,0 +1,182 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    registry::{
+        RegistryError,
+        manifest::{ManifestError, ManifestGameProvider, ManifestInstallHandler, ProviderManifest},
+        model::ProviderSource,
+    },
+    runtime::context::ContextBuilder,
+    tests::dummy::DummyModProvider,
+    traits::{
+        game_provider::{GameIcon, GameInstallError, GameProvider},
+        mod_provider::ModProvider,
+    },
+};
+
+const MANIFEST: &str = r#"{
+    "games": [
+        {
+            "id": "Lethal-Company",
+            "display_name": "Lethal Company",
+            "short_name": "LC",
+            "icon": "icons/lc.png",
+            "mod_provider_id": "thunderstore:mods",
+            "external_id": "steam:1966720"
+        },
+        {
+            "id": "risk-of-rain-2",
+            "display_name": "Risk of Rain 2",
+            "short_name": "RoR2",
+            "icon": "icons/ror2.png",
+            "mod_provider_id": "thunderstore:mods"
+        }
+    ]
+}"#;
+
+fn resolve(id: &str) -> Option<Arc<dyn ModProvider + Send + Sync>> {
+    (id == "thunderstore:mods").then(|| DummyModProvider::new(id) as _)
+}
+
+#[test]
+fn register_games_from_manifest() {
+    let manifest: ProviderManifest = serde_json::from_str(MANIFEST).unwrap();
+    let mut b = ContextBuilder::new();
+    b.register_from_manifest(
+        manifest,
+        ProviderSource::plugin("thunderstore"),
+        &resolve,
+        None,
+    )
+    .unwrap();
+    let ctx = b.freeze();
+
+    let providers = ctx.list_mod_providers_detailed();
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers[0].id, "thunderstore:mods");
+    assert_eq!(providers[0].source, ProviderSource::plugin("thunderstore"));
+
+    let games = ctx.list_games_detailed();
+    let ids: Vec<&str> = games.iter().map(|g| g.id.as_str()).collect();
+    assert_eq!(ids, ["lethal-company", "risk-of-rain-2"]);
+    assert_eq!(games[0].metadata.display_name, "Lethal Company");
+    assert_eq!(games[1].metadata.short_name, "RoR2");
+    assert!(matches!(&games[0].metadata.icon, GameIcon::Path(p) if p == "icons/lc.png"));
+    assert_eq!(games[0].required_provider_ids, ["thunderstore:mods"]);
+    assert_eq!(
+        ctx.find_game_by_external_id("steam:1966720").as_deref(),
+        Some("lethal-company")
+    );
+}
+
+#[test]
+fn manifest_errors_point_at_the_entry() {
+    let mut manifest: ProviderManifest = serde_json::from_str(MANIFEST).unwrap();
+    manifest.games[1].mod_provider_id = "nexus:mods".into();
+    let mut duplicate = manifest.games[0].clone();
+    duplicate.id = "lethal-company".into();
+    manifest.games.push(duplicate);
+
+    let mut b = ContextBuilder::new();
+    let errors = b
+        .register_from_manifest(
+            manifest,
+            ProviderSource::plugin("thunderstore"),
+            &resolve,
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            ManifestError {
+                index: 1,
+                game_id: "risk-of-rain-2".into(),
+                error: RegistryError::NotFound("nexus:mods".into()),
+            },
+            ManifestError {
+                index: 2,
+                game_id: "lethal-company".into(),
+                error: RegistryError::GameAlreadyExists("lethal-company".into()),
+            },
+        ]
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "Manifest game #1 (risk-of-rain-2): Cannot find id nexus:mods"
+    );
+    // Nothing was registered
+    assert!(b.is_empty());
+}
+
+#[test]
+fn manifest_is_checked_before_registering_providers() {
+    let mut manifest: ProviderManifest = serde_json::from_str(MANIFEST).unwrap();
+    manifest.games[1].mod_provider_id = "old:mods".into();
+
+    let mut b = ContextBuilder::new();
+    b.register_mod_provider(
+        "mod:a",
+        DummyModProvider::new("mod:a"),
+        ProviderSource::Core,
+    )
+    .unwrap();
+    b.alias_provider("old:mods", "mod:a").unwrap();
+    let resolve_all = |id: &str| Some(DummyModProvider::new(id) as _);
+    let errors = b
+        .register_from_manifest(
+            manifest,
+            ProviderSource::plugin("thunderstore"),
+            &resolve_all,
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        [ManifestError {
+            index: 1,
+            game_id: "risk-of-rain-2".into(),
+            error: RegistryError::NotFound("old:mods".into()),
+        }]
+    );
+
+    // The provider of the first game wasn't registered either
+    let ctx = b.freeze();
+    assert!(ctx.get_mod_provider("thunderstore:mods").is_err());
+    assert!(ctx.list_games_detailed().is_empty());
+}
+
+#[test]
+fn manifest_games_delegate_installs() {
+    let manifest: ProviderManifest = serde_json::from_str(MANIFEST).unwrap();
+    let game = manifest.games[0].clone();
+    let source = ProviderSource::plugin("thunderstore");
+
+    let plain = ManifestGameProvider::new(game, source.clone());
+    assert!(matches!(
+        plain.install_mod(Path::new("mod.zip")),
+        Err(GameInstallError::Unsupported(_))
+    ));
+
+    let installed = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&installed);
+    let handler: ManifestInstallHandler = Arc::new(move |game_id, path| {
+        log.lock()
+            .unwrap()
+            .push(format!("{}:{}", game_id, path.display()));
+        Ok(())
+    });
+    let mut b = ContextBuilder::new();
+    b.register_from_manifest(manifest, source, &resolve, Some(handler))
+        .unwrap();
+    let ctx = b.freeze();
+    ctx.activate_game("lethal-company").unwrap();
+    assert!(
+        ctx.install_mod_for_active_game(Path::new("mod.zip"), false)
+            .unwrap()
+    );
+    assert_eq!(*installed.lock().unwrap(), ["Lethal-Company:mod.zip"]);
+}
@@ -1,5 +1,13 @@
+mod api;
 mod capabilities;
 mod context;
+#[cfg(feature = "default-services")]
+mod default_download_service;
+mod dependencies;
+mod discovery;
+mod downloads;
 mod dummy;
 mod form_schema;
+mod manifest;
 mod registry;
+mod settings;
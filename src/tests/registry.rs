@@ -1,6 +1,10 @@
 use crate::registry::{
     RegistryError,
-    id::{is_core_id, normalize_id},
+    id::{
+        IdConfig, get_local_name, get_namespace, is_core_id, is_in_namespace, is_plugin_id,
+        levenshtein, normalize_id, normalize_id_with_config, suggest_ids,
+    },
+    model::{PluginOrigin, ProviderSource},
 };
 
 #[test]
@@ -45,8 +49,245 @@ fn boundary_lengths() {
     assert!(normalize_id(&too_long).is_err());
 }
 
+/// The message of an `InvalidId` error
+fn invalid_id_message(raw: &str) -> String {
+    match normalize_id(raw) {
+        Err(RegistryError::InvalidId(message)) => message,
+        other => panic!("expected InvalidId for {raw:?}, got {other:?}"),
+    }
+}
+
+#[test]
+fn reject_accented_letters_with_candidate() {
+    let message = invalid_id_message("Éclair");
+    assert!(message.contains("'É' at position 0"), "{message}");
+    assert!(message.contains("try 'eclair'"), "{message}");
+
+    // Positions count chars, not bytes
+    let message = invalid_id_message("ns:café-Straße");
+    assert!(message.contains("'é' at position 6"), "{message}");
+    assert!(message.contains("try 'ns:cafe-strasse'"), "{message}");
+}
+
+#[test]
+fn reject_combining_characters() {
+    let message = invalid_id_message("cafe\u{301}");
+    assert!(message.contains("at position 4"), "{message}");
+    assert!(message.contains("try 'cafe'"), "{message}");
+}
+
+#[test]
+fn reject_non_ascii_lowercasing_to_ascii() {
+    // KELVIN SIGN lowercases to an ASCII `k`
+    let message = invalid_id_message("\u{212A}elvin");
+    assert!(message.contains("at position 0"), "{message}");
+    assert!(message.contains("try 'kelvin'"), "{message}");
+}
+
+#[test]
+fn reject_emoji() {
+    let message = invalid_id_message("mod 🚀");
+    assert!(message.contains("' ' at position 3"), "{message}");
+    assert!(message.contains("try 'mod-'"), "{message}");
+
+    let message = invalid_id_message("🚀");
+    assert!(message.contains("'🚀' at position 0"), "{message}");
+    assert!(message.contains("only a-z"), "{message}");
+}
+
+#[test]
+fn length_counts_chars() {
+    // 200 chars but 400 bytes, rejected for the characters rather than the length
+    let message = invalid_id_message(&"é".repeat(200));
+    assert!(message.contains("'é' at position 0"), "{message}");
+    let message = invalid_id_message(&"é".repeat(201));
+    assert!(message.contains("longer than 200 characters"), "{message}");
+}
+
+#[test]
+fn default_config_matches_normalize_id() {
+    let config = IdConfig::default();
+    for raw in [
+        "Hello.World",
+        "ns:my-mod",
+        "abc$def",
+        "Éclair",
+        &"a".repeat(201),
+    ] {
+        assert_eq!(
+            normalize_id_with_config(raw, &config).map_err(|e| e.to_string()),
+            normalize_id(raw).map_err(|e| e.to_string())
+        );
+    }
+}
+
+#[test]
+fn config_max_len() {
+    let config = IdConfig {
+        max_len: 8,
+        ..IdConfig::default()
+    };
+    assert_eq!(
+        normalize_id_with_config("ns:abcde", &config).unwrap(),
+        "ns:abcde"
+    );
+    let Err(RegistryError::InvalidId(message)) = normalize_id_with_config("ns:abcdef", &config)
+    else {
+        panic!("expected InvalidId");
+    };
+    assert!(message.contains("longer than 8 characters"), "{message}");
+}
+
+#[test]
+fn config_without_dots() {
+    let config = IdConfig {
+        allow_dots: false,
+        ..IdConfig::default()
+    };
+    assert_eq!(
+        normalize_id_with_config("My_Mod-2", &config).unwrap(),
+        "my_mod-2"
+    );
+    let Err(RegistryError::InvalidId(message)) = normalize_id_with_config("my.mod", &config) else {
+        panic!("expected InvalidId");
+    };
+    assert!(message.contains("'.' at position 2"), "{message}");
+    assert!(message.contains("try 'my_mod'"), "{message}");
+}
+
+#[test]
+fn config_without_hyphens() {
+    let config = IdConfig {
+        allow_hyphens: false,
+        ..IdConfig::default()
+    };
+    assert!(normalize_id_with_config("my-mod", &config).is_err());
+    let Err(RegistryError::InvalidId(message)) = normalize_id_with_config("mein Café", &config)
+    else {
+        panic!("expected InvalidId");
+    };
+    assert!(message.contains("try 'mein_cafe'"), "{message}");
+
+    let Err(RegistryError::InvalidId(message)) = normalize_id_with_config("🚀", &config) else {
+        panic!("expected InvalidId");
+    };
+    assert!(
+        message.contains("only a-z, 0-9, '.', '_' and one ':'"),
+        "{message}"
+    );
+}
+
 #[test]
 fn core_detection() {
     assert!(is_core_id("core:foo"));
     assert!(!is_core_id("corex:foo"));
 }
+
+#[test]
+fn plugin_detection() {
+    assert!(is_plugin_id("vmm:thunderstore"));
+    assert!(is_plugin_id("corex:foo"));
+    assert!(!is_plugin_id("core:foo"));
+    assert!(!is_plugin_id("thunderstore"));
+}
+
+#[test]
+fn namespace_and_local_name() {
+    assert_eq!(get_namespace("vmm:thunderstore"), Some("vmm"));
+    assert_eq!(get_namespace("core:foo"), Some("core"));
+    assert_eq!(get_namespace("thunderstore"), None);
+
+    assert_eq!(get_local_name("vmm:thunderstore"), "thunderstore");
+    assert_eq!(get_local_name("core:foo"), "foo");
+    assert_eq!(get_local_name("thunderstore"), "thunderstore");
+}
+
+#[test]
+fn levenshtein_distance() {
+    assert_eq!(levenshtein("", ""), 0);
+    assert_eq!(levenshtein("abc", ""), 3);
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("core:thunderstor", "core:thunderstore"), 1);
+}
+
+#[test]
+fn suggest_closest_ids() {
+    let known = [
+        "core:thunderstore",
+        "core:nexus",
+        "core:thunderstore-dev",
+        "plugin:modio",
+    ];
+    assert_eq!(
+        suggest_ids("core:thunderstor", known),
+        ["core:thunderstore", "core:thunderstore-dev"]
+    );
+    assert!(suggest_ids("something-else", known).is_empty());
+}
+
+#[test]
+fn not_found_with_suggestions() {
+    let err = RegistryError::not_found("core:nexsu".into(), ["core:nexus", "plugin:modio"]);
+    assert_eq!(
+        err,
+        RegistryError::NotFoundWithSuggestions {
+            id: "core:nexsu".into(),
+            suggestions: vec!["core:nexus".into()],
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "Cannot find id core:nexsu, did you mean core:nexus?"
+    );
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(serde_json::from_str::<RegistryError>(&json).unwrap(), err);
+
+    let err = RegistryError::not_found("unrelated".into(), ["core:nexus"]);
+    assert_eq!(err, RegistryError::NotFound("unrelated".into()));
+}
+
+#[test]
+fn provider_source_roundtrip() {
+    let source = ProviderSource::Plugin {
+        id: "thunderstore".into(),
+        version: Some("1.4.2".into()),
+        origin: PluginOrigin::Remote {
+            url: "https://example.com/plugin.zip".into(),
+        },
+    };
+    let json = serde_json::to_string(&source).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ProviderSource>(&json).unwrap(),
+        source
+    );
+    assert_eq!(
+        serde_json::from_str::<ProviderSource>(r#""Core""#).unwrap(),
+        ProviderSource::Core
+    );
+}
+
+#[test]
+fn provider_source_accepts_legacy_plugin_id() {
+    let source: ProviderSource = serde_json::from_str(r#"{"Plugin":"thunderstore"}"#).unwrap();
+    assert_eq!(source, ProviderSource::plugin("thunderstore"));
+
+    // Missing fields fall back to their defaults
+    let source: ProviderSource =
+        serde_json::from_str(r#"{"Plugin":{"id":"thunderstore","origin":"Bundled"}}"#).unwrap();
+    assert_eq!(
+        source,
+        ProviderSource::Plugin {
+            id: "thunderstore".into(),
+            version: None,
+            origin: PluginOrigin::Bundled,
+        }
+    );
+}
+
+#[test]
+fn namespace_detection() {
+    assert!(is_in_namespace("vmm:thunderstore", "vmm"));
+    assert!(!is_in_namespace("vmm.extra:thunderstore", "vmm"));
+    assert!(!is_in_namespace("vmm", "vmm"));
+    assert!(!is_in_namespace("thunderstore:vmm", "vmm"));
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use crate::{
+    registry::model::ProviderSource,
+    runtime::{
+        context::ContextBuilder,
+        settings::{InMemorySettingsStore, SettingsScope, SettingsStore},
+    },
+    tests::dummy::DummyModProvider,
+};
+
+fn two_provider_builder() -> ContextBuilder {
+    let mut b = ContextBuilder::new();
+    for id in ["mod:a", "mod:b"] {
+        b.register_mod_provider(id, DummyModProvider::new(id), ProviderSource::Core)
+            .unwrap();
+    }
+    b
+}
+
+#[test]
+fn same_key_in_different_scopes_is_separate() {
+    let ctx = two_provider_builder().freeze();
+    let settings = ctx.settings();
+    let a = || SettingsScope::Provider("mod:a".into());
+    let b = || SettingsScope::Provider("mod:b".into());
+
+    settings.set(a(), "mirror", "eu".into());
+    settings.set(b(), "mirror", "us".into());
+    settings.set(SettingsScope::Game("mod:a".into()), "mirror", "game".into());
+
+    assert_eq!(settings.get(a(), "mirror").as_deref(), Some("eu"));
+    assert_eq!(settings.get(b(), "mirror").as_deref(), Some("us"));
+    assert_eq!(
+        settings
+            .get(SettingsScope::Game("mod:a".into()), "mirror")
+            .as_deref(),
+        Some("game")
+    );
+
+    assert_eq!(settings.remove(a(), "mirror").as_deref(), Some("eu"));
+    assert_eq!(settings.get(a(), "mirror"), None);
+    assert_eq!(settings.get(b(), "mirror").as_deref(), Some("us"));
+    assert_eq!(settings.remove(a(), "mirror"), None);
+}
+
+#[test]
+fn keys_are_listed_per_scope() {
+    let store = InMemorySettingsStore::default();
+    let scope = || SettingsScope::Provider("mod:a".into());
+    store.set(scope(), "sort", "downloads".into());
+    store.set(scope(), "last_sync", "1700000000".into());
+    store.set(scope(), "sort", "name".into());
+    store.set(SettingsScope::Provider("mod:b".into()), "token", "x".into());
+
+    assert_eq!(store.keys(scope()), ["last_sync", "sort"]);
+    assert_eq!(store.get(scope(), "sort").as_deref(), Some("name"));
+    assert!(store.keys(SettingsScope::Game("game-a".into())).is_empty());
+}
+
+#[test]
+fn host_supplied_store_is_used() {
+    let store = Arc::new(InMemorySettingsStore::default());
+    store.set(
+        SettingsScope::Provider("mod:a".into()),
+        "mirror",
+        "saved".into(),
+    );
+
+    let mut b = two_provider_builder();
+    b.with_settings_store(store.clone());
+    let ctx = b.freeze();
+    assert_eq!(
+        ctx.settings()
+            .get(SettingsScope::Provider("mod:a".into()), "mirror")
+            .as_deref(),
+        Some("saved")
+    );
+
+    ctx.settings().set(
+        SettingsScope::Provider("mod:b".into()),
+        "mirror",
+        "new".into(),
+    );
+    assert_eq!(
+        store.keys(SettingsScope::Provider("mod:b".into())),
+        ["mirror"]
+    );
+}
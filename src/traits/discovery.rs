@@ -1,14 +1,43 @@
+use std::{cmp::Ordering, collections::HashMap};
+
 use serde::{Deserialize, Serialize};
 
+use crate::capabilities::builder::CapabilityError;
+
 /// The supported sort orders of VMM's discovery page
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum SortOrder {
     Relevance,
     Downloads,
     Views,
     Likes,
+    /// Requires providers to set `ModSummary::created_at`
     Newest,
+    /// Requires providers to set `ModSummary::last_updated`
     Updated,
+    /// Requires providers to set `ModSummary::rating_score`
+    Rating,
+    /// A provider specific sort mode (e.g. "featured"), the key is opaque to VMM.
+    /// Providers that don't recognize it should fall back to `Relevance`
+    Custom(String),
+}
+
+impl SortOrder {
+    /// Maps unrecognized `Custom` keys to `Relevance`, other orders are returned as-is
+    pub fn or_relevance(&self, known_custom: &[&str]) -> SortOrder {
+        match self {
+            SortOrder::Custom(key) if !known_custom.contains(&key.as_str()) => SortOrder::Relevance,
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum SortDirection {
+    Ascending,
+    Descending,
 }
 
 /// The query parameters for VMM's discovery page
@@ -26,6 +55,226 @@ pub struct DiscoveryQuery {
     pub tags: Option<Vec<String>>,
     /// The target sort mode
     pub sort: Option<SortOrder>,
+    /// The direction of `sort`, providers pick their natural direction when unset
+    #[serde(default)]
+    pub sort_direction: Option<SortDirection>,
+    /// The ID of the author to filter by
+    #[serde(default)]
+    pub author_id: Option<String>,
+    /// Only include mods rated at least this score (0.0..=5.0)
+    #[serde(default)]
+    pub min_rating: Option<f32>,
+    /// Leave out mods flagged as adult content
+    #[serde(default)]
+    pub exclude_nsfw: bool,
+    /// An opaque offset token from `PaginationMeta::next_cursor`, providers without cursor support ignore it
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Leave out mods with any of these tags
+    #[serde(default)]
+    pub exclude_tags: Option<Vec<String>>,
+    /// Only include mods with at least this many downloads
+    #[serde(default)]
+    pub min_downloads: Option<u32>,
+    /// Only include mods updated on or after this ISO 8601 date
+    #[serde(default)]
+    pub date_after: Option<String>,
+    /// Only include mods updated on or before this ISO 8601 date
+    #[serde(default)]
+    pub date_before: Option<String>,
+}
+
+impl DiscoveryQuery {
+    /// Starts building a query, see `DiscoveryQueryBuilder`
+    pub fn builder() -> DiscoveryQueryBuilder {
+        DiscoveryQueryBuilder::default()
+    }
+
+    /// Checks `exclude_tags`, `min_downloads` and the date range against a summary,
+    /// for providers which can't filter server-side.
+    ///
+    /// Dates are compared on `ModSummary::last_updated`, mods without one are kept.
+    pub fn matches(&self, summary: &ModSummary) -> bool {
+        let excluded = self
+            .exclude_tags
+            .as_ref()
+            .is_some_and(|tags| summary.tags.iter().any(|t| tags.contains(t)));
+        let too_few_downloads = self
+            .min_downloads
+            .is_some_and(|min| summary.downloads < min);
+
+        // ISO 8601 dates in the same format sort lexicographically, compare on the date part
+        fn date(d: &str) -> &str {
+            d.get(..10).unwrap_or(d)
+        }
+        let updated = summary.last_updated.as_deref().map(date);
+        let after = self.date_after.as_deref().map(date);
+        let before = self.date_before.as_deref().map(date);
+        let too_old = matches!((updated, after), (Some(u), Some(after)) if u < after);
+        let too_new = matches!((updated, before), (Some(u), Some(before)) if u > before);
+
+        !(excluded || too_few_downloads || too_old || too_new)
+    }
+
+    /// Checks `search` against a summary's name and descriptions, ignoring case.
+    /// Used by `Context::discover_mods` for providers without full-text search.
+    pub fn matches_search(&self, summary: &ModSummary) -> bool {
+        let Some(search) = self.search.as_deref().map(str::trim) else {
+            return true;
+        };
+        let search = search.to_lowercase();
+        [
+            &summary.name,
+            &summary.short_description,
+            &summary.description,
+        ]
+        .iter()
+        .any(|text| text.to_lowercase().contains(&search))
+    }
+}
+
+/// Fluent builder for `DiscoveryQuery`, only the game id is required
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryQueryBuilder {
+    game_id: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    search: Option<String>,
+    tags: Option<Vec<String>>,
+    sort: Option<SortOrder>,
+    sort_direction: Option<SortDirection>,
+    author_id: Option<String>,
+    min_rating: Option<f32>,
+    exclude_nsfw: bool,
+    cursor: Option<String>,
+    exclude_tags: Option<Vec<String>>,
+    min_downloads: Option<u32>,
+    date_after: Option<String>,
+    date_before: Option<String>,
+}
+
+impl DiscoveryQueryBuilder {
+    pub fn game_id(mut self, s: &str) -> Self {
+        self.game_id = Some(s.to_string());
+        self
+    }
+
+    pub fn page(mut self, n: u32) -> Self {
+        self.page = Some(n);
+        self
+    }
+
+    pub fn page_size(mut self, n: u32) -> Self {
+        self.page_size = Some(n);
+        self
+    }
+
+    pub fn search(mut self, s: &str) -> Self {
+        self.search = Some(s.to_string());
+        self
+    }
+
+    /// Adds a single tag to the applied filters
+    pub fn tag(mut self, t: &str) -> Self {
+        self.tags.get_or_insert_with(Vec::new).push(t.to_string());
+        self
+    }
+
+    /// Replaces the applied filters
+    pub fn tags(mut self, v: Vec<String>) -> Self {
+        self.tags = Some(v);
+        self
+    }
+
+    pub fn sort(mut self, s: SortOrder) -> Self {
+        self.sort = Some(s);
+        self
+    }
+
+    pub fn sort_direction(mut self, d: SortDirection) -> Self {
+        self.sort_direction = Some(d);
+        self
+    }
+
+    pub fn ascending(self) -> Self {
+        self.sort_direction(SortDirection::Ascending)
+    }
+
+    pub fn descending(self) -> Self {
+        self.sort_direction(SortDirection::Descending)
+    }
+
+    pub fn author_id(mut self, s: &str) -> Self {
+        self.author_id = Some(s.to_string());
+        self
+    }
+
+    pub fn min_rating(mut self, rating: f32) -> Self {
+        self.min_rating = Some(rating);
+        self
+    }
+
+    pub fn exclude_nsfw(mut self) -> Self {
+        self.exclude_nsfw = true;
+        self
+    }
+
+    pub fn cursor(mut self, cursor: &str) -> Self {
+        self.cursor = Some(cursor.to_string());
+        self
+    }
+
+    /// Adds a single tag to the excluded tags
+    pub fn exclude_tag(mut self, t: &str) -> Self {
+        self.exclude_tags
+            .get_or_insert_with(Vec::new)
+            .push(t.to_string());
+        self
+    }
+
+    pub fn min_downloads(mut self, n: u32) -> Self {
+        self.min_downloads = Some(n);
+        self
+    }
+
+    /// Only include mods updated on or after the given ISO 8601 date
+    pub fn updated_after(mut self, date: &str) -> Self {
+        self.date_after = Some(date.to_string());
+        self
+    }
+
+    /// Only include mods updated on or before the given ISO 8601 date
+    pub fn updated_before(mut self, date: &str) -> Self {
+        self.date_before = Some(date.to_string());
+        self
+    }
+
+    /// Builds the query, fails if the game id is missing or blank
+    pub fn build(self) -> Result<DiscoveryQuery, String> {
+        let game_id = match self.game_id {
+            Some(id) if !id.trim().is_empty() => id,
+            Some(_) => return Err("The game id cannot be empty".into()),
+            None => return Err("A game id is required".into()),
+        };
+
+        Ok(DiscoveryQuery {
+            game_id,
+            page: self.page,
+            page_size: self.page_size,
+            search: self.search,
+            tags: self.tags,
+            sort: self.sort,
+            sort_direction: self.sort_direction,
+            author_id: self.author_id,
+            min_rating: self.min_rating,
+            exclude_nsfw: self.exclude_nsfw,
+            cursor: self.cursor,
+            exclude_tags: self.exclude_tags,
+            min_downloads: self.min_downloads,
+            date_after: self.date_after,
+            date_before: self.date_before,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +284,31 @@ pub struct PaginationMeta {
     pub page_size: u32,
     pub total_pages: Option<u32>,
     pub total_items: Option<u32>,
+    /// The cursor of the next page, for providers with cursor based pagination
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+impl PaginationMeta {
+    /// Whether this is the last page, based on `total_pages` or `total_items`.
+    /// Returns `false` when neither is known.
+    pub fn is_last_page(&self) -> bool {
+        let by_pages = self.total_pages.is_some_and(|t| self.current >= t);
+        let by_items = self
+            .total_items
+            .is_some_and(|n| self.current as u64 * self.page_size as u64 >= n as u64);
+        by_pages || by_items
+    }
+
+    /// Pages are 1-based
+    pub fn is_first_page(&self) -> bool {
+        self.current == 1
+    }
+
+    /// The total number of results across all pages, if known
+    pub fn total_count(&self) -> Option<u64> {
+        self.total_items.map(u64::from)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +316,34 @@ pub struct PaginationMeta {
 pub struct Tag {
     pub id: String,
     pub name: String,
+    /// The id of the parent tag, for hierarchical categories
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// A hex color hint for the UI, e.g. `#FF5733`
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl Tag {
+    /// Creates a top-level tag without a color
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            parent_id: None,
+            color: None,
+        }
+    }
+}
+
+/// Groups tags by their `parent_id`, top-level tags are under `None`.
+/// Tags keep their relative order within each group.
+pub fn build_tag_tree(tags: &[Tag]) -> HashMap<Option<String>, Vec<&Tag>> {
+    let mut tree: HashMap<Option<String>, Vec<&Tag>> = HashMap::new();
+    for tag in tags {
+        tree.entry(tag.parent_id.clone()).or_default().push(tag);
+    }
+    tree
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +363,80 @@ pub struct DiscoveryResult {
     pub mods: Vec<ModSummary>,
 }
 
+impl DiscoveryResult {
+    /// Combines the results of two providers for the same query.
+    ///
+    /// Mods and tags are deduplicated by id (the first occurrence wins), totals are summed when
+    /// both are known and the provider ids are comma-joined. Cursors are provider specific, so the
+    /// merged result has none.
+    pub fn merge(mut self, other: DiscoveryResult) -> DiscoveryResult {
+        for summary in other.mods {
+            if !self.mods.iter().any(|m| m.id == summary.id) {
+                self.mods.push(summary);
+            }
+        }
+
+        let meta = &mut self.meta;
+        meta.provider_id = format!("{},{}", meta.provider_id, other.meta.provider_id);
+
+        let pagination = &mut meta.pagination;
+        let theirs = other.meta.pagination;
+        pagination.total_items = pagination
+            .total_items
+            .zip(theirs.total_items)
+            .map(|(a, b)| a + b);
+        pagination.total_pages = pagination
+            .total_pages
+            .zip(theirs.total_pages)
+            .map(|(a, b)| a.max(b));
+        pagination.next_cursor = None;
+
+        for tag in other.meta.applied_tags {
+            if !meta.applied_tags.contains(&tag) {
+                meta.applied_tags.push(tag);
+            }
+        }
+        meta.available_tags = match (meta.available_tags.take(), other.meta.available_tags) {
+            (Some(mut ours), Some(theirs)) => {
+                for tag in theirs {
+                    if !ours.iter().any(|t| t.id == tag.id) {
+                        ours.push(tag);
+                    }
+                }
+                Some(ours)
+            }
+            (ours, theirs) => ours.or(theirs),
+        };
+
+        self
+    }
+
+    /// Whether the provider has more results after this page
+    pub fn has_next_page(&self) -> bool {
+        let pagination = &self.meta.pagination;
+        pagination.next_cursor.is_some() || pagination.current < pagination.total_pages.unwrap_or(0)
+    }
+
+    /// Returns the query for the next page, preferring the cursor when the provider returned one.
+    /// Returns `None` on the last page.
+    pub fn next_query(&self, original: &DiscoveryQuery) -> Option<DiscoveryQuery> {
+        if !self.has_next_page() {
+            return None;
+        }
+
+        let pagination = &self.meta.pagination;
+        let mut query = original.clone();
+        match &pagination.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor.clone()),
+            None => {
+                query.page = Some(pagination.current + 1);
+                query.cursor = None;
+            }
+        }
+        Some(query)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct ModSummary {
@@ -75,6 +451,28 @@ pub struct ModSummary {
     pub tags: Vec<String>,
     pub user_name: String,
     pub user_avatar: String,
+    /// The provider's stable ID of the author, unlike `user_name`
+    #[serde(default)]
+    pub author_id: Option<String>,
+    #[serde(default)]
+    pub author_url: Option<String>,
+    /// ISO 8601 timestamp of the latest update
+    #[serde(default)]
+    pub last_updated: Option<String>,
+    /// ISO 8601 timestamp of the first upload
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Archive size in bytes
+    #[serde(default)]
+    pub file_size: Option<u64>,
+    /// Average rating, from 0.0 to 5.0
+    #[serde(default)]
+    pub rating_score: Option<f32>,
+    #[serde(default)]
+    pub rating_count: Option<u32>,
+    /// Whether the mod contains adult content
+    #[serde(default)]
+    pub is_nsfw: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,12 +483,145 @@ pub struct ModExtendedMetadata {
     pub version: String,
     pub installed: bool,
     pub description: String,
+    /// Release notes of the latest version
+    #[serde(default)]
+    pub changelog: Option<String>,
+    /// Previous releases, newest first
+    #[serde(default)]
+    pub version_history: Vec<ModVersion>,
+}
+
+/// A single released version of a mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ModVersion {
+    pub version: String,
+    /// ISO 8601 release date
+    pub release_date: Option<String>,
+    pub changelog: Option<String>,
+    pub download_url: Option<String>,
+    /// Archive size in bytes
+    pub file_size: Option<u64>,
+}
+
+/// The kind of a file hosted alongside a mod
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ModFileCategory {
+    Main,
+    Optional,
+    Patch,
+    /// Files of a previous version
+    Old,
+    Other(String),
+}
+
+/// A single downloadable file of a mod, see `HasMultipleFiles`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ModFileInfo {
+    pub file_id: String,
+    pub name: String,
+    /// Size in bytes
+    pub size: Option<u64>,
+    pub category: ModFileCategory,
+    pub version: Option<String>,
+    /// ISO 8601 upload date
+    pub uploaded_at: Option<String>,
+}
+
+/// A mod required (or recommended) by another mod, see `ResolvesDependencies`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ModDependency {
+    pub mod_id: String,
+    /// A provider specific version requirement, e.g. `>=1.2`
+    pub version_req: Option<String>,
+    pub optional: bool,
+}
+
+/// A mod that is currently installed, as known to the runtime
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct InstalledMod {
+    pub mod_id: String,
+    pub installed_version: String,
+}
+
+/// A newer version being available for an installed mod
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct UpdateInfo {
+    pub mod_id: String,
+    pub current_version: String,
+    pub latest_version: String,
+    pub release_notes: Option<String>,
+}
+
+/// The profile of the user authenticated with a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct UserProfile {
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub profile_url: Option<String>,
+}
+
+/// Compares two version strings segment by segment (e.g. `1.10.0` > `1.9.2`, `1.0` == `1.0.0`).
+///
+/// Numeric segments are compared as numbers and rank below other segments, which are compared
+/// as strings. Like in semver, a pre-release (`1.0.0-beta.2`) ranks below its release and build
+/// metadata (`+build.5`) is ignored.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn parts(v: &str) -> (Vec<&str>, Option<Vec<&str>>) {
+        let v = v.trim().trim_start_matches(['v', 'V']);
+        let v = v.split_once('+').map_or(v, |(v, _)| v);
+        match v.split_once('-') {
+            Some((release, pre)) => (release.split('.').collect(), Some(pre.split('.').collect())),
+            None => (v.split('.').collect(), None),
+        }
+    }
+    fn compare_segments(l: &str, r: &str) -> Ordering {
+        match (l.parse::<u64>(), r.parse::<u64>()) {
+            (Ok(l), Ok(r)) => l.cmp(&r),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => l.cmp(r),
+        }
+    }
+    let ((a, a_pre), (b, b_pre)) = (parts(a), parts(b));
+
+    for i in 0..a.len().max(b.len()) {
+        let l = a.get(i).copied().unwrap_or("0");
+        let r = b.get(i).copied().unwrap_or("0");
+        let ord = compare_segments(l, r);
+        if ord.is_ne() {
+            return ord;
+        }
+    }
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        // Unlike release segments, a longer pre-release ranks higher (`beta` < `beta.1`)
+        (Some(a), Some(b)) => a
+            .iter()
+            .zip(&b)
+            .map(|(l, r)| compare_segments(l, r))
+            .find(|ord| ord.is_ne())
+            .unwrap_or(a.len().cmp(&b.len())),
+    }
 }
 
 #[derive(Debug, thiserror::Error, Clone, Serialize, Deserialize)]
 pub enum DiscoveryError {
     #[error("Network error: {0}")]
     Network(String),
+    /// The provider rejected the request (HTTP 429), retry after the given delay if known
+    #[error("Rate limited by the provider")]
+    RateLimit { retry_after_secs: Option<u64> },
+    #[error("Request timed out after {elapsed_ms}ms")]
+    Timeout { elapsed_ms: u64 },
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
     #[error("The required provider is unavailable")]
@@ -98,3 +629,14 @@ pub enum DiscoveryError {
     #[error("Internal error: {0}")]
     Internal(String),
 }
+
+impl From<CapabilityError> for DiscoveryError {
+    fn from(value: CapabilityError) -> Self {
+        match value {
+            CapabilityError::ProviderDropped => DiscoveryError::ProviderUnavailable,
+            CapabilityError::Network(e) => DiscoveryError::Network(e),
+            CapabilityError::Timeout(elapsed_ms) => DiscoveryError::Timeout { elapsed_ms },
+            e @ CapabilityError::DuplicateCapability(_) => DiscoveryError::Internal(e.to_string()),
+        }
+    }
+}
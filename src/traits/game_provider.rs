@@ -1,21 +1,85 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Child,
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
 
-use crate::{registry::model::ProviderSource, traits::provider::Provider};
+use crate::{
+    capabilities::builder::CapabilityError, registry::model::ProviderSource,
+    traits::provider::Provider,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub enum GameIcon {
     Path(String),
+    /// An HTTP(S) url
+    Url(String),
+    /// Raw image data, e.g. embedded in a plugin
+    Bytes(Vec<u8>),
+}
+
+impl GameIcon {
+    /// Returns a url to the icon, paths are resolved against `base_url`.
+    /// `Bytes` icons have no url.
+    pub fn to_url(&self, base_url: &str) -> Option<String> {
+        match self {
+            GameIcon::Url(url) => Some(url.clone()),
+            GameIcon::Path(path) => Some(format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            )),
+            GameIcon::Bytes(_) => None,
+        }
+    }
+}
+
+/// The result of `GameProvider::verify_game_files`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct GameVerifyReport {
+    pub total_files: usize,
+    pub missing: Vec<PathBuf>,
+    pub corrupted: Vec<PathBuf>,
+}
+
+impl GameVerifyReport {
+    pub fn is_healthy(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum GameInstallError {
     #[error("Mod archive is invalid or corrupted")]
     InvalidArchive,
+    /// Carries the verification report when the missing files are known
     #[error("Required game files are missing, is it installed?")]
-    MissingGameFiles,
+    MissingGameFiles(Option<GameVerifyReport>),
+    #[error("Mod {0} is not installed")]
+    ModNotFound(String),
+    #[error("Mod version {mod_version} is not compatible with game version {game_version}")]
+    IncompatibleVersion {
+        mod_version: String,
+        game_version: String,
+    },
+    #[error("Mod {mod_id} requires {dependency}, which is not installed")]
+    DependencyMissing { mod_id: String, dependency: String },
+    #[error("Invalid load order: {0}")]
+    LoadOrderConflict(String),
+    #[error("Mod {mod_id} is already installed (version {installed_version})")]
+    AlreadyInstalled {
+        mod_id: String,
+        installed_version: String,
+    },
+    /// The game provider can't perform the operation, e.g. a game declared in a manifest
+    #[error("Not supported: {0}")]
+    Unsupported(String),
     #[error("Filesystem error: {0}")]
     IO(#[from] std::io::Error),
     #[error("Provider error: {message}")]
@@ -27,6 +91,81 @@ pub enum GameInstallError {
     },
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum ModUninstallError {
+    #[error("Mod {0} is not installed")]
+    NotFound(String),
+    #[error("Filesystem error: {0}")]
+    IO(#[from] std::io::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GameLaunchError {
+    #[error("The game is not installed")]
+    NotInstalled,
+    #[error("The game is already running")]
+    AlreadyRunning,
+    #[error("The provider was dropped before the game could be launched.")]
+    ProviderDropped,
+    #[error("Failed to start the game: {0}")]
+    IO(#[from] std::io::Error),
+}
+
+impl From<CapabilityError> for GameLaunchError {
+    fn from(value: CapabilityError) -> Self {
+        match value {
+            CapabilityError::ProviderDropped => GameLaunchError::ProviderDropped,
+            e @ (CapabilityError::Network(_) | CapabilityError::DuplicateCapability(_)) => {
+                GameLaunchError::IO(io::Error::other(e))
+            }
+            e @ CapabilityError::Timeout(_) => {
+                GameLaunchError::IO(io::Error::new(io::ErrorKind::TimedOut, e))
+            }
+        }
+    }
+}
+
+/// A running game process started by `GameProvider::launch_game`
+#[derive(Debug)]
+pub struct GameLaunchHandle {
+    pub process_id: u32,
+    child: Mutex<Option<Child>>,
+}
+
+impl GameLaunchHandle {
+    /// Wraps a process spawned by the provider, which can later be killed
+    pub fn from_child(child: Child) -> Self {
+        Self {
+            process_id: child.id(),
+            child: Mutex::new(Some(child)),
+        }
+    }
+
+    /// For games started through a launcher (e.g. Steam), which VMM doesn't own
+    pub fn from_pid(process_id: u32) -> Self {
+        Self {
+            process_id,
+            child: Mutex::new(None),
+        }
+    }
+
+    /// Kills the game, only supported for handles created with `from_child`
+    pub fn kill(&self) -> Result<(), io::Error> {
+        match self
+            .child
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            Some(child) => child.kill(),
+            None => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "The game process is not owned by VMM",
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct GameMetadata {
@@ -35,16 +174,101 @@ pub struct GameMetadata {
     pub short_name: String,
     pub icon: GameIcon,
     pub provider_source: ProviderSource,
+    /// The installed game version, filled from `GameProvider::get_game_version` if left empty
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub executable_path: Option<PathBuf>,
+    /// The game's root folder, filled from `GameProvider::game_root_path` if left empty
+    #[serde(default)]
+    pub install_path: Option<PathBuf>,
+}
+
+/// A mod installed into a game, as reported by `GameProvider::list_installed_mods`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct InstalledModInfo {
+    pub mod_id: String,
+    pub version: Option<String>,
+    pub install_path: PathBuf,
+    pub enabled: bool,
+    pub install_time: Option<SystemTime>,
 }
 
 #[async_trait]
 pub trait GameProvider: Provider + Send + Sync {
-    #[deprecated(since = "0.2.0", note = "Use id() instead")]
-    fn game_id(&self) -> &str {
-        self.id()
-    }
+    /// The per-instance id of the game, used as its registry key.
+    /// Unlike `Provider::id()` this differs between instances of the same provider type.
+    fn game_id(&self) -> &str;
     fn mod_provider_id(&self) -> &str;
+    /// Every mod provider the game pulls mods from, the first one is the primary provider.
+    /// Defaults to `mod_provider_id()`
+    fn mod_provider_ids(&self) -> Vec<&str> {
+        vec![self.mod_provider_id()]
+    }
+    /// Capability ids the primary mod provider has to have, checked by
+    /// `ContextBuilder::register_game_provider`. Games registered with
+    /// `register_game_provider_lenient` get a warning from `freeze_validated` instead.
+    fn required_mod_provider_capabilities(&self) -> &[&'static str] {
+        &[]
+    }
     fn metadata(&self) -> GameMetadata;
     fn get_external_id(&self) -> &str;
     fn install_mod(&self, path: &Path) -> Result<(), GameInstallError>;
+
+    /// Installs a mod archive over an installed version of the same mod, used by
+    /// `Context::install_mod_for_active_game` with `force`. Defaults to `install_mod`, providers
+    /// returning `GameInstallError::AlreadyInstalled` from it should replace the old files here.
+    fn reinstall_mod(&self, path: &Path) -> Result<(), GameInstallError> {
+        self.install_mod(path)
+    }
+
+    /// Lists the mods currently installed into the game
+    fn list_installed_mods(&self) -> Result<Vec<InstalledModInfo>, GameInstallError> {
+        Ok(vec![])
+    }
+
+    /// Re-enables a mod previously disabled with `disable_mod`
+    #[allow(unused_variables)]
+    fn enable_mod(&self, mod_id: &str) -> Result<(), GameInstallError> {
+        Ok(())
+    }
+
+    /// Disables a mod without uninstalling it
+    #[allow(unused_variables)]
+    fn disable_mod(&self, mod_id: &str) -> Result<(), GameInstallError> {
+        Ok(())
+    }
+
+    /// Returns the ids of the installed mods in the order the game loads them
+    fn get_load_order(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Reorders the installed mods, mods later in the list win conflicts
+    #[allow(unused_variables)]
+    fn set_load_order(&self, order: Vec<String>) -> Result<(), GameInstallError> {
+        Ok(())
+    }
+
+    /// The folder the game is installed in
+    fn game_root_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// The installed game version, e.g. parsed from the executable or a version file
+    fn get_game_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Checks the game files are intact, e.g. before blaming a mod for a crash
+    async fn verify_game_files(&self) -> Result<GameVerifyReport, GameInstallError> {
+        Ok(GameVerifyReport::default())
+    }
+
+    /// Launches the game with the given arguments
+    #[allow(unused_variables)]
+    async fn launch_game(&self, args: Vec<String>) -> Result<GameLaunchHandle, GameLaunchError> {
+        Err(GameLaunchError::NotInstalled)
+    }
 }
@@ -1,9 +1,12 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
 
 use crate::traits::discovery::{
-    DiscoveryError, DiscoveryQuery, DiscoveryResult, ModExtendedMetadata, ModSummary,
+    DiscoveryError, DiscoveryQuery, DiscoveryResult, InstalledMod, ModExtendedMetadata, ModSummary,
+    ModVersion, UpdateInfo, UserProfile, compare_versions,
 };
 use crate::traits::provider::Provider;
 
@@ -13,17 +16,106 @@ use crate::traits::provider::Provider;
 pub struct ModProviderFeatures {
     pub supports_endorsements: bool,
     pub requires_api_token: bool,
+    /// Superseded by the `HasMultipleFiles` capability
     pub mod_multi_file: bool,
 }
 
+/// How far a running download got, see `ModDownloadResult::Progress`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct DownloadProgress {
+    pub bytes_downloaded: u64,
+    /// `None` when the size is unknown, e.g. the server sent no Content-Length
+    pub total_bytes: Option<u64>,
+    /// `None` if the download service doesn't measure it
+    pub bytes_per_second: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// Progress without a measured speed
+    pub fn new(bytes_downloaded: u64, total_bytes: Option<u64>) -> Self {
+        Self {
+            bytes_downloaded,
+            total_bytes,
+            bytes_per_second: None,
+        }
+    }
+
+    /// Whole percent done, at most 100. `None` if the size is unknown.
+    pub fn percent(&self) -> Option<u8> {
+        let total = self.total_bytes?;
+        if total == 0 {
+            return Some(100);
+        }
+        let percent = (self.bytes_downloaded as u128 * 100 / total as u128).min(100);
+        Some(percent as u8)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum ModDownloadResult {
     Failed(String),
+    /// Note: Providers can't always compute an accurate percentage, this will be removed in a future release.
+    #[deprecated(since = "0.3.0", note = "Use `Progress` with byte counts instead")]
     InProgress(u8),
+    /// The download is running
+    Progress(DownloadProgress),
     Completed(PathBuf),
     Cancelled,
     CannotComplete(String),
 }
 
+impl ModDownloadResult {
+    /// Computes the transfer speed in bytes per second between two `Progress` updates.
+    ///
+    /// Returns `None` if either update isn't `Progress`, no time has elapsed or the byte count went backwards.
+    pub fn speed_bps(current: &Self, previous: &Self, elapsed: Duration) -> Option<f64> {
+        let (ModDownloadResult::Progress(now), ModDownloadResult::Progress(before)) =
+            (current, previous)
+        else {
+            return None;
+        };
+        let (now, before) = (now.bytes_downloaded, before.bytes_downloaded);
+
+        let secs = elapsed.as_secs_f64();
+        if secs <= 0.0 || now < before {
+            return None;
+        }
+        Some((now - before) as f64 / secs)
+    }
+
+    /// Estimates the time left for the download, based on the speed between two `Progress` updates.
+    ///
+    /// Returns `None` if the speed can't be computed, is zero, or the total size is unknown.
+    pub fn estimated_remaining(
+        current: &Self,
+        previous: &Self,
+        elapsed: Duration,
+    ) -> Option<Duration> {
+        let ModDownloadResult::Progress(DownloadProgress {
+            bytes_downloaded,
+            total_bytes: Some(total),
+            ..
+        }) = current
+        else {
+            return None;
+        };
+
+        let speed = Self::speed_bps(current, previous, elapsed)?;
+        if speed <= 0.0 {
+            return None;
+        }
+        let remaining = total.saturating_sub(*bytes_downloaded) as f64;
+        Some(Duration::from_secs_f64(remaining / speed))
+    }
+}
+
+impl From<DownloadProgress> for ModDownloadResult {
+    fn from(progress: DownloadProgress) -> Self {
+        ModDownloadResult::Progress(progress)
+    }
+}
+
 #[async_trait]
 pub trait ModProvider: Provider + Send + Sync {
     async fn download_mod(&self, mod_id: String) -> ModDownloadResult;
@@ -37,6 +129,63 @@ pub trait ModProvider: Provider + Send + Sync {
 
     async fn get_extended_mod(&self, mod_id: &str) -> ModExtendedMetadata;
 
+    /// Returns the version history of a mod, newest first.
+    #[allow(unused_variables)]
+    async fn get_mod_versions(&self, mod_id: &str) -> Result<Vec<ModVersion>, DiscoveryError> {
+        Err(DiscoveryError::Internal("Not implemented".into()))
+    }
+
+    /// Resolves the download URL of a mod without downloading it, `None` resolves the latest version.
+    ///
+    /// This lets a `DownloadService` own the transfer while the provider only supplies the metadata.
+    #[allow(unused_variables)]
+    async fn get_download_url(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+    ) -> Result<String, DiscoveryError> {
+        Err(DiscoveryError::Internal("Not implemented".into()))
+    }
+
+    /// Returns the profile of the authenticated user, for providers requiring an API key.
+    async fn get_user_profile(&self) -> Result<UserProfile, DiscoveryError> {
+        Err(DiscoveryError::Internal("Not supported".into()))
+    }
+
+    /// Checks which of the installed mods have a newer version available.
+    ///
+    /// The default calls `get_mod_versions` for every mod concurrently, override this if the
+    /// platform offers a batch endpoint. Mods whose versions can't be fetched, e.g. ones removed
+    /// from the platform, are left out.
+    async fn check_for_updates(
+        &self,
+        installed: &[InstalledMod],
+    ) -> Result<Vec<UpdateInfo>, DiscoveryError> {
+        let histories = join_all(installed.iter().map(|m| self.get_mod_versions(&m.mod_id))).await;
+
+        let mut updates = Vec::new();
+        for (installed, versions) in installed.iter().zip(histories) {
+            let Ok(versions) = versions else {
+                continue;
+            };
+            let latest = versions
+                .into_iter()
+                .max_by(|a, b| compare_versions(&a.version, &b.version));
+
+            if let Some(latest) = latest
+                && compare_versions(&latest.version, &installed.installed_version).is_gt()
+            {
+                updates.push(UpdateInfo {
+                    mod_id: installed.mod_id.clone(),
+                    current_version: installed.installed_version.clone(),
+                    latest_version: latest.version,
+                    release_notes: latest.changelog,
+                });
+            }
+        }
+        Ok(updates)
+    }
+
     #[deprecated(since = "0.2.0", note = "Use capabilities instead")]
     #[allow(deprecated)]
     fn configure(&self) -> &ModProviderFeatures {
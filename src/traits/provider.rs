@@ -1,8 +1,43 @@
-use crate::capabilities::base::{Capability, CapabilityRef};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
+use crate::capabilities::base::{Capability, CapabilityDescriptor, CapabilityRef};
+
+/// Result of `Provider::health_check`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub enum ProviderHealthStatus {
+    Healthy,
+    /// Working, but e.g. slow or missing some features
+    Degraded(String),
+    Unavailable(String),
+}
+
+/// A problem found by `Provider::validate`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProviderValidationError {
+    pub message: String,
+    /// Id of the capability the problem belongs to, if any
+    pub capability: Option<String>,
+}
+
+#[async_trait]
 pub trait Provider: Send + Sync {
+    /// For mod providers this has to match the id they are registered with,
+    /// see `ContextBuilder::validate`
     fn id(&self) -> &'static str;
 
+    /// Human readable name, e.g. for dropdowns and error reports
+    fn display_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// The provider's version, e.g. that of its plugin, shown in diagnostics
+    fn version(&self) -> Option<&str> {
+        None
+    }
+
     /// A list of capabilities that providers have.
     fn capabilities(&self) -> &[CapabilityRef];
 
@@ -14,6 +49,29 @@ pub trait Provider: Send + Sync {
             .find(|o| o.id() == id)
     }
 
+    fn has_capability(&self, id: &str) -> bool {
+        self.find_capability(id).is_some()
+    }
+
+    /// Checks the provider's setup, called on registration in debug builds
+    fn validate(&self) -> Result<(), Vec<ProviderValidationError>> {
+        Ok(())
+    }
+
+    /// Describes every capability, in the order of `capabilities()`
+    fn describe_capabilities(&self) -> Vec<CapabilityDescriptor> {
+        self.capabilities().iter().map(|c| c.descriptor()).collect()
+    }
+
+    /// Called by `Context::shutdown` once the tracked tasks stopped, e.g. to flush caches or
+    /// stop background work the provider started itself
+    fn on_shutdown(&self) {}
+
+    /// Checks whether the provider is in working order, e.g. by pinging its API
+    async fn health_check(&self) -> ProviderHealthStatus {
+        ProviderHealthStatus::Healthy
+    }
+
     /// Helper to get a concrete type
     fn get<T: Capability + 'static>(&self) -> Option<&T>
     where
@@ -23,4 +81,15 @@ pub trait Provider: Send + Sync {
             .iter()
             .find_map(|o| o.as_ref().as_any().downcast_ref::<T>())
     }
+
+    /// Every capability of a concrete type, in the order of `capabilities()`
+    fn capabilities_of_type<T: Capability + 'static>(&self) -> Vec<&T>
+    where
+        Self: Sized,
+    {
+        self.capabilities()
+            .iter()
+            .filter_map(|o| o.as_ref().as_any().downcast_ref::<T>())
+            .collect()
+    }
 }